@@ -4,13 +4,28 @@
 //! This helps find mutations that are more likely to catch weak tests.
 
 use crate::cli::AiProvider;
-use crate::mutation::{Mutation, MutationOperator, SourceLocation};
+use crate::mutation::{Mutation, MutationOperator};
+use crate::parser::parse_dart;
 use anyhow::{Context, Result};
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tree_sitter::Tree;
+
+/// Directory, relative to the project, holding cached AI suggestions
+pub const AI_CACHE_DIR: &str = ".dart_mutant_ai_cache";
+
+const DEFAULT_MAX_RETRIES: u32 = 3;
+
+/// Longest wait between retries, whatever `Retry-After` asks for
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(60);
+
+const ANTHROPIC_MODEL: &str = "claude-sonnet-4-20250514";
+const OPENAI_MODEL: &str = "gpt-4-turbo-preview";
+const GEMINI_MODEL: &str = "gemini-1.5-pro";
 
 /// A single mutation suggestion from AI
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MutationSuggestion {
     pub line: usize,
     pub column: usize,
@@ -20,6 +35,14 @@ pub struct MutationSuggestion {
     pub confidence: f64,
 }
 
+/// Where an Azure OpenAI deployment lives
+#[derive(Debug, Clone, Default)]
+pub struct AzureConfig {
+    pub endpoint: Option<String>,
+    pub deployment: Option<String>,
+    pub api_version: String,
+}
+
 /// AI-powered mutation suggester
 pub struct AiMutationSuggester {
     provider: AiProvider,
@@ -27,6 +50,10 @@ pub struct AiMutationSuggester {
     ollama_url: String,
     ollama_model: String,
     max_per_file: usize,
+    cache_dir: Option<PathBuf>,
+    max_retries: u32,
+    prompt_template: Option<String>,
+    azure: AzureConfig,
 }
 
 impl AiMutationSuggester {
@@ -37,6 +64,7 @@ impl AiMutationSuggester {
         ollama_url: String,
         ollama_model: String,
         max_per_file: usize,
+        cache_dir: Option<PathBuf>,
     ) -> Self {
         Self {
             provider,
@@ -44,88 +72,139 @@ impl AiMutationSuggester {
             ollama_url,
             ollama_model,
             max_per_file,
+            cache_dir,
+            max_retries: DEFAULT_MAX_RETRIES,
+            prompt_template: None,
+            azure: AzureConfig::default(),
         }
     }
 
+    /// Endpoint and deployment for `--ai azure-openai`
+    pub fn with_azure(mut self, azure: AzureConfig) -> Self {
+        self.azure = azure;
+        self
+    }
+
+    /// Use a custom prompt (see [`load_prompt_template`]) instead of the
+    /// built-in one
+    pub fn with_prompt_template(mut self, template: Option<String>) -> Self {
+        self.prompt_template = template;
+        self
+    }
+
+    /// Retry rate-limited (429) and server error (5xx) responses up to
+    /// `max_retries` times
+    pub const fn with_max_retries(mut self, max_retries: u32) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
     /// Suggest high-value mutations for a Dart file
     pub async fn suggest_mutations(&self, file_path: &Path, source: &str) -> Result<Vec<Mutation>> {
-        let suggestions = match self.provider {
-            AiProvider::Anthropic => self.suggest_with_anthropic(source).await?,
-            AiProvider::OpenAI => self.suggest_with_openai(source).await?,
-            AiProvider::Ollama => self.suggest_with_ollama(source).await?,
-            AiProvider::None => return Ok(vec![]),
+        let cache_path = self.cache_path(source);
+        let cached = cache_path
+            .as_deref()
+            .and_then(|path| std::fs::read_to_string(path).ok())
+            .and_then(|json| serde_json::from_str::<Vec<MutationSuggestion>>(&json).ok());
+
+        let suggestions = if let Some(suggestions) = cached {
+            tracing::debug!("Using cached AI suggestions for {}", file_path.display());
+            suggestions
+        } else {
+            let suggestions = match self.provider {
+                AiProvider::Anthropic => self.suggest_with_anthropic(source).await?,
+                AiProvider::OpenAI => self.suggest_with_openai(source).await?,
+                AiProvider::AzureOpenAI => self.suggest_with_azure(source).await?,
+                AiProvider::Gemini => self.suggest_with_gemini(source).await?,
+                AiProvider::Ollama => self.suggest_with_ollama(source).await?,
+                AiProvider::None => return Ok(vec![]),
+            };
+            // An empty answer is usually an API error, so it isn't cached
+            if let Some(path) = cache_path.filter(|_| !suggestions.is_empty()) {
+                if let Err(e) = store_suggestions(&path, &suggestions) {
+                    tracing::warn!("Failed to cache AI suggestions: {e:#}");
+                }
+            }
+            suggestions
         };
 
-        // Convert suggestions to mutations
+        // Convert suggestions to mutations, dropping any the AST doesn't support
+        let tree = parse_dart(source)?;
         let mutations: Vec<Mutation> = suggestions
             .into_iter()
             .take(self.max_per_file)
             .filter_map(|s| self.suggestion_to_mutation(file_path, source, s))
+            .filter(|m| match check_against_ast(source, &tree, m) {
+                Ok(()) => true,
+                Err(reason) => {
+                    tracing::debug!(
+                        "Rejected AI suggestion at {}:{}:{}: {reason}",
+                        file_path.display(),
+                        m.location.start_line,
+                        m.location.start_col
+                    );
+                    false
+                }
+            })
             .collect();
 
         Ok(mutations)
     }
 
+    /// Model name sent to the provider
+    fn model(&self) -> &str {
+        match self.provider {
+            AiProvider::Anthropic => ANTHROPIC_MODEL,
+            AiProvider::OpenAI => OPENAI_MODEL,
+            AiProvider::AzureOpenAI => self.azure.deployment.as_deref().unwrap_or_default(),
+            AiProvider::Gemini => GEMINI_MODEL,
+            AiProvider::Ollama => &self.ollama_model,
+            AiProvider::None => "",
+        }
+    }
+
+    /// Cache file for a source file's suggestions, keyed by everything that
+    /// shapes the answer: provider, model and the full prompt
+    fn cache_path(&self, source: &str) -> Option<PathBuf> {
+        let dir = self.cache_dir.as_ref()?;
+        let key = md5::compute(format!(
+            "{:?}\n{}\n{}",
+            self.provider,
+            self.model(),
+            self.build_prompt(source)
+        ));
+        Some(dir.join(format!("{key:x}.json")))
+    }
+
     fn suggestion_to_mutation(
         &self,
         file_path: &Path,
         source: &str,
         suggestion: MutationSuggestion,
     ) -> Option<Mutation> {
-        // Find the byte offset for the given line and column
-        let lines: Vec<&str> = source.lines().collect();
-        if suggestion.line == 0 || suggestion.line > lines.len() {
+        let byte_start = byte_offset(source, suggestion.line, suggestion.column)?;
+        let byte_end = byte_start + suggestion.original.len();
+        if source.get(byte_start..byte_end) != Some(suggestion.original.as_str()) {
             return None;
         }
-
-        let mut byte_start = 0;
-        for (i, line) in lines.iter().enumerate() {
-            if i + 1 == suggestion.line {
-                // Find the column within this line
-                let col_offset = suggestion.column.saturating_sub(1);
-                if col_offset < line.len() {
-                    byte_start += col_offset;
-
-                    // Find where the original text ends
-                    let remaining = &source[byte_start..];
-                    if remaining.starts_with(&suggestion.original) {
-                        let byte_end = byte_start + suggestion.original.len();
-
-                        return Some(Mutation {
-                            id: format!(
-                                "ai-{:x}",
-                                md5::compute(format!(
-                                    "{}:{}:{}",
-                                    file_path.display(),
-                                    suggestion.line,
-                                    suggestion.original
-                                ))
-                            ),
-                            location: SourceLocation {
-                                file: file_path.to_path_buf(),
-                                start_line: suggestion.line,
-                                start_col: suggestion.column,
-                                end_line: suggestion.line,
-                                end_col: suggestion.column + suggestion.original.len(),
-                                byte_start,
-                                byte_end,
-                            },
-                            operator: MutationOperator::AiSuggested,
-                            original: suggestion.original,
-                            mutated: suggestion.mutated.clone(),
-                            description: format!("AI: {}", suggestion.reason),
-                            replacements: vec![suggestion.mutated],
-                            ai_suggested: true,
-                            ai_confidence: Some(suggestion.confidence),
-                        });
-                    }
-                }
-                break;
-            }
-            byte_start += line.len() + 1; // +1 for newline
-        }
-
-        None
+        // Columns are stored as bytes, like the parser's, so a suggestion
+        // matching a parser mutant gets its id and the duplicate is dropped
+        let line_start = source[..byte_start].rfind('\n').map_or(0, |i| i + 1);
+
+        let mut mutation = Mutation::new(
+            file_path.to_path_buf(),
+            byte_start,
+            byte_end,
+            suggestion.line,
+            byte_start - line_start + 1,
+            suggestion.original,
+            suggestion.mutated,
+            MutationOperator::AiSuggested,
+        );
+        mutation.description = format!("AI: {}", suggestion.reason);
+        mutation.ai_suggested = true;
+        mutation.ai_confidence = Some(suggestion.confidence);
+        Some(mutation)
     }
 
     async fn suggest_with_anthropic(&self, source: &str) -> Result<Vec<MutationSuggestion>> {
@@ -138,22 +217,20 @@ impl AiMutationSuggester {
         let prompt = self.build_prompt(source);
 
         let client = reqwest::Client::new();
-        let response = client
+        let request = client
             .post("https://api.anthropic.com/v1/messages")
             .header("x-api-key", &api_key)
             .header("anthropic-version", "2023-06-01")
             .header("content-type", "application/json")
             .json(&serde_json::json!({
-                "model": "claude-sonnet-4-20250514",
+                "model": ANTHROPIC_MODEL,
                 "max_tokens": 4096,
                 "messages": [{
                     "role": "user",
                     "content": prompt
                 }]
-            }))
-            .send()
-            .await
-            .context("Failed to call Anthropic API")?;
+            }));
+        let response = self.send_with_retry(request, "Anthropic").await?;
 
         let body: serde_json::Value = response.json().await?;
         self.parse_ai_response(&body)
@@ -169,22 +246,92 @@ impl AiMutationSuggester {
         let prompt = self.build_prompt(source);
 
         let client = reqwest::Client::new();
-        let response = client
+        let request = client
             .post("https://api.openai.com/v1/chat/completions")
             .header("Authorization", format!("Bearer {}", api_key))
             .header("Content-Type", "application/json")
             .json(&serde_json::json!({
-                "model": "gpt-4-turbo-preview",
+                "model": OPENAI_MODEL,
                 "messages": [{
                     "role": "user",
                     "content": prompt
                 }],
                 "max_tokens": 4096,
                 "temperature": 0.3
-            }))
-            .send()
-            .await
-            .context("Failed to call OpenAI API")?;
+            }));
+        let response = self.send_with_retry(request, "OpenAI").await?;
+
+        let body: serde_json::Value = response.json().await?;
+        self.parse_ai_response(&body)
+    }
+
+    async fn suggest_with_azure(&self, source: &str) -> Result<Vec<MutationSuggestion>> {
+        let api_key = self
+            .api_key
+            .clone()
+            .or_else(|| std::env::var("AZURE_OPENAI_API_KEY").ok())
+            .context(
+                "Azure OpenAI API key not set. Use --ai-key or AZURE_OPENAI_API_KEY env var",
+            )?;
+        let endpoint = self.azure.endpoint.as_deref().context(
+            "Azure OpenAI endpoint not set. Use --azure-endpoint or AZURE_OPENAI_ENDPOINT env var",
+        )?;
+        let deployment = self.azure.deployment.as_deref().context(
+            "Azure OpenAI deployment not set. Use --azure-deployment or AZURE_OPENAI_DEPLOYMENT env var",
+        )?;
+
+        let prompt = self.build_prompt(source);
+
+        let client = reqwest::Client::new();
+        let request = client
+            .post(format!(
+                "{}/openai/deployments/{deployment}/chat/completions",
+                endpoint.trim_end_matches('/')
+            ))
+            .query(&[("api-version", &self.azure.api_version)])
+            .header("api-key", &api_key)
+            .header("Content-Type", "application/json")
+            .json(&serde_json::json!({
+                "messages": [{
+                    "role": "user",
+                    "content": prompt
+                }],
+                "max_tokens": 4096,
+                "temperature": 0.3
+            }));
+        let response = self.send_with_retry(request, "Azure OpenAI").await?;
+
+        // Same response shape as OpenAI
+        let body: serde_json::Value = response.json().await?;
+        self.parse_ai_response(&body)
+    }
+
+    async fn suggest_with_gemini(&self, source: &str) -> Result<Vec<MutationSuggestion>> {
+        let api_key = self
+            .api_key
+            .clone()
+            .or_else(|| std::env::var("GEMINI_API_KEY").ok())
+            .context("Gemini API key not set. Use --ai-key or GEMINI_API_KEY env var")?;
+
+        let prompt = self.build_prompt(source);
+
+        let client = reqwest::Client::new();
+        let request = client
+            .post(format!(
+                "https://generativelanguage.googleapis.com/v1beta/models/{GEMINI_MODEL}:generateContent"
+            ))
+            .header("x-goog-api-key", &api_key)
+            .header("Content-Type", "application/json")
+            .json(&serde_json::json!({
+                "contents": [{
+                    "parts": [{ "text": prompt }]
+                }],
+                "generationConfig": {
+                    "maxOutputTokens": 4096,
+                    "temperature": 0.3
+                }
+            }));
+        let response = self.send_with_retry(request, "Gemini").await?;
 
         let body: serde_json::Value = response.json().await?;
         self.parse_ai_response(&body)
@@ -194,22 +341,63 @@ impl AiMutationSuggester {
         let prompt = self.build_prompt(source);
 
         let client = reqwest::Client::new();
-        let response = client
+        let request = client
             .post(format!("{}/api/generate", self.ollama_url))
             .json(&serde_json::json!({
                 "model": self.ollama_model,
                 "prompt": prompt,
                 "stream": false
-            }))
-            .send()
-            .await
-            .context("Failed to call Ollama API")?;
+            }));
+        let response = self.send_with_retry(request, "Ollama").await?;
 
         let body: serde_json::Value = response.json().await?;
         self.parse_ai_response(&body)
     }
 
+    /// Send a request, retrying rate limits, server errors and connection
+    /// failures with exponential backoff (1s, 2s, 4s, ...) or the server's
+    /// `Retry-After`
+    async fn send_with_retry(
+        &self,
+        request: reqwest::RequestBuilder,
+        api_name: &str,
+    ) -> Result<reqwest::Response> {
+        let mut attempt = 0;
+        loop {
+            let this_try = request
+                .try_clone()
+                .context("AI request body cannot be retried")?;
+            let (retry_after, failure) = match this_try.send().await {
+                Ok(response) if !is_retryable(response.status()) => return Ok(response),
+                Ok(response) => (
+                    retry_after(&response),
+                    format!("{api_name} API returned {}", response.status()),
+                ),
+                Err(e) if e.is_connect() || e.is_timeout() => {
+                    (None, format!("Failed to call {api_name} API: {e}"))
+                }
+                Err(e) => return Err(e).with_context(|| format!("Failed to call {api_name} API")),
+            };
+            if attempt >= self.max_retries {
+                anyhow::bail!("{failure} (gave up after {attempt} retries)");
+            }
+            let delay = retry_after
+                .unwrap_or_else(|| Duration::from_secs(1 << attempt.min(6)))
+                .min(MAX_RETRY_DELAY);
+            tracing::debug!("{failure}, retrying in {:.1}s", delay.as_secs_f64());
+            tokio::time::sleep(delay).await;
+            attempt += 1;
+        }
+    }
+
+    #[allow(clippy::literal_string_with_formatting_args)] // template placeholders
     fn build_prompt(&self, source: &str) -> String {
+        if let Some(template) = &self.prompt_template {
+            // Substitute `{source}` last so placeholders inside the code stay
+            return template
+                .replace("{max}", &self.max_per_file.to_string())
+                .replace("{source}", source);
+        }
         format!(
             r#"Analyze this Dart code and suggest high-value mutation locations for mutation testing.
 
@@ -267,6 +455,18 @@ Dart code:
                     .and_then(|msg| msg.get("content"))
                     .and_then(|t| t.as_str())
             })
+            // Gemini format
+            .or_else(|| {
+                body.get("candidates")
+                    .and_then(|c| c.as_array())
+                    .and_then(|arr| arr.first())
+                    .and_then(|candidate| candidate.get("content"))
+                    .and_then(|content| content.get("parts"))
+                    .and_then(|p| p.as_array())
+                    .and_then(|arr| arr.first())
+                    .and_then(|part| part.get("text"))
+                    .and_then(|t| t.as_str())
+            })
             // Ollama format
             .or_else(|| body.get("response").and_then(|r| r.as_str()))
             .unwrap_or("");
@@ -290,27 +490,96 @@ Dart code:
     }
 }
 
+/// Load a prompt template for `--ai-prompt-file`. `{source}` is replaced by
+/// the Dart file and `{max}` by the suggestion limit.
+#[allow(clippy::literal_string_with_formatting_args)] // template placeholders
+pub fn load_prompt_template(path: &Path) -> Result<String> {
+    let template = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read AI prompt file: {}", path.display()))?;
+    if !template.contains("{source}") {
+        anyhow::bail!(
+            "AI prompt file {} has no {{source}} placeholder for the Dart code",
+            path.display()
+        );
+    }
+    Ok(template)
+}
+
+fn is_retryable(status: reqwest::StatusCode) -> bool {
+    status == reqwest::StatusCode::TOO_MANY_REQUESTS || status.is_server_error()
+}
+
+/// `Retry-After` in seconds; the HTTP-date form falls back to backoff
+fn retry_after(response: &reqwest::Response) -> Option<Duration> {
+    response
+        .headers()
+        .get(reqwest::header::RETRY_AFTER)?
+        .to_str()
+        .ok()?
+        .trim()
+        .parse::<u64>()
+        .ok()
+        .map(Duration::from_secs)
+}
+
+fn store_suggestions(path: &Path, suggestions: &[MutationSuggestion]) -> Result<()> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)
+            .with_context(|| format!("Failed to create {}", dir.display()))?;
+    }
+    std::fs::write(path, serde_json::to_string_pretty(suggestions)?)
+        .with_context(|| format!("Failed to write {}", path.display()))
+}
+
+/// Byte offset of a 1-based line and 1-based column. The column counts
+/// characters, as a model reading the source does, and lines end at `\n`
+/// with or without a `\r`
+fn byte_offset(source: &str, line: usize, column: usize) -> Option<usize> {
+    let index = line.checked_sub(1)?;
+    let line_start: usize = source.split_inclusive('\n').take(index).map(str::len).sum();
+    let text = source.split_inclusive('\n').nth(index)?;
+    let text = text.strip_suffix('\n').unwrap_or(text);
+    let text = text.strip_suffix('\r').unwrap_or(text);
+    let (offset, _) = text.char_indices().nth(column.checked_sub(1)?)?;
+    Some(line_start + offset)
+}
+
+/// Accept a suggested mutation only if it replaces a whole syntax node (an
+/// operator, literal or expression rather than part of a token) and the
+/// mutated file still parses
+fn check_against_ast(source: &str, tree: &Tree, mutation: &Mutation) -> Result<(), String> {
+    let (start, end) = (mutation.location.byte_start, mutation.location.byte_end);
+    let spans_node = tree
+        .root_node()
+        .descendant_for_byte_range(start, end)
+        .is_some_and(|node| node.start_byte() == start && node.end_byte() == end);
+    if !spans_node {
+        return Err(format!(
+            "`{}` is not a complete syntax node",
+            mutation.original
+        ));
+    }
+
+    // A file the grammar can't fully parse gives no signal either way
+    let mutated = parse_dart(&mutation.apply(source)).map_err(|e| e.to_string())?;
+    if mutated.root_node().has_error() && !tree.root_node().has_error() {
+        return Err(format!(
+            "`{}` → `{}` produces invalid Dart",
+            mutation.original, mutation.mutated
+        ));
+    }
+    Ok(())
+}
+
 /// Convenience function to suggest mutations for multiple files
 pub async fn suggest_mutations_for_files(
     files: &[PathBuf],
-    provider: AiProvider,
-    api_key: Option<String>,
-    ollama_url: &str,
-    ollama_model: &str,
-    max_per_file: usize,
+    suggester: &AiMutationSuggester,
 ) -> Result<Vec<Mutation>> {
-    if matches!(provider, AiProvider::None) {
+    if matches!(suggester.provider, AiProvider::None) {
         return Ok(vec![]);
     }
 
-    let suggester = AiMutationSuggester::new(
-        provider,
-        api_key,
-        ollama_url.to_string(),
-        ollama_model.to_string(),
-        max_per_file,
-    );
-
     let mut all_mutations = Vec::new();
 
     for file in files {
@@ -325,3 +594,241 @@ pub async fn suggest_mutations_for_files(
 
     Ok(all_mutations)
 }
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    fn suggest(
+        source: &str,
+        line: usize,
+        column: usize,
+        original: &str,
+        mutated: &str,
+    ) -> Mutation {
+        let suggester = AiMutationSuggester::new(
+            AiProvider::None,
+            None,
+            String::new(),
+            String::new(),
+            10,
+            None,
+        );
+        suggester
+            .suggestion_to_mutation(
+                Path::new("calc.dart"),
+                source,
+                MutationSuggestion {
+                    line,
+                    column,
+                    original: original.to_owned(),
+                    mutated: mutated.to_owned(),
+                    reason: String::new(),
+                    confidence: 0.5,
+                },
+            )
+            .unwrap()
+    }
+
+    #[test]
+    fn test_maps_columns_in_crlf_source() {
+        let source = "int a(int x) => x;\r\nbool adult(int age) => age >= 18;\r\n";
+
+        let mutation = suggest(source, 2, 28, ">=", ">");
+
+        assert_eq!(
+            mutation.apply(source),
+            "int a(int x) => x;\r\nbool adult(int age) => age > 18;\r\n"
+        );
+        assert_eq!(mutation.location.start_col, 28);
+    }
+
+    #[test]
+    fn test_maps_character_columns_past_multibyte_text() {
+        // `é` is two bytes and the emoji four, but one column each
+        let source = "// héllo\nString s(int n) => n > 0 ? '🎉' : 'é' + 'x';\n";
+
+        let mutation = suggest(source, 2, 38, "+", "-");
+
+        assert_eq!(
+            mutation.apply(source),
+            "// héllo\nString s(int n) => n > 0 ? '🎉' : 'é' - 'x';\n"
+        );
+        // Stored as a byte column, like the parser's
+        assert_eq!(mutation.location.start_col, 42);
+
+        // A column past the end of the line, or text that isn't there
+        let suggester = AiMutationSuggester::new(
+            AiProvider::None,
+            None,
+            String::new(),
+            String::new(),
+            10,
+            None,
+        );
+        let miss = |line, column, original: &str| {
+            suggester.suggestion_to_mutation(
+                Path::new("calc.dart"),
+                source,
+                MutationSuggestion {
+                    line,
+                    column,
+                    original: original.to_owned(),
+                    mutated: String::new(),
+                    reason: String::new(),
+                    confidence: 0.5,
+                },
+            )
+        };
+        assert!(miss(2, 80, "+").is_none());
+        assert!(miss(2, 37, "+").is_none());
+        assert!(miss(3, 1, "+").is_none());
+        assert!(miss(0, 1, "/").is_none());
+    }
+
+    #[test]
+    fn test_ast_check_rejects_partial_tokens_and_invalid_code() {
+        let source = "bool adult(int age) => age >= 18;\n";
+        let tree = parse_dart(source).unwrap();
+        let check = |m: &Mutation| check_against_ast(source, &tree, m);
+
+        assert!(check(&suggest(source, 1, 28, ">=", ">")).is_ok());
+        assert!(check(&suggest(source, 1, 31, "18", "17")).is_ok());
+        // `>` is only half of the `>=` token
+        assert!(check(&suggest(source, 1, 28, ">", "<")).is_err());
+        assert!(check(&suggest(source, 1, 28, ">=", "=>>")).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_cached_suggestions_skip_the_provider() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = "bool adult(int age) => age >= 18;\n";
+        let suggester = |model: &str| {
+            AiMutationSuggester::new(
+                AiProvider::Ollama,
+                None,
+                "http://127.0.0.1:9".to_owned(),
+                model.to_owned(),
+                10,
+                Some(dir.path().to_path_buf()),
+            )
+            .with_max_retries(0)
+        };
+        let suggestion = MutationSuggestion {
+            line: 1,
+            column: 28,
+            original: ">=".to_owned(),
+            mutated: ">".to_owned(),
+            reason: "Boundary".to_owned(),
+            confidence: 0.9,
+        };
+        let codellama = suggester("codellama");
+        store_suggestions(&codellama.cache_path(source).unwrap(), &[suggestion]).unwrap();
+
+        // Nothing listens on the Ollama URL, so only a cache hit succeeds
+        let mutations = codellama
+            .suggest_mutations(Path::new("calc.dart"), source)
+            .await
+            .unwrap();
+        assert_eq!(mutations.len(), 1);
+        assert_eq!(mutations[0].mutated, ">");
+
+        let other_model = suggester("llama3");
+        assert_ne!(other_model.cache_path(source), codellama.cache_path(source));
+        assert!(other_model
+            .suggest_mutations(Path::new("calc.dart"), source)
+            .await
+            .is_err());
+    }
+
+    /// Serve one canned HTTP response per connection, after reading the request
+    async fn serve(responses: Vec<String>) -> String {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            for response in responses {
+                let (mut stream, _) = listener.accept().await.unwrap();
+                let mut request = Vec::new();
+                let mut buf = [0; 4096];
+                while let Ok(n @ 1..) = stream.read(&mut buf).await {
+                    request.extend_from_slice(&buf[..n]);
+                    let text = String::from_utf8_lossy(&request);
+                    if let Some((head, body)) = text.split_once("\r\n\r\n") {
+                        let length = head
+                            .lines()
+                            .find_map(|l| {
+                                l.to_lowercase()
+                                    .strip_prefix("content-length:")
+                                    .map(|v| v.trim().parse::<usize>().unwrap())
+                            })
+                            .unwrap_or(0);
+                        if body.len() >= length {
+                            break;
+                        }
+                    }
+                }
+                stream.write_all(response.as_bytes()).await.unwrap();
+            }
+        });
+        url
+    }
+
+    #[tokio::test]
+    async fn test_retries_rate_limited_requests() {
+        let body = r#"{"response": "[{\"line\": 1, \"column\": 28, \"original\": \">=\", \"mutated\": \">\", \"reason\": \"Boundary\", \"confidence\": 0.9}]"}"#;
+        let url = serve(vec![
+            "HTTP/1.1 429 Too Many Requests\r\nRetry-After: 0\r\nContent-Length: 0\r\nConnection: close\r\n\r\n".to_owned(),
+            format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                body.len()
+            ),
+        ])
+        .await;
+        let suggester = AiMutationSuggester::new(
+            AiProvider::Ollama,
+            None,
+            url,
+            "codellama".to_owned(),
+            10,
+            None,
+        )
+        .with_max_retries(1);
+
+        let mutations = suggester
+            .suggest_mutations(
+                Path::new("calc.dart"),
+                "bool adult(int age) => age >= 18;\n",
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(mutations.len(), 1);
+    }
+
+    #[test]
+    #[allow(clippy::literal_string_with_formatting_args)]
+    fn test_prompt_template_substitutes_placeholders() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("prompt.txt");
+        std::fs::write(&path, "Focus on rounding. Up to {max} items.\n{source}").unwrap();
+        let suggester = AiMutationSuggester::new(
+            AiProvider::None,
+            None,
+            String::new(),
+            String::new(),
+            5,
+            None,
+        )
+        .with_prompt_template(Some(load_prompt_template(&path).unwrap()));
+
+        assert_eq!(
+            suggester.build_prompt("var s = '{max}';"),
+            "Focus on rounding. Up to 5 items.\nvar s = '{max}';"
+        );
+
+        std::fs::write(&path, "Suggest mutations").unwrap();
+        assert!(load_prompt_template(&path).is_err());
+    }
+}
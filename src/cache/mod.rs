@@ -0,0 +1,371 @@
+//! Incremental result cache
+//!
+//! Stores the outcome of each tested mutant keyed by `Mutation::id`, along
+//! with a hash of the source file it was tested against. A later
+//! `--incremental` run reuses a cached outcome as long as that file is
+//! unchanged, and drops every entry for files that changed.
+//!
+//! Killed mutants and compile errors stay decided while the source is
+//! unchanged. Whether a mutant survives or times out also depends on the
+//! tests, so those entries are tied to a hash of the `test/` directory too
+//! and re-tested once a test changes.
+
+use crate::mutation::{MutantStatus, Mutation};
+use crate::runner::MutantTestResult;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use walkdir::WalkDir;
+
+/// Bumped when the cache layout changes; older caches are discarded
+const CACHE_VERSION: u32 = 2;
+
+/// Cached mutant outcomes
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResultCache {
+    version: u32,
+    entries: HashMap<String, CacheEntry>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CacheEntry {
+    file: PathBuf,
+    file_hash: String,
+    /// Set for outcomes the tests could change
+    tests_hash: Option<String>,
+    status: MutantStatus,
+    duration_ms: u64,
+    /// The compile error of an `Error` result
+    error: Option<String>,
+}
+
+impl Default for ResultCache {
+    fn default() -> Self {
+        Self {
+            version: CACHE_VERSION,
+            entries: HashMap::new(),
+        }
+    }
+}
+
+impl ResultCache {
+    /// Load the cache, starting empty if it is missing, unreadable or from
+    /// another version
+    pub fn load(path: &Path) -> Self {
+        let Ok(json) = std::fs::read_to_string(path) else {
+            return Self::default();
+        };
+        match serde_json::from_str::<Self>(&json) {
+            Ok(cache) if cache.version == CACHE_VERSION => cache,
+            Ok(_) => Self::default(),
+            Err(e) => {
+                tracing::warn!("Ignoring unreadable cache {}: {e}", path.display());
+                Self::default()
+            }
+        }
+    }
+
+    /// Write the cache as JSON
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let json = serde_json::to_string(self)?;
+        std::fs::write(path, json)
+            .with_context(|| format!("Failed to write cache file: {}", path.display()))
+    }
+
+    /// Drop entries whose source file, or for test-dependent outcomes the
+    /// tests, changed since they were cached. `file_hashes` holds the current
+    /// hash of every mutated file.
+    pub fn invalidate(&mut self, file_hashes: &FileHashes) {
+        self.entries
+            .retain(|_, entry| entry.is_current(file_hashes));
+    }
+
+    /// Split mutations into cached results and those still to be tested
+    pub fn partition(
+        &self,
+        mutations: Vec<Mutation>,
+        file_hashes: &FileHashes,
+    ) -> (Vec<MutantTestResult>, Vec<Mutation>) {
+        let mut cached = Vec::new();
+        let mut untested = Vec::new();
+        for mutation in mutations {
+            match self.get(&mutation, file_hashes) {
+                Some(result) => cached.push(result),
+                None => untested.push(mutation),
+            }
+        }
+        (cached, untested)
+    }
+
+    /// The cached result for a mutation, if its file is unchanged
+    fn get(&self, mutation: &Mutation, file_hashes: &FileHashes) -> Option<MutantTestResult> {
+        let entry = self.entries.get(&mutation.id)?;
+        entry.is_current(file_hashes).then(|| MutantTestResult {
+            mutation: mutation.clone(),
+            status: entry.status,
+            duration: Duration::from_millis(entry.duration_ms),
+            output: None,
+            error: entry.error.clone(),
+            retries: 0,
+            killed_by: None,
+        })
+    }
+
+    /// Record fresh results. Killed, survived and timed-out mutants and
+    /// compile errors are cached; other errors and no-coverage results are
+    /// retried next run.
+    pub fn record(&mut self, results: &[MutantTestResult], file_hashes: &FileHashes) {
+        for result in results {
+            let tests_hash = match result.status {
+                MutantStatus::Killed => None,
+                MutantStatus::Error if is_compile_error(result) => None,
+                MutantStatus::Survived | MutantStatus::Timeout => match &file_hashes.tests {
+                    Some(hash) => Some(hash.clone()),
+                    None => continue,
+                },
+                _ => continue,
+            };
+            let file = &result.mutation.location.file;
+            let Some(file_hash) = file_hashes.get(file) else {
+                continue;
+            };
+            self.entries.insert(
+                result.mutation.id.clone(),
+                CacheEntry {
+                    file: file.clone(),
+                    file_hash: file_hash.to_owned(),
+                    tests_hash,
+                    status: result.status,
+                    duration_ms: result.duration.as_millis() as u64,
+                    error: result
+                        .error
+                        .clone()
+                        .filter(|_| result.status == MutantStatus::Error),
+                },
+            );
+        }
+    }
+}
+
+impl CacheEntry {
+    /// Whether the entry was recorded against the current source and, if
+    /// it depends on them, the current tests
+    fn is_current(&self, file_hashes: &FileHashes) -> bool {
+        file_hashes.get(&self.file) == Some(self.file_hash.as_str())
+            && self
+                .tests_hash
+                .as_ref()
+                .map_or(true, |hash| file_hashes.tests.as_ref() == Some(hash))
+    }
+}
+
+/// `dart analyze` rejected the mutant, which no test change can alter
+fn is_compile_error(result: &MutantTestResult) -> bool {
+    result
+        .error
+        .as_deref()
+        .is_some_and(|e| e.starts_with("Compile error"))
+}
+
+/// Content hashes of source files, computed before any file is mutated
+#[derive(Debug, Clone, Default)]
+pub struct FileHashes {
+    hashes: HashMap<PathBuf, String>,
+    /// One hash over every file under `test/`, see `with_tests`
+    tests: Option<String>,
+}
+
+impl FileHashes {
+    /// Hash each file's current content, skipping unreadable files
+    pub fn compute(files: &[PathBuf]) -> Self {
+        let hashes = files
+            .iter()
+            .filter_map(|file| Some((file.clone(), hash_file(file)?)))
+            .collect();
+        Self {
+            hashes,
+            tests: None,
+        }
+    }
+
+    /// Also hash the tests of `project_path`: the path and content of every
+    /// file under `test/`. Without it, survivors and timeouts aren't cached
+    pub fn with_tests(mut self, project_path: &Path) -> Self {
+        let test_dir = project_path.join("test");
+        let mut files: Vec<PathBuf> = WalkDir::new(&test_dir)
+            .into_iter()
+            .filter_map(Result::ok)
+            .filter(|entry| entry.file_type().is_file())
+            .map(walkdir::DirEntry::into_path)
+            .collect();
+        files.sort();
+
+        let mut context = md5::Context::new();
+        for file in &files {
+            let Ok(content) = std::fs::read(file) else {
+                continue;
+            };
+            let relative = file.strip_prefix(&test_dir).unwrap_or(file);
+            context.consume(relative.to_string_lossy().as_bytes());
+            context.consume([0]);
+            context.consume(md5::compute(content).0);
+        }
+        self.tests = Some(format!("{:x}", context.compute()));
+        self
+    }
+
+    /// Re-hash `files`, returning those whose content differs from the last
+    /// hash. A file that can no longer be read counts as changed and is
+    /// forgotten
+    pub fn update(&mut self, files: &[PathBuf]) -> Vec<PathBuf> {
+        let mut changed = Vec::new();
+        for file in files {
+            let previous = match hash_file(file) {
+                Some(hash) => self.hashes.insert(file.clone(), hash),
+                None => self.hashes.remove(file),
+            };
+            if previous.as_ref() != self.hashes.get(file) {
+                changed.push(file.clone());
+            }
+        }
+        changed
+    }
+
+    fn get(&self, file: &Path) -> Option<&str> {
+        self.hashes.get(file).map(String::as_str)
+    }
+}
+
+fn hash_file(file: &Path) -> Option<String> {
+    let content = std::fs::read(file).ok()?;
+    Some(format!("{:x}", md5::compute(content)))
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::mutation::testing::mutant;
+
+    fn result(file: &Path, status: MutantStatus) -> MutantTestResult {
+        mutant()
+            .file(file)
+            .status(status)
+            .duration(Duration::from_millis(1200))
+            .result()
+    }
+
+    #[test]
+    fn test_reuses_results_until_file_changes() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("calc.dart");
+        let cache_path = dir.path().join(".dart_mutant_cache");
+        std::fs::write(&file, "int add(int a, int b) => a + b;").unwrap();
+
+        let hashes = FileHashes::compute(std::slice::from_ref(&file)).with_tests(dir.path());
+        let tested = result(&file, MutantStatus::Survived);
+        let mut cache = ResultCache::default();
+        cache.record(std::slice::from_ref(&tested), &hashes);
+        cache.save(&cache_path).unwrap();
+
+        let mut cache = ResultCache::load(&cache_path);
+        let (cached, untested) = cache.partition(vec![tested.mutation.clone()], &hashes);
+        assert!(untested.is_empty());
+        assert_eq!(cached[0].status, MutantStatus::Survived);
+        assert_eq!(cached[0].duration, Duration::from_millis(1200));
+
+        std::fs::write(&file, "int add(int a, int b) => b + a;").unwrap();
+        let changed = FileHashes::compute(std::slice::from_ref(&file)).with_tests(dir.path());
+        let (cached, untested) = cache.partition(vec![tested.mutation.clone()], &changed);
+        assert!(cached.is_empty());
+        assert_eq!(untested.len(), 1);
+
+        cache.invalidate(&changed);
+        assert!(cache.entries.is_empty());
+    }
+
+    #[test]
+    fn test_survivors_are_retested_when_tests_change() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("calc.dart");
+        let test_file = dir.path().join("test").join("calc_test.dart");
+        std::fs::write(&file, "int add(int a, int b) => a + b;").unwrap();
+        std::fs::create_dir(dir.path().join("test")).unwrap();
+        std::fs::write(&test_file, "void main() {}").unwrap();
+
+        let mut survived = result(&file, MutantStatus::Survived);
+        survived.mutation.id = "survived".to_owned();
+        let killed = result(&file, MutantStatus::Killed);
+        let hashes = FileHashes::compute(std::slice::from_ref(&file)).with_tests(dir.path());
+        let mut cache = ResultCache::default();
+        cache.record(&[survived.clone(), killed.clone()], &hashes);
+
+        // Survivors need the tests hash; killed mutants don't
+        let mut untracked = ResultCache::default();
+        untracked.record(
+            &[survived.clone(), killed.clone()],
+            &FileHashes::compute(std::slice::from_ref(&file)),
+        );
+        assert_eq!(untracked.entries.len(), 1);
+
+        std::fs::write(&test_file, "void main() { expect(add(1, 2), 3); }").unwrap();
+        let new_tests = FileHashes::compute(std::slice::from_ref(&file)).with_tests(dir.path());
+        let (cached, untested) =
+            cache.partition(vec![survived.mutation, killed.mutation], &new_tests);
+        assert_eq!(cached.len(), 1);
+        assert_eq!(cached[0].status, MutantStatus::Killed);
+        assert_eq!(untested[0].id, "survived");
+
+        cache.invalidate(&new_tests);
+        assert_eq!(cache.entries.len(), 1);
+    }
+
+    #[test]
+    fn test_only_compile_errors_are_cached() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("calc.dart");
+        std::fs::write(&file, "int add(int a, int b) => a + b;").unwrap();
+
+        let hashes = FileHashes::compute(std::slice::from_ref(&file));
+        let mut cache = ResultCache::default();
+        let mut failed = result(&file, MutantStatus::Error);
+        failed.error = Some("Failed to write mutated file".to_owned());
+        cache.record(&[failed], &hashes);
+        assert!(cache.entries.is_empty());
+
+        let mut invalid = result(&file, MutantStatus::Error);
+        invalid.error = Some("Compile error: 1:1: Expected an identifier".to_owned());
+        cache.record(std::slice::from_ref(&invalid), &hashes);
+        let (cached, _) = cache.partition(vec![invalid.mutation.clone()], &hashes);
+        assert_eq!(cached[0].error, invalid.error);
+
+        let mut cache = ResultCache::default();
+        cache.record(&[result(&file, MutantStatus::Error)], &hashes);
+        assert!(cache.entries.is_empty());
+        assert!(ResultCache::load(&dir.path().join("missing"))
+            .entries
+            .is_empty());
+    }
+
+    #[test]
+    fn test_update_reports_only_content_changes() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("calc.dart");
+        let new_file = dir.path().join("new.dart");
+        std::fs::write(&file, "int x = 1;").unwrap();
+        let mut hashes = FileHashes::compute(std::slice::from_ref(&file));
+
+        // Rewritten with the same content, like a mutant being restored
+        std::fs::write(&file, "int x = 1;").unwrap();
+        std::fs::write(&new_file, "int y = 2;").unwrap();
+        let both = [file.clone(), new_file.clone()];
+        assert_eq!(hashes.update(&both), std::slice::from_ref(&new_file));
+
+        std::fs::write(&file, "int x = 2;").unwrap();
+        std::fs::remove_file(&new_file).unwrap();
+        assert_eq!(hashes.update(&both), both);
+        assert!(hashes.update(&both).is_empty());
+    }
+}
@@ -0,0 +1,262 @@
+//! Lines changed versus a git ref, for `--diff-only`
+//!
+//! Runs `git diff --unified=0` against the merge base of the ref and `HEAD`,
+//! so the working tree (committed, staged and unstaged edits) is compared
+//! with the point the branch started from. Untracked files count as changed
+//! in full.
+
+use crate::mutation::Mutation;
+use anyhow::{bail, Context, Result};
+use std::collections::{HashMap, HashSet};
+use std::path::{Component, Path, PathBuf};
+use std::process::Command;
+
+/// Lines that differ from the base ref, per file relative to the project
+#[derive(Debug, Clone, Default)]
+pub struct ChangedLines {
+    files: HashMap<PathBuf, FileChange>,
+    roots: Vec<PathBuf>,
+}
+
+#[derive(Debug, Clone)]
+enum FileChange {
+    /// Untracked file: every line is changed
+    Whole,
+    Lines(HashSet<usize>),
+}
+
+impl ChangedLines {
+    /// Ask git for the lines changed in `project` since it diverged from
+    /// `base_ref`
+    pub fn from_git(project: &Path, base_ref: &str) -> Result<Self> {
+        let dir = if project.is_file() {
+            project.parent().unwrap_or(project)
+        } else {
+            project
+        };
+        if which::which("git").is_err() {
+            bail!("--diff-only needs git, but it was not found on PATH");
+        }
+
+        // Diff against where the branch started, not the ref's current tip,
+        // so commits that landed on the base since don't count as changes.
+        // Shallow clones may lack the merge base; fall back to the ref.
+        let base = git(dir, &["merge-base", base_ref, "HEAD"])
+            .map_or_else(|_| base_ref.to_owned(), |out| out.trim().to_owned());
+        let diff = git(
+            dir,
+            &[
+                "-c",
+                "core.quotePath=false",
+                "diff",
+                "--unified=0",
+                "--no-color",
+                "--no-ext-diff",
+                "--find-renames",
+                "--relative",
+                &base,
+                "--",
+            ],
+        )
+        .with_context(|| format!("Failed to diff against `{base_ref}`"))?;
+        let untracked = git(dir, &["ls-files", "--others", "--exclude-standard"])?;
+
+        let mut roots = vec![dir.to_path_buf()];
+        if let Ok(canonical) = dir.canonicalize() {
+            roots.push(canonical);
+        }
+        let mut changes = Self::parse_diff(&diff);
+        changes.roots = roots;
+        for file in untracked.lines().filter(|l| !l.is_empty()) {
+            changes.files.insert(PathBuf::from(file), FileChange::Whole);
+        }
+        Ok(changes)
+    }
+
+    /// Parse `git diff --unified=0` output. Hunks are recorded against the
+    /// new path, so renamed files are matched by their current name
+    fn parse_diff(diff: &str) -> Self {
+        let mut files = HashMap::new();
+        let mut current: Option<PathBuf> = None;
+        // Only before the first hunk; an added line can start with `++ `
+        let mut in_header = false;
+
+        for line in diff.lines() {
+            if line.starts_with("diff --git ") {
+                current = None;
+                in_header = true;
+            } else if let Some(path) = line.strip_prefix("+++ ").filter(|_| in_header) {
+                current = new_path(path);
+            } else if let (Some(file), Some(header)) = (&current, line.strip_prefix("@@ ")) {
+                in_header = false;
+                let Some((start, count)) = new_range(header) else {
+                    continue;
+                };
+                let entry = files
+                    .entry(file.clone())
+                    .or_insert_with(|| FileChange::Lines(HashSet::new()));
+                if let FileChange::Lines(lines) = entry {
+                    lines.extend(start..start + count);
+                }
+            }
+        }
+
+        Self {
+            files,
+            roots: Vec::new(),
+        }
+    }
+
+    /// Whether `file` has any changed lines
+    pub fn touches_file(&self, file: &Path) -> bool {
+        self.files.contains_key(&self.relative(file))
+    }
+
+    /// Whether any line the mutation spans was changed
+    pub fn contains(&self, mutation: &Mutation) -> bool {
+        let location = &mutation.location;
+        match self.files.get(&self.relative(&location.file)) {
+            Some(FileChange::Whole) => true,
+            Some(FileChange::Lines(lines)) => {
+                (location.start_line..=location.end_line).any(|line| lines.contains(&line))
+            }
+            None => false,
+        }
+    }
+
+    fn relative(&self, path: &Path) -> PathBuf {
+        let relative = self
+            .roots
+            .iter()
+            .find_map(|root| path.strip_prefix(root).ok())
+            .unwrap_or(path);
+        // Drop any leading `./` so paths compare equal to git's
+        relative
+            .components()
+            .filter(|c| !matches!(c, Component::CurDir))
+            .collect()
+    }
+}
+
+/// Run git in `dir`, returning stdout or an error carrying git's stderr
+pub(crate) fn git(dir: &Path, args: &[&str]) -> Result<String> {
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .context("Failed to run git")?;
+    if !output.status.success() {
+        bail!(
+            "git {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Path from a `+++ b/path` line; `None` for deleted files
+fn new_path(path: &str) -> Option<PathBuf> {
+    let path = path.trim_end();
+    if path == "/dev/null" {
+        return None;
+    }
+    // Paths with unusual characters are quoted: `"b/odd name.dart"`
+    let path = path
+        .strip_prefix('"')
+        .and_then(|p| p.strip_suffix('"'))
+        .unwrap_or(path);
+    Some(PathBuf::from(path.strip_prefix("b/").unwrap_or(path)))
+}
+
+/// `(start, count)` of the new side of a hunk header such as
+/// `-12,2 +12,3 @@ fn`. A missing count means one line
+fn new_range(header: &str) -> Option<(usize, usize)> {
+    let new = header.split_whitespace().find(|p| p.starts_with('+'))?;
+    let new = new.trim_start_matches('+');
+    let (start, count) = match new.split_once(',') {
+        Some((start, count)) => (start.parse().ok()?, count.parse().ok()?),
+        None => (new.parse().ok()?, 1),
+    };
+    Some((start, count))
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::mutation::testing::mutant;
+
+    fn mutation_at(file: &str, line: usize) -> Mutation {
+        mutant().file(file).line(line).mutation()
+    }
+
+    const DIFF: &str = "\
+diff --git a/lib/calc.dart b/lib/calc.dart
+index 1111111..2222222 100644
+--- a/lib/calc.dart
++++ b/lib/calc.dart
+@@ -3 +3,2 @@ int add(int a, int b) {
+-  return a;
++  final sum = a + b;
++  return sum;
+@@ -10,2 +11,0 @@ int sub(int a, int b) {
+-  // gone
+-  // gone
+diff --git a/lib/old_name.dart b/lib/new_name.dart
+similarity index 90%
+rename from lib/old_name.dart
+rename to lib/new_name.dart
+index 3333333..4444444 100644
+--- a/lib/old_name.dart
++++ b/lib/new_name.dart
+@@ -7 +7 @@ bool isAdult(int age) {
+-  return age > 18;
++  return age >= 18;
+diff --git a/lib/deleted.dart b/lib/deleted.dart
+deleted file mode 100644
+--- a/lib/deleted.dart
++++ /dev/null
+@@ -1 +0,0 @@
+-void main() {}
+";
+
+    #[test]
+    fn test_parses_changed_lines_per_file() {
+        let changes = ChangedLines::parse_diff(DIFF);
+
+        assert!(changes.contains(&mutation_at("lib/calc.dart", 3)));
+        assert!(changes.contains(&mutation_at("lib/calc.dart", 4)));
+        assert!(!changes.contains(&mutation_at("lib/calc.dart", 5)));
+        // A pure deletion adds no lines
+        assert!(!changes.contains(&mutation_at("lib/calc.dart", 11)));
+        assert_eq!(changes.files.len(), 2);
+    }
+
+    #[test]
+    fn test_renamed_file_uses_new_path() {
+        let changes = ChangedLines::parse_diff(DIFF);
+
+        assert!(changes.contains(&mutation_at("lib/new_name.dart", 7)));
+        assert!(!changes.touches_file(Path::new("lib/old_name.dart")));
+        assert!(!changes.touches_file(Path::new("lib/deleted.dart")));
+    }
+
+    #[test]
+    fn test_paths_are_relative_to_project() {
+        let mut changes = ChangedLines::parse_diff(DIFF);
+        changes.roots = vec![PathBuf::from("/work/app")];
+
+        assert!(changes.contains(&mutation_at("/work/app/lib/calc.dart", 3)));
+        assert!(changes.touches_file(Path::new("./lib/calc.dart")));
+    }
+
+    #[test]
+    fn test_multi_line_mutation_overlapping_change() {
+        let changes = ChangedLines::parse_diff(DIFF);
+        let mut mutation = mutation_at("lib/calc.dart", 1);
+        mutation.location.end_line = 3;
+
+        assert!(changes.contains(&mutation));
+    }
+}
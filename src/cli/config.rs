@@ -0,0 +1,402 @@
+//! Project configuration file (`dart_mutant.yaml` or `dart_mutant.toml`)
+//!
+//! Keys are the long option names in snake_case. Values are merged into
+//! [`Args`] with this precedence, highest first: command-line flags and
+//! environment variables, the config file, `--preset`, built-in defaults.
+
+use super::{
+    parse_category, AiProvider, Args, FailOn, OutputFormat, Preset, SampleStrategy, TimeoutPolicy,
+};
+use crate::mutators::MutatorCategory;
+use anyhow::{Context, Result};
+use clap::ValueEnum;
+use serde::de::Error as _;
+use serde::{Deserialize, Deserializer};
+use std::collections::{BTreeMap, HashSet};
+use std::path::{Path, PathBuf};
+
+/// File names looked up in the project root, first match wins
+pub const CONFIG_FILE_NAMES: &[&str] = &["dart_mutant.yaml", "dart_mutant.yml", "dart_mutant.toml"];
+
+/// Settings read from a config file; `None` leaves the option untouched
+///
+/// There is deliberately no `ai_key`: keep API keys in the environment, not
+/// in a committed file.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    glob: Option<String>,
+    exclude: Option<Vec<String>>,
+    generated_suffixes: Option<Vec<String>>,
+    include_generated: Option<bool>,
+    parallel: Option<usize>,
+    sandbox: Option<bool>,
+    concurrency_per_file: Option<usize>,
+    reuse_vm: Option<bool>,
+    timeout: Option<u64>,
+    timeout_factor: Option<f64>,
+    max_duration: Option<u64>,
+    #[serde(default, deserialize_with = "value_enum")]
+    timeout_policy: Option<TimeoutPolicy>,
+    warmup: Option<bool>,
+    skip_baseline_check: Option<bool>,
+    threshold: Option<f64>,
+    threshold_high: Option<u32>,
+    threshold_low: Option<u32>,
+    #[serde(default, deserialize_with = "category_thresholds")]
+    category_thresholds: Option<Vec<(MutatorCategory, f64)>>,
+    #[serde(default, deserialize_with = "value_enums")]
+    fail_on: Option<Vec<FailOn>>,
+    file_thresholds: Option<PathBuf>,
+    history_file: Option<PathBuf>,
+    trend: Option<bool>,
+    output: Option<PathBuf>,
+    quiet: Option<bool>,
+    verbose: Option<bool>,
+    summary_detail: Option<bool>,
+    killing_tests: Option<Vec<String>>,
+    non_killing_tests: Option<Vec<String>>,
+    rerun_killed: Option<u32>,
+    confirm_survivors: Option<bool>,
+    test_command: Option<String>,
+    #[serde(default, deserialize_with = "key_values")]
+    test_env: Option<Vec<(String, String)>>,
+    #[serde(default, deserialize_with = "key_values")]
+    dart_define: Option<Vec<(String, String)>>,
+    test_map: Option<String>,
+    #[serde(default, deserialize_with = "value_enum")]
+    preset: Option<Preset>,
+    sample: Option<usize>,
+    #[serde(default, deserialize_with = "value_enum")]
+    sample_strategy: Option<SampleStrategy>,
+    seed: Option<u64>,
+    max_mutations: Option<usize>,
+    max_mutations_per_file: Option<usize>,
+    operators: Option<Vec<String>>,
+    skip_asserts: Option<bool>,
+    include_print_calls: Option<bool>,
+    coverage_file: Option<PathBuf>,
+    no_import_check: Option<bool>,
+    incremental: Option<bool>,
+    cache_file: Option<PathBuf>,
+    diff_only: Option<bool>,
+    base_ref: Option<String>,
+    #[serde(default, deserialize_with = "value_enum")]
+    ai: Option<AiProvider>,
+    no_ai_cache: Option<bool>,
+    ollama_model: Option<String>,
+    ollama_url: Option<String>,
+    azure_endpoint: Option<String>,
+    azure_deployment: Option<String>,
+    azure_api_version: Option<String>,
+    ai_max_per_file: Option<usize>,
+    ai_retries: Option<u32>,
+    ai_prompt_file: Option<PathBuf>,
+    html: Option<bool>,
+    relative_paths: Option<bool>,
+    #[serde(default, deserialize_with = "value_enum")]
+    output_format: Option<OutputFormat>,
+    json: Option<bool>,
+    junit: Option<bool>,
+    cobertura: Option<bool>,
+    sarif: Option<bool>,
+    ai_report: Option<bool>,
+    badge_output: Option<PathBuf>,
+    ndjson: Option<PathBuf>,
+    html_offline: Option<bool>,
+    report_include_source: Option<bool>,
+    report_source_max_lines: Option<usize>,
+    json_with_diff: Option<bool>,
+}
+
+impl Config {
+    /// Load the config file: `explicit` if given, otherwise the first of
+    /// [`CONFIG_FILE_NAMES`] found in the project root. Relative paths in
+    /// the file are resolved against the file's directory
+    pub fn load(explicit: Option<&Path>, project: &Path) -> Result<Option<Self>> {
+        let Some(path) = explicit
+            .map(Path::to_path_buf)
+            .or_else(|| Self::find(project))
+        else {
+            return Ok(None);
+        };
+
+        let content = std::fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read config file {}", path.display()))?;
+        let mut config = Self::parse(&content, &path)
+            .with_context(|| format!("Invalid config file {}", path.display()))?;
+        if let Some(dir) = path.parent() {
+            config.resolve_paths(dir);
+        }
+        Ok(Some(config))
+    }
+
+    /// First of [`CONFIG_FILE_NAMES`] in the project root
+    fn find(project: &Path) -> Option<PathBuf> {
+        let root = if project.is_file() {
+            project.parent().unwrap_or(project)
+        } else {
+            project
+        };
+        CONFIG_FILE_NAMES
+            .iter()
+            .map(|name| root.join(name))
+            .find(|candidate| candidate.is_file())
+    }
+
+    /// Parse TOML for `.toml` files and YAML for anything else
+    fn parse(content: &str, path: &Path) -> Result<Self> {
+        if path.extension().is_some_and(|ext| ext == "toml") {
+            Ok(toml::from_str(content)?)
+        } else if content.trim().is_empty() {
+            Ok(Self::default())
+        } else {
+            Ok(serde_yaml::from_str(content)?)
+        }
+    }
+
+    /// Make relative paths relative to `dir` rather than the working
+    /// directory. `cache_file` is already relative to the project path
+    fn resolve_paths(&mut self, dir: &Path) {
+        let paths = [
+            &mut self.file_thresholds,
+            &mut self.history_file,
+            &mut self.output,
+            &mut self.coverage_file,
+            &mut self.ai_prompt_file,
+            &mut self.badge_output,
+            &mut self.ndjson,
+        ];
+        for path in paths.into_iter().flatten() {
+            if path.is_relative() {
+                *path = dir.join(&*path);
+            }
+        }
+    }
+}
+
+impl Args {
+    /// Copy config values onto options not set by `explicit`, returning the
+    /// ids of the options the config set
+    pub(super) fn apply_config(
+        &mut self,
+        config: Config,
+        explicit: impl Fn(&str) -> bool,
+    ) -> HashSet<&'static str> {
+        let mut set = HashSet::new();
+        macro_rules! merge {
+            ($wrap:path; $($field:ident),* $(,)?) => {$(
+                if let Some(value) = config.$field {
+                    if !explicit(stringify!($field)) {
+                        self.$field = $wrap(value);
+                        set.insert(stringify!($field));
+                    }
+                }
+            )*};
+        }
+
+        merge!(std::convert::identity;
+            glob, exclude, generated_suffixes, include_generated, parallel,
+            timeout_factor, timeout_policy, warmup, skip_baseline_check,
+            threshold, threshold_high, threshold_low, category_thresholds, fail_on, output, quiet, verbose,
+            summary_detail, killing_tests, non_killing_tests, test_command,
+            test_env, dart_define, skip_asserts, include_print_calls,
+            incremental, cache_file, diff_only, base_ref, ai, no_ai_cache, ollama_model,
+            ollama_url, azure_api_version, ai_max_per_file, ai_retries, html,
+            relative_paths, output_format, json, junit, cobertura, sarif,
+            ai_report, html_offline, report_include_source,
+            report_source_max_lines, json_with_diff, sample_strategy,
+            rerun_killed, confirm_survivors, no_import_check, sandbox,
+            reuse_vm, trend,
+        );
+        merge!(Some;
+            timeout, max_duration, file_thresholds, test_map, preset, sample, seed,
+            max_mutations, max_mutations_per_file, operators, coverage_file, azure_endpoint,
+            azure_deployment, ai_prompt_file, badge_output, ndjson, history_file,
+            concurrency_per_file,
+        );
+
+        set
+    }
+}
+
+/// Deserialize a name using the same spelling as the command line
+fn value_enum<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: ValueEnum,
+{
+    Option::<String>::deserialize(deserializer)?
+        .map(|name| parse_value_enum(&name).map_err(D::Error::custom))
+        .transpose()
+}
+
+/// Deserialize a list of names using the same spelling as the command line
+fn value_enums<'de, D, T>(deserializer: D) -> Result<Option<Vec<T>>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: ValueEnum,
+{
+    Option::<Vec<String>>::deserialize(deserializer)?
+        .map(|names| {
+            names
+                .iter()
+                .map(|name| parse_value_enum(name).map_err(D::Error::custom))
+                .collect()
+        })
+        .transpose()
+}
+
+fn parse_value_enum<T: ValueEnum>(name: &str) -> Result<T, String> {
+    T::from_str(name, true).map_err(|_| {
+        let names: Vec<String> = T::value_variants()
+            .iter()
+            .filter_map(|v| v.to_possible_value())
+            .map(|v| v.get_name().to_owned())
+            .collect();
+        format!(
+            "unknown value `{name}`, expected one of {}",
+            names.join(", ")
+        )
+    })
+}
+
+/// Deserialize a `category: score` map
+fn category_thresholds<'de, D>(
+    deserializer: D,
+) -> Result<Option<Vec<(MutatorCategory, f64)>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Option::<BTreeMap<String, f64>>::deserialize(deserializer)?
+        .map(|map| {
+            map.into_iter()
+                .map(|(name, score)| {
+                    parse_category(&name)
+                        .map(|category| (category, score))
+                        .map_err(D::Error::custom)
+                })
+                .collect()
+        })
+        .transpose()
+}
+
+/// Deserialize a `KEY: VALUE` map into pairs
+fn key_values<'de, D>(deserializer: D) -> Result<Option<Vec<(String, String)>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Ok(
+        Option::<BTreeMap<String, String>>::deserialize(deserializer)?
+            .map(|map| map.into_iter().collect()),
+    )
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    fn resolve(dir: &Path, cli: &[&str]) -> Args {
+        let path = dir.to_str().unwrap();
+        let mut argv = vec!["dart_mutant", "--path", path];
+        argv.extend_from_slice(cli);
+        Args::try_parse_resolved_from(argv).unwrap()
+    }
+
+    #[test]
+    fn test_config_threshold_used_without_cli_flag() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("dart_mutant.yaml"),
+            "threshold: 85\nparallel: 2\nexclude:\n  - lib/legacy/**\n",
+        )
+        .unwrap();
+
+        let args = resolve(dir.path(), &[]);
+
+        assert!((args.threshold - 85.0).abs() < f64::EPSILON);
+        assert_eq!(args.parallel, 2);
+        assert_eq!(args.exclude, vec!["lib/legacy/**".to_owned()]);
+    }
+
+    #[test]
+    fn test_cli_flag_overrides_config() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("dart_mutant.yaml"), "threshold: 85\n").unwrap();
+
+        let args = resolve(dir.path(), &["--threshold", "60"]);
+
+        assert!((args.threshold - 60.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_toml_config_and_enum_names() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("dart_mutant.toml"),
+            "ai = \"azure-openai\"\nfail_on = [\"survived\", \"error\"]\noutput = \"reports\"\n\n\
+             [category_thresholds]\nnull_safety = 90\n",
+        )
+        .unwrap();
+
+        let args = resolve(dir.path(), &[]);
+
+        assert!(matches!(args.ai, AiProvider::AzureOpenAI));
+        assert_eq!(args.fail_on, vec![FailOn::Survived, FailOn::Error]);
+        assert_eq!(args.output, dir.path().join("reports"));
+        assert_eq!(
+            args.category_thresholds,
+            vec![(MutatorCategory::NullSafety, 90.0)]
+        );
+    }
+
+    #[test]
+    fn test_config_operators_override_preset() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("dart_mutant.yaml"),
+            "preset: smoke\noperators: [null_safety]\n",
+        )
+        .unwrap();
+
+        let args = resolve(dir.path(), &[]);
+
+        assert_eq!(args.operators, Some(vec!["null_safety".to_owned()]));
+        assert_eq!(args.timeout, Some(Preset::Smoke.timeout_secs()));
+    }
+
+    #[test]
+    fn test_smoke_preset_targets_tests_and_limits_the_run() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let args = resolve(dir.path(), &["--preset", "smoke"]);
+        assert_eq!(args.test_map, Some(Preset::Smoke.test_map()));
+        assert_eq!(args.max_duration, Some(Preset::Smoke.max_duration_secs()));
+
+        let args = resolve(
+            dir.path(),
+            &[
+                "--preset",
+                "smoke",
+                "--max-duration",
+                "60",
+                "--test-map",
+                "test/{name}_test.dart",
+            ],
+        );
+        assert_eq!(args.max_duration, Some(60));
+        assert_eq!(args.test_map.as_deref(), Some("test/{name}_test.dart"));
+    }
+
+    #[test]
+    fn test_unknown_key_is_an_error() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("dart_mutant.yaml");
+        std::fs::write(&path, "treshold: 80\n").unwrap();
+
+        let err = Config::load(None, dir.path()).unwrap_err();
+
+        assert!(format!("{err:#}").contains("treshold"));
+    }
+}
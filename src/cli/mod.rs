@@ -1,20 +1,126 @@
 //! Command-line interface for dart_mutant
 
-use clap::{Parser, ValueEnum};
+mod config;
+
+use crate::exit::ExitCode;
+use crate::mutators::MutatorCategory;
+use anyhow::Result;
+use clap::parser::ValueSource;
+use clap::{ArgMatches, CommandFactory, FromArgMatches, Parser, ValueEnum};
 use std::path::PathBuf;
 
+/// Language model used to suggest where to mutate
 #[derive(Debug, Clone, Copy, ValueEnum, Default)]
 pub enum AiProvider {
+    /// Place mutations by AST rules alone
     #[default]
     None,
     /// Use Anthropic Claude for smart mutation placement
     Anthropic,
     /// Use OpenAI GPT for smart mutation placement
     OpenAI,
+    /// Use an OpenAI deployment on Azure (see --azure-endpoint)
+    #[value(name = "azure-openai")]
+    AzureOpenAI,
+    /// Use Google Gemini for smart mutation placement
+    Gemini,
     /// Use local Ollama model for smart mutation placement
     Ollama,
 }
 
+/// How timed-out mutants factor into the mutation score
+#[derive(Debug, Clone, Copy, ValueEnum, Default, PartialEq, Eq)]
+pub enum TimeoutPolicy {
+    /// Count timeouts as killed (likely infinite loop)
+    #[default]
+    Killed,
+    /// Exclude timeouts from the mutation score entirely
+    Ignored,
+    /// Count timeouts as errors
+    Error,
+}
+
+/// Outcomes that make the run exit non-zero
+#[derive(Debug, Clone, Copy, ValueEnum, PartialEq, Eq)]
+pub enum FailOn {
+    /// Score below --threshold, --file-thresholds or --category-threshold
+    Score,
+    /// Any mutant survived
+    Survived,
+    /// Any mutant failed to compile or could not be tested
+    Error,
+    /// Any mutant timed out
+    Timeout,
+    /// Score lower than the last run in --history-file
+    Regression,
+}
+
+/// How `--sample` picks mutants
+#[derive(Debug, Clone, Copy, ValueEnum, Default, PartialEq, Eq)]
+pub enum SampleStrategy {
+    /// Uniformly at random across all mutants
+    #[default]
+    Uniform,
+    /// Proportionally per operator category, at least one from each
+    Stratified,
+}
+
+/// Layout of the HTML report
+#[derive(Debug, Clone, Copy, ValueEnum, Default, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// dart_mutant's own themed report
+    #[default]
+    Html,
+    /// Standard Stryker report rendered by `mutation-testing-elements`
+    StrykerHtml,
+}
+
+/// Bundled settings for common use cases
+#[derive(Debug, Clone, Copy, ValueEnum, PartialEq, Eq)]
+pub enum Preset {
+    /// Fast, representative run for CI gating
+    Smoke,
+}
+
+impl Preset {
+    /// Number of mutations to sample from `total` generated mutations
+    pub fn sample_size(self, total: usize) -> usize {
+        match self {
+            Self::Smoke => (total / 10).clamp(25, 200),
+        }
+    }
+
+    fn operators(self) -> Vec<String> {
+        match self {
+            Self::Smoke => ["arithmetic", "comparison", "logical", "boolean"]
+                .iter()
+                .map(|s| (*s).to_string())
+                .collect(),
+        }
+    }
+
+    fn timeout_secs(self) -> u64 {
+        match self {
+            Self::Smoke => 15,
+        }
+    }
+
+    /// Tests to run per mutated file, following the `dart test` layout
+    fn test_map(self) -> String {
+        match self {
+            Self::Smoke => "test/{dir}/{name}_test.dart".to_owned(),
+        }
+    }
+
+    /// Time budget for the whole run
+    fn max_duration_secs(self) -> u64 {
+        match self {
+            Self::Smoke => 600,
+        }
+    }
+}
+
+/// Command-line options, and the configuration for [`crate::run`]
 #[derive(Parser, Debug, Clone)]
 #[command(
     name = "dart_mutant",
@@ -51,8 +157,8 @@ EXAMPLES:
     # Test only a sample of mutations for quick feedback
     dart_mutant --sample 50
 
-    # Incremental mode - only test changed files
-    dart_mutant --incremental --base-ref main
+    # Incremental mode - reuse results for unchanged files
+    dart_mutant --incremental
 "#
 )]
 pub struct Args {
@@ -60,33 +166,120 @@ pub struct Args {
     #[arg(short, long, default_value = ".")]
     pub path: PathBuf,
 
+    /// Config file to load instead of dart_mutant.yaml, dart_mutant.yml or
+    /// dart_mutant.toml in the project root
+    #[arg(long, value_name = "PATH")]
+    pub config: Option<PathBuf>,
+
     /// Glob pattern for files to mutate
     #[arg(short, long, default_value = "lib/**/*.dart")]
     pub glob: String,
 
     /// Glob patterns to exclude
     #[arg(short, long, default_values_t = vec![
-        "**/*.g.dart".to_string(),
-        "**/*.freezed.dart".to_string(),
-        "**/*.mocks.dart".to_string(),
         "**/generated/**".to_string(),
         "**/test/**".to_string(),
         "**/*_test.dart".to_string(),
     ])]
     pub exclude: Vec<String>,
 
+    /// File name suffixes of generated code to skip (can repeat; replaces
+    /// the defaults)
+    #[arg(long = "generated-suffix", value_name = "SUFFIX", default_values_t = vec![
+        ".g.dart".to_string(),
+        ".freezed.dart".to_string(),
+        ".mocks.dart".to_string(),
+    ])]
+    pub generated_suffixes: Vec<String>,
+
+    /// Mutate generated files too, ignoring --generated-suffix
+    #[arg(long)]
+    pub include_generated: bool,
+
     /// Number of parallel mutation test jobs
     #[arg(short = 'j', long, default_value_t = num_cpus())]
     pub parallel: usize,
 
-    /// Timeout per mutation test in seconds
-    #[arg(short, long, default_value = "30")]
-    pub timeout: u64,
+    /// Test in a private copy of the project per parallel job, so mutants
+    /// of the same file run at once and the project itself is never modified
+    #[arg(long)]
+    pub sandbox: bool,
+
+    /// With --sandbox, how many mutants of one file are tested at once
+    /// [default: --parallel]. Without --sandbox it is always one
+    #[arg(long, value_name = "N")]
+    pub concurrency_per_file: Option<usize>,
+
+    /// Keep a Dart VM running per parallel job and run each mutant's test
+    /// files in it, instead of starting `dart test` for every mutant
+    #[arg(long)]
+    pub reuse_vm: bool,
+
+    /// Timeout per mutation test in seconds [default: baseline test run
+    /// duration × --timeout-factor]
+    #[arg(short, long)]
+    pub timeout: Option<u64>,
+
+    /// Multiple of the baseline test run used as the per-mutant timeout
+    /// when `--timeout` is not given
+    #[arg(long, default_value = "3.0", value_name = "FACTOR")]
+    pub timeout_factor: f64,
+
+    /// Wall-clock budget for the whole run in seconds. Mutants not started
+    /// in time are reported as pending
+    #[arg(long, value_name = "SECS")]
+    pub max_duration: Option<u64>,
+
+    /// How timed-out mutants count towards the mutation score
+    #[arg(long, value_enum, default_value = "killed")]
+    pub timeout_policy: TimeoutPolicy,
+
+    /// Run the test suite once before mutating to warm pub and build caches
+    #[arg(long)]
+    pub warmup: bool,
+
+    /// Test mutants even when the tests fail on the unmodified code
+    #[arg(long)]
+    pub skip_baseline_check: bool,
 
     /// Minimum mutation score threshold (0-100)
     #[arg(long, default_value = "0")]
     pub threshold: f64,
 
+    /// Score from which the badge and JSON report count as good (0-100)
+    #[arg(long, default_value_t = 80, value_parser = clap::value_parser!(u32).range(0..=100))]
+    pub threshold_high: u32,
+
+    /// Score below which the badge and JSON report count as poor (0-100)
+    #[arg(long, default_value_t = 60, value_parser = clap::value_parser!(u32).range(0..=100))]
+    pub threshold_low: u32,
+
+    /// Minimum score per operator category, e.g. arithmetic=80,null_safety=90
+    #[arg(
+        long = "category-threshold",
+        value_name = "CATEGORY=SCORE",
+        value_delimiter = ',',
+        value_parser = parse_category_threshold
+    )]
+    pub category_thresholds: Vec<(MutatorCategory, f64)>,
+
+    /// When to exit non-zero: score, survived, error, timeout, regression
+    /// (comma-separated)
+    #[arg(long, value_enum, value_delimiter = ',', default_value = "score")]
+    pub fail_on: Vec<FailOn>,
+
+    /// JSON file of per-glob score thresholds, e.g. {"lib/payments/**": 90}
+    #[arg(long, value_name = "PATH")]
+    pub file_thresholds: Option<PathBuf>,
+
+    /// Append each run's totals and score to PATH, one JSON line per run
+    #[arg(long, value_name = "PATH")]
+    pub history_file: Option<PathBuf>,
+
+    /// Compare the score with the last run in --history-file
+    #[arg(long)]
+    pub trend: bool,
+
     /// Output directory for reports
     #[arg(short, long, default_value = "./mutation-reports")]
     pub output: PathBuf,
@@ -95,51 +288,157 @@ pub struct Args {
     #[arg(long)]
     pub dry_run: bool,
 
-    /// Quiet mode - minimal output
-    #[arg(short, long)]
+    /// Check that each mutant compiles, without running tests, and report
+    /// the ones that don't by operator (for validating mutation operators)
+    #[arg(long, conflicts_with_all = ["dry_run", "watch"])]
+    pub verify_mutations: bool,
+
+    /// Quiet mode: no banner or progress, only the final score line
+    #[arg(short, long, conflicts_with = "verbose")]
     pub quiet: bool,
 
-    /// Verbose output
+    /// Verbose output: debug logging, including each mutant's status
     #[arg(short, long)]
     pub verbose: bool,
 
-    /// Test command to run (default: dart test)
-    #[arg(long, default_value = "dart test")]
+    /// Print a per-file table (killed/total, score, operator with the most
+    /// survivors) before the summary
+    #[arg(long)]
+    pub summary_detail: bool,
+
+    /// Only failures of tests whose names match these globs count as kills
+    #[arg(long, value_name = "GLOB")]
+    pub killing_tests: Vec<String>,
+
+    /// Failures of tests whose names match these globs never count as kills
+    #[arg(long, value_name = "GLOB")]
+    pub non_killing_tests: Vec<String>,
+
+    /// Rerun killed mutants up to N times; a mutant that passes any rerun
+    /// is reported as survived (guards against flaky failures)
+    #[arg(long, default_value = "0", value_name = "N")]
+    pub rerun_killed: u32,
+
+    /// Rerun surviving mutants once; one that fails the rerun is reported as
+    /// killed (guards against flaky passes)
+    #[arg(long)]
+    pub confirm_survivors: bool,
+
+    /// After the run, keep watching the project and re-test the mutants of
+    /// each saved Dart file until interrupted
+    #[arg(long)]
+    pub watch: bool,
+
+    /// Explain a single mutation by id (from a report) and exit
+    #[arg(long, value_name = "MUTATION_ID")]
+    pub explain: Option<String>,
+
+    /// List every mutation operator by `--operators` category and exit
+    #[arg(long)]
+    pub list_operators: bool,
+
+    /// Test command to run, split with shell quoting rules. `--reporter` is
+    /// only appended to plain `dart test` without a reporter of its own
+    #[arg(long, default_value = "dart test", value_name = "COMMAND")]
     pub test_command: String,
 
+    /// Environment variable for the test process (can repeat)
+    #[arg(long, value_name = "KEY=VALUE", value_parser = parse_key_value)]
+    pub test_env: Vec<(String, String)>,
+
+    /// Appended to the test command as `--dart-define=KEY=VALUE` (can repeat)
+    #[arg(long, value_name = "KEY=VALUE", value_parser = parse_key_value)]
+    pub dart_define: Vec<(String, String)>,
+
+    /// Run only the tests mapped to each mutated file, e.g.
+    /// `test/{dir}/{name}_test.dart` (`{dir}` is relative to `lib/`). Falls
+    /// back to the whole suite when no test file matches
+    #[arg(long, value_name = "PATTERN")]
+    pub test_map: Option<String>,
+
+    /// Apply a preset (explicit flags override preset values)
+    #[arg(long, value_enum)]
+    pub preset: Option<Preset>,
+
     /// Sample number of mutations to test (0 = all)
     #[arg(long)]
     pub sample: Option<usize>,
 
+    /// How --sample picks mutants
+    #[arg(long, value_enum, default_value = "uniform")]
+    pub sample_strategy: SampleStrategy,
+
+    /// Seed for --sample, so runs pick the same mutants [default: random,
+    /// printed in the summary]
+    #[arg(long, value_name = "SEED")]
+    pub seed: Option<u64>,
+
+    /// Hard cap on mutations tested, applied deterministically across files
+    #[arg(long)]
+    pub max_mutations: Option<usize>,
+
+    /// Cap the mutations generated for any one file, keeping N spread evenly
+    /// through it
+    #[arg(long, value_name = "N")]
+    pub max_mutations_per_file: Option<usize>,
+
     /// Mutation operators to use (default: all)
     #[arg(long, value_delimiter = ',')]
     pub operators: Option<Vec<String>>,
 
+    /// Don't mutate expressions inside `assert(...)` clauses
+    #[arg(long)]
+    pub skip_asserts: bool,
+
+    /// Also remove `print(...)` and `debugPrint(...)` call statements
+    #[arg(long)]
+    pub include_print_calls: bool,
+
     /// Only mutate lines covered by tests (requires coverage file)
     #[arg(long)]
     pub coverage_file: Option<PathBuf>,
 
-    /// Generate incremental results (cache killed/survived status)
+    /// Test mutants in files no test imports, instead of reporting them as
+    /// no coverage (only applies without --coverage-file)
+    #[arg(long)]
+    pub no_import_check: bool,
+
+    /// Reuse cached results for mutants in files unchanged since the last run
     #[arg(long)]
     pub incremental: bool,
 
-    /// Path to incremental cache file
+    /// Path to incremental cache file, relative to the project path
     #[arg(long, default_value = ".dart_mutant_cache")]
     pub cache_file: PathBuf,
 
-    /// Git base ref for incremental mode
+    /// Only mutate lines changed since the working tree diverged from
+    /// --base-ref, including uncommitted and untracked changes
+    #[arg(long)]
+    pub diff_only: bool,
+
+    /// Git ref that --diff-only compares against
     #[arg(long, default_value = "main")]
     pub base_ref: String,
 
+    /// Only test the mutants that survived in a previous JSON report.
+    /// Mutants in files edited since are matched by operator and line
+    #[arg(long, value_name = "REPORT_JSON")]
+    pub only_survivors: Option<PathBuf>,
+
     // ===== AI-Powered Mutations =====
     /// Enable AI-powered smart mutation placement
     #[arg(long, value_enum, default_value = "none")]
     pub ai: AiProvider,
 
-    /// API key for AI provider (or set ANTHROPIC_API_KEY / OPENAI_API_KEY env var)
+    /// API key for AI provider (or set ANTHROPIC_API_KEY / OPENAI_API_KEY / GEMINI_API_KEY env var)
     #[arg(long, env = "DART_MUTANT_AI_KEY")]
     pub ai_key: Option<String>,
 
+    /// Always ask the AI provider, ignoring suggestions cached for unchanged
+    /// files
+    #[arg(long)]
+    pub no_ai_cache: bool,
+
     /// Ollama model name (for --ai ollama)
     #[arg(long, default_value = "codellama")]
     pub ollama_model: String,
@@ -148,15 +447,49 @@ pub struct Args {
     #[arg(long, default_value = "http://localhost:11434")]
     pub ollama_url: String,
 
+    /// Azure OpenAI resource endpoint, e.g. https://my-resource.openai.azure.com
+    #[arg(long, env = "AZURE_OPENAI_ENDPOINT", value_name = "URL")]
+    pub azure_endpoint: Option<String>,
+
+    /// Azure OpenAI deployment name (for --ai azure-openai)
+    #[arg(long, env = "AZURE_OPENAI_DEPLOYMENT", value_name = "NAME")]
+    pub azure_deployment: Option<String>,
+
+    /// Azure OpenAI REST API version
+    #[arg(long, default_value = "2024-02-01", value_name = "VERSION")]
+    pub azure_api_version: String,
+
     /// Maximum number of AI-suggested mutations per file
     #[arg(long, default_value = "10")]
     pub ai_max_per_file: usize,
 
+    /// Retries for rate-limited or failing AI API calls, with exponential
+    /// backoff
+    #[arg(long, default_value = "3", value_name = "N")]
+    pub ai_retries: u32,
+
+    /// Prompt template for AI suggestions, with `{source}` (the Dart file)
+    /// and `{max}` (suggestions per file) placeholders
+    #[arg(long, value_name = "PATH")]
+    pub ai_prompt_file: Option<PathBuf>,
+
     // ===== Report Options =====
     /// Generate HTML report
     #[arg(long, default_value_t = true)]
     pub html: bool,
 
+    /// Skip the HTML report
+    #[arg(long)]
+    pub no_html: bool,
+
+    /// Show file paths in reports relative to the project path
+    #[arg(long, default_value_t = true, action = clap::ArgAction::Set)]
+    pub relative_paths: bool,
+
+    /// Layout of the HTML report
+    #[arg(long, value_enum, default_value = "html")]
+    pub output_format: OutputFormat,
+
     /// Generate JSON report (Stryker-compatible format)
     #[arg(long)]
     pub json: bool,
@@ -165,15 +498,95 @@ pub struct Args {
     #[arg(long)]
     pub junit: bool,
 
+    /// Generate a Cobertura-style XML report mapping mutation results onto
+    /// coverage (an approximation, not line coverage)
+    #[arg(long)]
+    pub cobertura: bool,
+
+    /// Generate a SARIF report of surviving mutants for GitHub code scanning
+    #[arg(long)]
+    pub sarif: bool,
+
     /// Generate AI-optimized markdown report for LLM consumption
     #[arg(long)]
     pub ai_report: bool,
 
+    /// Write a shields.io endpoint badge JSON with the mutation score
+    #[arg(long, value_name = "PATH")]
+    pub badge_output: Option<PathBuf>,
+
+    /// Append one JSON line per mutant to PATH as soon as it is tested, so
+    /// results can be followed live and survive a killed run
+    #[arg(long, value_name = "PATH")]
+    pub ndjson: Option<PathBuf>,
+
+    /// Write a markdown diff report comparing scores against a baseline JSON report
+    #[arg(long, value_name = "BASELINE_JSON")]
+    pub diff_report: Option<PathBuf>,
+
+    /// Don't load web fonts in the HTML report, so it renders the same
+    /// without network access
+    #[arg(long)]
+    pub html_offline: bool,
+
+    /// Embed the full source of each mutated file in the HTML report
+    #[arg(long)]
+    pub report_include_source: bool,
+
+    /// Skip embedding source for files longer than this many lines
+    #[arg(long, default_value = "2000")]
+    pub report_source_max_lines: usize,
+
+    /// Add a unified diff of each mutant to the JSON report
+    #[arg(long)]
+    pub json_with_diff: bool,
+
     /// Open HTML report in browser after completion
     #[arg(long)]
     pub open: bool,
 }
 
+/// Parse a `KEY=VALUE` pair; the value may itself contain `=`
+fn parse_key_value(s: &str) -> Result<(String, String), String> {
+    match s.split_once('=') {
+        Some((key, value)) if !key.is_empty() => Ok((key.to_owned(), value.to_owned())),
+        _ => Err(format!("expected KEY=VALUE, got `{s}`")),
+    }
+}
+
+/// Parse a `CATEGORY=SCORE` pair, e.g. `null_safety=90`
+fn parse_category_threshold(s: &str) -> Result<(MutatorCategory, f64), String> {
+    let (name, score) = s
+        .split_once('=')
+        .ok_or_else(|| format!("expected CATEGORY=SCORE, got `{s}`"))?;
+    let category = parse_category(name.trim())?;
+    let score: f64 = score
+        .trim()
+        .parse()
+        .map_err(|_| format!("invalid score `{score}` for {name}"))?;
+    Ok((category, score))
+}
+
+fn parse_category(name: &str) -> Result<MutatorCategory, String> {
+    MutatorCategory::from_str(name).ok_or_else(|| {
+        let names: Vec<&str> = MutatorCategory::ALL.iter().map(|c| c.as_str()).collect();
+        format!(
+            "unknown category `{name}`, expected one of {}",
+            names.join(", ")
+        )
+    })
+}
+
+/// Print a clap error or help text and exit. Usage errors exit with
+/// [`ExitCode::Error`] rather than clap's 2, which means no Dart files
+fn exit_on_clap_error(error: &clap::Error) -> ! {
+    if error.use_stderr() {
+        error.print().ok();
+        std::process::exit(ExitCode::Error.code());
+    }
+    error.exit()
+}
+
 fn num_cpus() -> usize {
     std::thread::available_parallelism()
         .map(|n| n.get())
@@ -181,15 +594,80 @@ fn num_cpus() -> usize {
 }
 
 impl Args {
+    /// Parse command-line arguments, then merge in the config file and any
+    /// `--preset`. Exits on `--help`, `--version` and invalid arguments
+    pub fn parse_resolved() -> Result<Self> {
+        let matches = Self::command()
+            .try_get_matches()
+            .unwrap_or_else(|e| exit_on_clap_error(&e));
+        let args = Self::from_arg_matches(&matches).unwrap_or_else(|e| exit_on_clap_error(&e));
+        args.resolve(&matches)
+    }
+
+    /// Like [`Args::parse_resolved`], but from the given arguments (the
+    /// first is the program name) and returning errors instead of exiting
+    pub fn try_parse_resolved_from<I, T>(argv: I) -> Result<Self>
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<std::ffi::OsString> + Clone,
+    {
+        let matches = Self::command().try_get_matches_from(argv)?;
+        let args = Self::from_arg_matches(&matches)?;
+        args.resolve(&matches)
+    }
+
+    /// Apply the config file, then the preset, to options not given on the
+    /// command line or via the environment
+    fn resolve(mut self, matches: &ArgMatches) -> Result<Self> {
+        let explicit = |id: &str| {
+            matches!(
+                matches.value_source(id),
+                Some(ValueSource::CommandLine | ValueSource::EnvVariable)
+            )
+        };
+
+        let from_config = match config::Config::load(self.config.as_deref(), &self.path)? {
+            Some(config) => self.apply_config(config, explicit),
+            None => std::collections::HashSet::new(),
+        };
+
+        Ok(self.resolve_preset(|id| explicit(id) || from_config.contains(id)))
+    }
+
+    /// Fill in preset values for any option not set explicitly
+    fn resolve_preset(mut self, explicit: impl Fn(&str) -> bool) -> Self {
+        let Some(preset) = self.preset else {
+            return self;
+        };
+
+        if !explicit("operators") {
+            self.operators = Some(preset.operators());
+        }
+        if !explicit("timeout") {
+            self.timeout = Some(preset.timeout_secs());
+        }
+        if !explicit("test_map") {
+            self.test_map = Some(preset.test_map());
+        }
+        if !explicit("max_duration") {
+            self.max_duration = Some(preset.max_duration_secs());
+        }
+
+        self
+    }
+
     /// Check if AI mutation suggestions are enabled
     pub fn is_ai_enabled(&self) -> bool {
         !matches!(self.ai, AiProvider::None)
     }
 
+    /// `--ai-key`, or the provider's API key environment variable
     pub fn get_ai_api_key(&self) -> Option<String> {
         self.ai_key.clone().or_else(|| match self.ai {
             AiProvider::Anthropic => std::env::var("ANTHROPIC_API_KEY").ok(),
             AiProvider::OpenAI => std::env::var("OPENAI_API_KEY").ok(),
+            AiProvider::AzureOpenAI => std::env::var("AZURE_OPENAI_API_KEY").ok(),
+            AiProvider::Gemini => std::env::var("GEMINI_API_KEY").ok(),
             AiProvider::Ollama | AiProvider::None => None,
         })
     }
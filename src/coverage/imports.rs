@@ -0,0 +1,187 @@
+//! Files reachable from the tests through `import`, `export` and `part`
+//!
+//! A static stand-in for coverage data: a mutant in a file no test
+//! transitively imports can't be killed, so it is reported as `NoCoverage`
+//! without running the suite.
+
+use crate::mutation::Mutation;
+use crate::parser;
+use serde::Deserialize;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+/// Directories whose Dart files are test entry points
+const TEST_DIRS: &[&str] = &["test", "integration_test"];
+
+/// Canonical paths of every project file some test imports
+#[derive(Debug, Clone, Default)]
+pub struct TestImports {
+    reachable: HashSet<PathBuf>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Pubspec {
+    name: String,
+}
+
+impl TestImports {
+    /// Follow directives from every test file in `project_root`. `None` when
+    /// there are no test files to start from, since then nothing is known
+    pub fn scan(project_root: &Path) -> Option<Self> {
+        if !project_root.is_dir() {
+            return None;
+        }
+        let package = std::fs::read_to_string(project_root.join("pubspec.yaml"))
+            .ok()
+            .and_then(|text| serde_yaml::from_str::<Pubspec>(&text).ok())
+            .map(|pubspec| pubspec.name);
+        let lib_dir = project_root.join("lib");
+
+        let mut pending: Vec<PathBuf> = TEST_DIRS
+            .iter()
+            .flat_map(|dir| WalkDir::new(project_root.join(dir)).sort_by_file_name())
+            .filter_map(Result::ok)
+            .map(walkdir::DirEntry::into_path)
+            .filter(|path| path.extension().is_some_and(|ext| ext == "dart"))
+            .filter_map(|path| path.canonicalize().ok())
+            .collect();
+        if pending.is_empty() {
+            return None;
+        }
+
+        let mut reachable = HashSet::new();
+        while let Some(file) = pending.pop() {
+            if !reachable.insert(file.clone()) {
+                continue;
+            }
+            let Ok(source) = std::fs::read_to_string(&file) else {
+                continue;
+            };
+            let dir = file.parent().unwrap_or(&file);
+            pending.extend(
+                directive_uris(&source)
+                    .iter()
+                    .filter_map(|uri| resolve_uri(uri, dir, &lib_dir, package.as_deref()))
+                    .filter_map(|path| path.canonicalize().ok())
+                    .filter(|path| !reachable.contains(path)),
+            );
+        }
+
+        Some(Self { reachable })
+    }
+
+    /// Whether some test imports the file the mutation is in
+    pub fn is_imported(&self, mutation: &Mutation) -> bool {
+        let file = &mutation.location.file;
+        file.canonicalize()
+            .map_or(false, |canonical| self.reachable.contains(&canonical))
+    }
+}
+
+/// URIs of the `import`, `export` and `part` directives in a file, including
+/// every branch of a conditional import. `part of` points back at the
+/// library, so it isn't followed
+fn directive_uris(source: &str) -> Vec<String> {
+    let Ok(tree) = parser::parse_dart(source) else {
+        return Vec::new();
+    };
+    let root = tree.root_node();
+    let mut uris = Vec::new();
+    let mut cursor = root.walk();
+    for directive in root.children(&mut cursor) {
+        if !matches!(directive.kind(), "import_or_export" | "part_directive") {
+            continue;
+        }
+        collect_uris(directive, source, &mut uris);
+    }
+    uris
+}
+
+fn collect_uris(node: tree_sitter::Node<'_>, source: &str, uris: &mut Vec<String>) {
+    if node.kind() == "uri" {
+        if let Ok(text) = node.utf8_text(source.as_bytes()) {
+            uris.push(text.trim_matches(|c| c == '\'' || c == '"').to_owned());
+        }
+        return;
+    }
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        collect_uris(child, source, uris);
+    }
+}
+
+/// Path a directive URI refers to, if it is inside this project.
+/// `package:<own name>/x.dart` lives at `lib/x.dart`; `dart:` and other
+/// packages are outside the project
+fn resolve_uri(uri: &str, dir: &Path, lib_dir: &Path, package: Option<&str>) -> Option<PathBuf> {
+    if let Some(rest) = uri.strip_prefix("package:") {
+        let (name, path) = rest.split_once('/')?;
+        return (Some(name) == package).then(|| lib_dir.join(path));
+    }
+    if uri.contains(':') {
+        return None;
+    }
+    Some(dir.join(uri))
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::mutation::testing::mutant;
+
+    fn mutation_in(file: PathBuf) -> Mutation {
+        mutant().file(file).mutation()
+    }
+
+    #[test]
+    fn test_follows_imports_exports_and_parts_from_tests() {
+        let dir = tempfile::tempdir().unwrap();
+        for (file, source) in [
+            ("pubspec.yaml", "name: app\n"),
+            (
+                "test/app_test.dart",
+                "import 'package:app/app.dart';\nimport '../lib/direct.dart';\nimport 'package:test/test.dart';\n",
+            ),
+            ("lib/app.dart", "export 'src/model.dart';\n"),
+            (
+                "lib/src/model.dart",
+                "import 'io.dart' if (dart.library.html) 'web.dart';\npart 'model.g.dart';\n",
+            ),
+            ("lib/src/model.g.dart", "part of 'model.dart';\n"),
+            ("lib/src/io.dart", "int x = 1;\n"),
+            ("lib/src/web.dart", "int x = 1;\n"),
+            ("lib/direct.dart", "int x = 1;\n"),
+            ("lib/orphan.dart", "import 'app.dart';\n"),
+        ] {
+            let path = dir.path().join(file);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, source).unwrap();
+        }
+
+        let imports = TestImports::scan(dir.path()).unwrap();
+        let imported = |file: &str| imports.is_imported(&mutation_in(dir.path().join(file)));
+
+        for file in [
+            "lib/app.dart",
+            "lib/src/model.dart",
+            "lib/src/model.g.dart",
+            "lib/src/io.dart",
+            "lib/src/web.dart",
+            "lib/direct.dart",
+        ] {
+            assert!(imported(file), "{file} should be reachable");
+        }
+        assert!(!imported("lib/orphan.dart"));
+    }
+
+    #[test]
+    fn test_no_test_files_means_unknown() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("lib")).unwrap();
+        std::fs::write(dir.path().join("lib/app.dart"), "int x = 1;\n").unwrap();
+
+        assert!(TestImports::scan(dir.path()).is_none());
+    }
+}
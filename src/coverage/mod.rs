@@ -0,0 +1,194 @@
+//! Line coverage from `dart test --coverage`
+//!
+//! Reads either an LCOV file (`format_coverage --lcov`) or the raw coverage
+//! JSON written by `dart test --coverage`, so mutants on lines no test runs
+//! can be reported as `NoCoverage` without running the tests.
+
+pub mod imports;
+
+use crate::mutation::Mutation;
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+/// Covered lines per project-relative file
+#[derive(Debug, Clone, Default)]
+pub struct Coverage {
+    covered: HashMap<PathBuf, HashSet<usize>>,
+    roots: Vec<PathBuf>,
+}
+
+/// One entry of `dart test --coverage` JSON
+#[derive(Debug, Deserialize)]
+struct JsonCoverageEntry {
+    source: String,
+    #[serde(default)]
+    hits: Vec<serde_json::Value>,
+}
+
+#[derive(Debug, Deserialize)]
+struct JsonCoverage {
+    coverage: Vec<JsonCoverageEntry>,
+}
+
+impl Coverage {
+    /// Load an LCOV or coverage JSON file. Paths in it are resolved
+    /// relative to `project_root`.
+    pub fn load(path: &Path, project_root: &Path) -> Result<Self> {
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read coverage file: {}", path.display()))?;
+        Self::parse(&text, project_root)
+            .with_context(|| format!("Failed to parse coverage file: {}", path.display()))
+    }
+
+    fn parse(text: &str, project_root: &Path) -> Result<Self> {
+        let mut roots = vec![project_root.to_path_buf()];
+        if let Ok(canonical) = project_root.canonicalize() {
+            roots.push(canonical);
+        }
+        let mut coverage = Self {
+            covered: HashMap::new(),
+            roots,
+        };
+
+        let hits = if text.trim_start().starts_with('{') {
+            parse_json(text)?
+        } else {
+            parse_lcov(text)
+        };
+        for (source, lines) in hits {
+            let file = coverage.relative(&source_path(&source));
+            coverage.covered.entry(file).or_default().extend(lines);
+        }
+        Ok(coverage)
+    }
+
+    /// Whether a test executes the line a mutation starts on. Files missing
+    /// from the coverage data were never loaded by a test.
+    pub fn is_covered(&self, mutation: &Mutation) -> bool {
+        self.covered
+            .get(&self.relative(&mutation.location.file))
+            .is_some_and(|lines| lines.contains(&mutation.location.start_line))
+    }
+
+    fn relative(&self, path: &Path) -> PathBuf {
+        self.roots
+            .iter()
+            .find_map(|root| path.strip_prefix(root).ok())
+            .unwrap_or(path)
+            .to_path_buf()
+    }
+}
+
+/// Map a coverage source to a file path: `package:name/x.dart` lives at
+/// `lib/x.dart`, and `file://` URIs are plain paths
+fn source_path(source: &str) -> PathBuf {
+    if let Some(rest) = source.strip_prefix("package:") {
+        let within_package = rest.split_once('/').map_or(rest, |(_, path)| path);
+        return Path::new("lib").join(within_package);
+    }
+    PathBuf::from(source.strip_prefix("file://").unwrap_or(source))
+}
+
+/// Lines with a non-zero hit count per `SF:` record
+fn parse_lcov(text: &str) -> Vec<(String, HashSet<usize>)> {
+    let mut files = Vec::new();
+    let mut current: Option<(String, HashSet<usize>)> = None;
+
+    for line in text.lines().map(str::trim) {
+        if let Some(source) = line.strip_prefix("SF:") {
+            files.extend(current.take());
+            current = Some((source.to_owned(), HashSet::new()));
+        } else if let Some(data) = line.strip_prefix("DA:") {
+            let mut fields = data.split(',');
+            let line_number = fields.next().and_then(|f| f.parse::<usize>().ok());
+            let count = fields.next().and_then(|f| f.parse::<u64>().ok());
+            if let (Some((_, lines)), Some(line_number), Some(count)) =
+                (current.as_mut(), line_number, count)
+            {
+                if count > 0 {
+                    lines.insert(line_number);
+                }
+            }
+        } else if line == "end_of_record" {
+            files.extend(current.take());
+        }
+    }
+    files.extend(current);
+    files
+}
+
+/// `hits` is a flat list of `line, count` pairs, where `line` may also be a
+/// `"start-end"` range
+fn parse_json(text: &str) -> Result<Vec<(String, HashSet<usize>)>> {
+    let report: JsonCoverage = serde_json::from_str(text)?;
+    Ok(report
+        .coverage
+        .into_iter()
+        .map(|entry| {
+            let mut lines = HashSet::new();
+            for pair in entry.hits.chunks(2) {
+                let [line, count] = pair else { continue };
+                if count.as_u64().unwrap_or(0) == 0 {
+                    continue;
+                }
+                if let Some(line) = line.as_u64() {
+                    lines.insert(line as usize);
+                } else if let Some((start, end)) = line.as_str().and_then(|r| r.split_once('-')) {
+                    if let (Ok(start), Ok(end)) = (start.parse::<usize>(), end.parse::<usize>()) {
+                        lines.extend(start..=end);
+                    }
+                }
+            }
+            (entry.source, lines)
+        })
+        .collect())
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::mutation::testing::mutant;
+
+    fn mutation_at(file: &str, line: usize) -> Mutation {
+        mutant().file(file).line(line).mutation()
+    }
+
+    #[test]
+    fn test_lcov_covered_lines() {
+        let lcov = "\
+SF:/project/lib/calc.dart
+DA:3,4
+DA:4,0
+end_of_record
+SF:lib/other.dart
+DA:10,1
+end_of_record
+";
+        let coverage = Coverage::parse(lcov, Path::new("/project")).unwrap();
+
+        assert!(coverage.is_covered(&mutation_at("/project/lib/calc.dart", 3)));
+        assert!(!coverage.is_covered(&mutation_at("/project/lib/calc.dart", 4)));
+        assert!(coverage.is_covered(&mutation_at("/project/lib/other.dart", 10)));
+        assert!(!coverage.is_covered(&mutation_at("/project/lib/untested.dart", 1)));
+    }
+
+    #[test]
+    fn test_json_covered_lines() {
+        let json = r#"{
+            "type": "CodeCoverage",
+            "coverage": [
+                { "source": "package:calc/calc.dart", "hits": [3, 2, 4, 0, "7-9", 1] },
+                { "source": "dart:core", "hits": [1, 1] }
+            ]
+        }"#;
+        let coverage = Coverage::parse(json, Path::new("/project")).unwrap();
+
+        assert!(coverage.is_covered(&mutation_at("/project/lib/calc.dart", 3)));
+        assert!(!coverage.is_covered(&mutation_at("/project/lib/calc.dart", 4)));
+        assert!(coverage.is_covered(&mutation_at("/project/lib/calc.dart", 8)));
+        assert!(Coverage::parse("{ not json", Path::new("/project")).is_err());
+    }
+}
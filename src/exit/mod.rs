@@ -0,0 +1,123 @@
+//! Exit codes of the `dart_mutant` binary
+//!
+//! CI scripts branch on these, so a low score, a project with nothing to
+//! test, a red baseline and a crash each get their own code. Errors that
+//! need a specific code are raised as a [`RunError`]; anything else exits
+//! with [`ExitCode::Error`].
+
+use std::fmt;
+use std::path::PathBuf;
+
+/// Why the binary exited
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExitCode {
+    /// The run passed its `--fail-on` conditions
+    Success,
+    /// A `--fail-on` condition was met, by default a score below
+    /// `--threshold`
+    FailOn,
+    /// No Dart files were found to mutate
+    NoDartFiles,
+    /// The tests fail on the unmodified code
+    BaselineFailed,
+    /// Any other error: invalid arguments or config, I/O failures, crashes
+    Error,
+}
+
+impl ExitCode {
+    /// The process exit status
+    pub const fn code(self) -> i32 {
+        match self {
+            Self::Success => 0,
+            Self::FailOn => 1,
+            Self::NoDartFiles => 2,
+            Self::BaselineFailed => 3,
+            Self::Error => 4,
+        }
+    }
+
+    /// The exit code for an error returned by a run
+    pub fn from_error(error: &anyhow::Error) -> Self {
+        match error.downcast_ref::<RunError>() {
+            Some(RunError::NoDartFiles { .. }) => Self::NoDartFiles,
+            Some(RunError::BaselineFailed { .. }) => Self::BaselineFailed,
+            None => Self::Error,
+        }
+    }
+}
+
+/// Errors that end a run with their own [`ExitCode`]
+#[derive(Debug)]
+pub enum RunError {
+    /// Discovery found no Dart files under the project path
+    NoDartFiles {
+        /// The `--path` searched
+        path: PathBuf,
+    },
+    /// The test command fails before any mutant is applied
+    BaselineFailed {
+        /// The test command as shown to the user
+        command: String,
+        /// Its exit code
+        exit_code: i32,
+        /// Its output, possibly empty
+        output: String,
+    },
+}
+
+impl fmt::Display for RunError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NoDartFiles { path } => {
+                write!(f, "No Dart files found in {}", path.display())
+            }
+            Self::BaselineFailed {
+                command,
+                exit_code,
+                output,
+            } => {
+                write!(
+                    f,
+                    "The tests fail on the unmodified code ({command} exited with {exit_code}).\n\
+                     Mutation testing requires a green baseline: with failing tests every mutant \
+                     would count as killed. Fix the failures, or pass --skip-baseline-check to \
+                     run anyway."
+                )?;
+                if !output.is_empty() {
+                    write!(f, "\n\n{output}")?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl std::error::Error for RunError {}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use anyhow::Context;
+
+    #[test]
+    fn test_errors_map_to_their_exit_codes() {
+        let no_files = anyhow::Error::new(RunError::NoDartFiles {
+            path: PathBuf::from("app"),
+        });
+        assert_eq!(ExitCode::from_error(&no_files).code(), 2);
+
+        // Still found under added context
+        let baseline: anyhow::Result<()> = Err(RunError::BaselineFailed {
+            command: "dart test".to_owned(),
+            exit_code: 1,
+            output: String::new(),
+        }
+        .into());
+        let baseline = baseline.context("Run failed").unwrap_err();
+        assert_eq!(ExitCode::from_error(&baseline), ExitCode::BaselineFailed);
+
+        let other = anyhow::anyhow!("Failed to read file");
+        assert_eq!(ExitCode::from_error(&other).code(), 4);
+    }
+}
@@ -0,0 +1,190 @@
+//! Mutation score history for `--history-file` and `--trend`
+//!
+//! Each run appends one JSON line with its totals and score, so the file
+//! can be cached or committed in CI and read by other tools. The last line
+//! is read before appending, so `--trend` and `--fail-on regression` compare
+//! the run with the one before it. Runs limited to a subset of the mutants,
+//! such as `--diff-only` or `--sample`, record their scope and are only
+//! compared with earlier runs of the same scope.
+
+use crate::report::MutationResult;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fmt::Write as _;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::Path;
+
+/// One run in the history file
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HistoryRecord {
+    /// When the run finished
+    pub timestamp: DateTime<Utc>,
+    /// `HEAD` of the project's git repository, if it has one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub commit: Option<String>,
+    /// Mutants tested, or reported without testing
+    pub total: usize,
+    /// Mutants a test failed on
+    pub killed: usize,
+    /// Mutants every test passed on
+    pub survived: usize,
+    /// Mutation score in percent
+    pub score: f64,
+    /// Options that limited the run to a subset of the mutants, e.g.
+    /// `--diff-only main`; `None` for a full run
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scope: Option<String>,
+}
+
+impl HistoryRecord {
+    /// A record of `result`, finishing now
+    pub fn new(result: &MutationResult, commit: Option<String>, scope: Option<String>) -> Self {
+        Self {
+            timestamp: Utc::now(),
+            commit,
+            total: result.total,
+            killed: result.killed,
+            survived: result.survived,
+            score: result.mutation_score,
+            scope,
+        }
+    }
+
+    /// Short description for the terminal, e.g. `82.5% on 2024-05-01 at 3f9c2e1`
+    pub fn describe(&self) -> String {
+        let mut text = format!(
+            "{:.1}% on {}",
+            self.score,
+            self.timestamp.format("%Y-%m-%d")
+        );
+        if let Some(commit) = &self.commit {
+            let short: String = commit.chars().take(7).collect();
+            let _ = write!(text, " at {short}");
+        }
+        text
+    }
+}
+
+/// The last record in the history file with the given scope, or `None` if
+/// there is none yet
+pub fn last_record(path: &Path, scope: Option<&str>) -> Result<Option<HistoryRecord>> {
+    let text = match std::fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => {
+            return Err(e)
+                .with_context(|| format!("Failed to read history file: {}", path.display()))
+        }
+    };
+    for line in text.lines().rev().filter(|line| !line.trim().is_empty()) {
+        let record: HistoryRecord = serde_json::from_str(line)
+            .with_context(|| format!("Failed to parse history file: {}", path.display()))?;
+        if record.scope.as_deref() == scope {
+            return Ok(Some(record));
+        }
+    }
+    Ok(None)
+}
+
+/// Append `record` as a new line, creating the file if needed
+pub fn append(path: &Path, record: &HistoryRecord) -> Result<()> {
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .with_context(|| format!("Failed to open history file: {}", path.display()))?;
+    writeln!(file, "{}", serde_json::to_string(record)?)
+        .with_context(|| format!("Failed to write history file: {}", path.display()))
+}
+
+/// Score change from `previous` to `current`, in points rounded to the
+/// 0.1 shown in output so an unchanged printed score is no change
+pub fn score_change(previous: f64, current: f64) -> f64 {
+    (current * 10.0).round() / 10.0 - (previous * 10.0).round() / 10.0
+}
+
+/// Commit checked out in the project, if it is in a git repository
+pub fn head_commit(project: &Path) -> Option<String> {
+    let dir = if project.is_file() {
+        project.parent().unwrap_or(project)
+    } else {
+        project
+    };
+    crate::changes::git(dir, &["rev-parse", "HEAD"])
+        .ok()
+        .map(|sha| sha.trim().to_owned())
+        .filter(|sha| !sha.is_empty())
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::cli::TimeoutPolicy;
+    use crate::mutation::testing::mutant;
+    use crate::mutation::MutantStatus;
+
+    fn result(killed: usize, survived: usize) -> MutationResult {
+        let results: Vec<_> = [
+            (MutantStatus::Killed, killed),
+            (MutantStatus::Survived, survived),
+        ]
+        .into_iter()
+        .flat_map(|(status, count)| vec![mutant().status(status).result(); count])
+        .collect();
+        MutationResult::from_results(&results, TimeoutPolicy::Killed)
+    }
+
+    #[test]
+    fn test_appends_records_and_reads_the_last() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("history.jsonl");
+        assert_eq!(last_record(&path, None).unwrap(), None);
+
+        let first = HistoryRecord::new(&result(3, 1), Some("3f9c2e1a0b".to_owned()), None);
+        append(&path, &first).unwrap();
+        let second = HistoryRecord::new(&result(1, 1), None, None);
+        append(&path, &second).unwrap();
+
+        assert_eq!(std::fs::read_to_string(&path).unwrap().lines().count(), 2);
+        let last = last_record(&path, None).unwrap().unwrap();
+        assert_eq!(last, second);
+        assert!((last.score - 50.0).abs() < f64::EPSILON);
+        assert!(first.describe().starts_with("75.0% on "));
+        assert!(first.describe().ends_with(" at 3f9c2e1"));
+
+        // A commit that isn't ASCII doesn't split a character
+        let odd = HistoryRecord::new(&result(1, 0), Some("ééééééééé".to_owned()), None);
+        assert!(odd.describe().ends_with(" at ééééééé"));
+    }
+
+    #[test]
+    fn test_runs_are_compared_within_their_scope() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("history.jsonl");
+        let full = HistoryRecord::new(&result(9, 1), None, None);
+        append(&path, &full).unwrap();
+        let diff = HistoryRecord::new(
+            &result(1, 1),
+            None,
+            Some("--diff-only --base-ref main".to_owned()),
+        );
+        append(&path, &diff).unwrap();
+
+        assert_eq!(last_record(&path, None).unwrap(), Some(full));
+        assert_eq!(
+            last_record(&path, Some("--diff-only --base-ref main")).unwrap(),
+            Some(diff)
+        );
+        assert_eq!(last_record(&path, Some("--sample 50")).unwrap(), None);
+    }
+
+    #[test]
+    fn test_score_change_ignores_differences_below_display_precision() {
+        assert!(score_change(75.0, 80.0) > 0.0);
+        assert!(score_change(80.0, 75.0) < 0.0);
+        assert!(score_change(66.66, 66.67).abs() < f64::EPSILON);
+    }
+}
@@ -0,0 +1,1299 @@
+//! dart_mutant - A blazingly fast mutation testing tool for Dart
+//!
+//! Uses tree-sitter for AST-based mutations, ensuring precise and valid code modifications.
+//!
+//! The `dart_mutant` binary is a thin wrapper over this library. To embed
+//! mutation testing in another tool, build an [`Args`] the way the command
+//! line would and call [`run`]:
+//!
+//! ```no_run
+//! # async fn example() -> anyhow::Result<()> {
+//! let args = dart_mutant::Args::try_parse_resolved_from([
+//!     "dart_mutant", "--path", "my_app", "--sample", "50", "--json",
+//! ])?;
+//! let result = dart_mutant::run(&args).await?;
+//! println!("{:.1}% mutation score", result.mutation_score);
+//! # Ok(())
+//! # }
+//! ```
+
+/// `println!` unless `--quiet`
+macro_rules! status {
+    ($args:ident, $($arg:tt)*) => {
+        if !$args.quiet {
+            println!($($arg)*);
+        }
+    };
+}
+
+mod ai;
+mod cache;
+mod changes;
+mod cli;
+mod coverage;
+mod exit;
+mod history;
+mod mutation;
+mod mutators;
+mod parser;
+mod progress;
+mod report;
+mod runner;
+mod watch;
+
+pub use cli::{AiProvider, Args, FailOn, OutputFormat, Preset, SampleStrategy, TimeoutPolicy};
+pub use exit::{ExitCode, RunError};
+pub use mutation::{MutantStatus, Mutation, MutationOperator, SourceLocation};
+pub use mutators::MutatorCategory;
+pub use progress::{NoProgress, Phase, ProgressSink, TerminalProgress};
+pub use report::{
+    generate_ai_report, generate_badge, generate_cobertura_report, generate_diff_report,
+    generate_html_report, generate_json_report, generate_junit_report, generate_sarif_report,
+    generate_stryker_html_report, generate_verification_report, CappedFile, FileThresholdViolation,
+    MutationResult, ReportOptions, Thresholds,
+};
+pub use runner::MutantTestResult;
+
+use anyhow::{Context, Result};
+use clap::ValueEnum;
+use colored::Colorize;
+use indicatif::{MultiProgress, ProgressDrawTarget};
+use progress::{create_progress_bar, create_spinner};
+use rayon::prelude::*;
+use report::FileThresholds;
+use std::path::Path;
+use std::process::Stdio;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Everything the `dart_mutant` binary does after parsing arguments: the
+/// banner, `--list-operators`, `--explain`, the pipeline and the summary.
+/// Returns whether the run passed its `--fail-on` conditions.
+///
+/// With `--quiet` only the final score line is printed.
+pub async fn run_cli(args: &Args) -> Result<bool> {
+    if args.list_operators {
+        list_operators();
+        return Ok(true);
+    }
+
+    if !args.quiet {
+        print_banner();
+    }
+
+    if let Some(id) = &args.explain {
+        explain_mutation(args, id)?;
+        return Ok(true);
+    }
+
+    let needs_history = args.trend || args.fail_on.contains(&FailOn::Regression);
+    if needs_history && args.history_file.is_none() {
+        anyhow::bail!("--trend and --fail-on regression need a --history-file to compare against");
+    }
+    if args.threshold_low > args.threshold_high {
+        anyhow::bail!("--threshold-low can't be above --threshold-high");
+    }
+
+    let start = Instant::now();
+
+    // Run the mutation testing pipeline
+    let progress: Arc<dyn ProgressSink> = if args.quiet {
+        Arc::new(NoProgress)
+    } else {
+        Arc::new(TerminalProgress::default())
+    };
+    let (result, results) = run_pipeline(args, progress).await?;
+    // Nothing was tested, so there is no score to judge
+    if args.verify_mutations {
+        return Ok(true);
+    }
+
+    let duration = start.elapsed();
+    let previous = record_history(args, &result)?;
+    let categories_below = result.categories_below(&args.category_thresholds);
+    let failures = failure_reasons(
+        args,
+        &result,
+        !categories_below.is_empty(),
+        previous.as_ref(),
+    );
+    if args.quiet {
+        if !args.dry_run {
+            println!("{}", score_line(&result));
+        }
+    } else {
+        print_summary(&result, duration, &categories_below, thresholds(args));
+        if args.trend && !args.dry_run {
+            print_trend(&result, previous.as_ref());
+        }
+        for reason in &failures {
+            println!("{} {reason}", "✗".red());
+        }
+    }
+
+    if args.watch {
+        watch::watch(args, &result, results).await?;
+    }
+    Ok(failures.is_empty())
+}
+
+/// Append the run to `--history-file`, returning the last run of the same
+/// scope before it. Dry runs and runs without mutants aren't recorded
+fn record_history(args: &Args, result: &MutationResult) -> Result<Option<history::HistoryRecord>> {
+    let Some(path) = &args.history_file else {
+        return Ok(None);
+    };
+    if args.dry_run || result.total == 0 {
+        return Ok(None);
+    }
+    let scope = history_scope(args);
+    let previous = history::last_record(path, scope.as_deref())?;
+    let record = history::HistoryRecord::new(result, history::head_commit(&args.path), scope);
+    history::append(path, &record)?;
+    Ok(previous)
+}
+
+/// The options that limit the run to a subset of the mutants, as they would
+/// be written on the command line; `None` for a full run
+fn history_scope(args: &Args) -> Option<String> {
+    let mut scope = Vec::new();
+    if args.only_survivors.is_some() {
+        scope.push("--only-survivors".to_owned());
+    }
+    if args.diff_only {
+        scope.push(format!("--diff-only --base-ref {}", args.base_ref));
+    }
+    if let Some(operators) = &args.operators {
+        scope.push(format!("--operators {}", operators.join(",")));
+    }
+    if let Some(max) = args.max_mutations_per_file {
+        scope.push(format!("--max-mutations-per-file {max}"));
+    }
+    if let Some(max) = args.max_mutations {
+        scope.push(format!("--max-mutations {max}"));
+    }
+    if let Some(sample) = args.sample {
+        scope.push(format!("--sample {sample}"));
+    } else if let Some(preset) = args.preset.and_then(|p| p.to_possible_value()) {
+        scope.push(format!("--preset {}", preset.get_name()));
+    }
+    (!scope.is_empty()).then(|| scope.join(" "))
+}
+
+/// How the score moved since the last run in `--history-file`
+fn print_trend(result: &MutationResult, previous: Option<&history::HistoryRecord>) {
+    let Some(previous) = previous else {
+        println!(
+            "{} No earlier runs of this scope in the history file; the next run will be compared with this one",
+            "ℹ".cyan()
+        );
+        return;
+    };
+    let change = history::score_change(previous.score, result.mutation_score);
+    let since = format!("since the last run ({})", previous.describe());
+    if change > 0.0 {
+        println!(
+            "{} Mutation score up {:.1} points {since}",
+            "▲".green(),
+            change
+        );
+    } else if change < 0.0 {
+        println!(
+            "{} Mutation score down {:.1} points {since}",
+            "▼".red(),
+            -change
+        );
+    } else {
+        println!("{} Mutation score unchanged {since}", "=".cyan());
+    }
+}
+
+/// Why the run should fail under `--fail-on`; empty when it passes
+fn failure_reasons(
+    args: &Args,
+    result: &MutationResult,
+    categories_failed: bool,
+    previous: Option<&history::HistoryRecord>,
+) -> Vec<String> {
+    let mut reasons = Vec::new();
+    for fail_on in &args.fail_on {
+        match fail_on {
+            FailOn::Score => {
+                if result.mutation_score < args.threshold {
+                    reasons.push(format!(
+                        "Mutation score {:.1}% is below the threshold of {}%",
+                        result.mutation_score, args.threshold
+                    ));
+                }
+                if !result.file_threshold_violations.is_empty() {
+                    reasons.push(format!(
+                        "{} file(s) below their threshold",
+                        result.file_threshold_violations.len()
+                    ));
+                }
+                if categories_failed {
+                    reasons.push("Operator categories below their threshold".to_string());
+                }
+            }
+            FailOn::Survived if result.survived > 0 => {
+                reasons.push(format!("{} mutant(s) survived", result.survived));
+            }
+            FailOn::Error if result.errors > 0 => {
+                reasons.push(format!("{} mutant(s) had errors", result.errors));
+            }
+            FailOn::Timeout if result.timeout > 0 => {
+                reasons.push(format!("{} mutant(s) timed out", result.timeout));
+            }
+            FailOn::Regression => {
+                if let Some(previous) = previous.filter(|previous| {
+                    history::score_change(previous.score, result.mutation_score) < 0.0
+                }) {
+                    reasons.push(format!(
+                        "Mutation score fell from {:.1}% to {:.1}% since the last run",
+                        previous.score, result.mutation_score
+                    ));
+                }
+            }
+            FailOn::Survived | FailOn::Error | FailOn::Timeout => {}
+        }
+    }
+    reasons
+}
+
+fn print_banner() {
+    const BANNER: &str = r"
+    DART MUTANT - Mutation Testing for Dart
+    ========================================
+";
+    println!("{}", BANNER.bright_cyan());
+    println!(
+        "    {} {}\n",
+        "Mutation Testing for Dart".bright_white(),
+        format!("v{}", env!("CARGO_PKG_VERSION")).dimmed()
+    );
+}
+
+/// Run the mutation testing pipeline: discover and parse files, generate
+/// mutants, test them and write the requested reports
+pub async fn run(args: &Args) -> Result<MutationResult> {
+    run_with_progress(args, Arc::new(TerminalProgress::default())).await
+}
+
+/// [`run`], reporting phases and per-mutant results to `progress` instead of
+/// the terminal progress bar
+pub async fn run_with_progress(
+    args: &Args,
+    progress: Arc<dyn ProgressSink>,
+) -> Result<MutationResult> {
+    Ok(run_pipeline(args, progress).await?.0)
+}
+
+/// The pipeline behind [`run_with_progress`], also returning each mutant's
+/// result
+async fn run_pipeline(
+    args: &Args,
+    progress: Arc<dyn ProgressSink>,
+) -> Result<(MutationResult, Vec<MutantTestResult>)> {
+    let multi_progress = if args.quiet {
+        MultiProgress::with_draw_target(ProgressDrawTarget::hidden())
+    } else {
+        MultiProgress::new()
+    };
+
+    // Load per-file thresholds up front so a bad config fails fast
+    let file_thresholds = args
+        .file_thresholds
+        .as_deref()
+        .map(FileThresholds::load)
+        .transpose()?;
+    let coverage = args
+        .coverage_file
+        .as_deref()
+        .map(|path| coverage::Coverage::load(path, &args.path))
+        .transpose()?;
+    let changed_lines = args
+        .diff_only
+        .then(|| changes::ChangedLines::from_git(&args.path, &args.base_ref))
+        .transpose()?;
+    let survivors = args
+        .only_survivors
+        .as_deref()
+        .map(|report| report::Survivors::load(report, &args.path))
+        .transpose()?;
+
+    let report_options = ReportOptions {
+        path_root: args.relative_paths.then(|| args.path.clone()),
+        source_line_cap: args
+            .report_include_source
+            .then_some(args.report_source_max_lines),
+        json_diffs: args.json_with_diff,
+        html_offline: args.html_offline,
+        thresholds: thresholds(args),
+        timeout_policy: args.timeout_policy,
+    };
+    let progress: Arc<dyn ProgressSink> = match &args.ndjson {
+        Some(path) => Arc::new(report::NdjsonStream::create(
+            path,
+            report_options.clone(),
+            progress,
+        )?),
+        None => progress,
+    };
+
+    // Step 1: Discover Dart files
+    progress.on_phase_change(Phase::Discovering);
+    let discover_pb = create_spinner(&multi_progress, "Discovering Dart files...");
+    let mut dart_files = parser::discover_dart_files(
+        &args.path,
+        &args.glob,
+        &args.exclude,
+        generated_suffixes(args),
+    )?;
+    discover_pb.finish_with_message(format!(
+        "{} Found {} Dart files",
+        "✓".green(),
+        dart_files.len().to_string().cyan()
+    ));
+
+    if dart_files.is_empty() {
+        return Err(RunError::NoDartFiles {
+            path: args.path.clone(),
+        }
+        .into());
+    }
+
+    if let Some(changed) = &changed_lines {
+        dart_files.retain(|file| changed.touches_file(file));
+        status!(
+            args,
+            "{} {} Dart files changed since {} (--diff-only)",
+            "ℹ".cyan(),
+            dart_files.len().to_string().cyan(),
+            args.base_ref
+        );
+    }
+
+    if let Some(survivors) = &survivors {
+        if survivors.is_empty() {
+            status!(
+                args,
+                "\n{}",
+                "No surviving mutants in the report, nothing to re-test.".green()
+            );
+            return Ok((MutationResult::default(), Vec::new()));
+        }
+        dart_files.retain(|file| survivors.touches_file(file));
+    }
+
+    // Step 2: Parse files and generate mutations
+    progress.on_phase_change(Phase::Parsing {
+        files: dart_files.len(),
+    });
+    let parse_pb = create_progress_bar(&multi_progress, dart_files.len() as u64, "Parsing files");
+    let parse_options = parse_options(args);
+
+    // Parse in parallel; collecting an indexed iterator keeps file order
+    let per_file = dart_files
+        .par_iter()
+        .map(|file| {
+            let mutations = parser::parse_and_find_mutations(file, &parse_options);
+            parse_pb.inc(1);
+            mutations
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let mut all_mutations: Vec<Mutation> = per_file.into_iter().flatten().collect();
+    parse_pb.finish_with_message(format!(
+        "{} Generated {} mutations",
+        "✓".green(),
+        all_mutations.len().to_string().cyan()
+    ));
+
+    // Add AI-suggested mutations if enabled
+    if args.is_ai_enabled() {
+        progress.on_phase_change(Phase::AiSuggestions);
+        let ai_pb = create_spinner(&multi_progress, "Getting AI mutation suggestions...");
+        let suggester = ai::AiMutationSuggester::new(
+            args.ai,
+            args.get_ai_api_key(),
+            args.ollama_url.clone(),
+            args.ollama_model.clone(),
+            args.ai_max_per_file,
+            (!args.no_ai_cache).then(|| args.path.join(ai::AI_CACHE_DIR)),
+        )
+        .with_max_retries(args.ai_retries)
+        .with_azure(ai::AzureConfig {
+            endpoint: args.azure_endpoint.clone(),
+            deployment: args.azure_deployment.clone(),
+            api_version: args.azure_api_version.clone(),
+        })
+        .with_prompt_template(
+            args.ai_prompt_file
+                .as_deref()
+                .map(ai::load_prompt_template)
+                .transpose()?,
+        );
+        let ai_result = ai::suggest_mutations_for_files(&dart_files, &suggester).await;
+        match ai_result {
+            Ok(ai_mutations) => {
+                ai_pb.finish_with_message(format!(
+                    "{} AI suggested {} additional mutations",
+                    "✓".green(),
+                    ai_mutations.len()
+                ));
+                all_mutations.extend(ai_mutations);
+            }
+            Err(e) => {
+                ai_pb.finish_with_message(format!("{} AI suggestions failed: {e}", "✗".red()));
+            }
+        }
+    }
+    mutation::dedup_mutations(&mut all_mutations);
+
+    if let Some(survivors) = &survivors {
+        let missing = survivors.retain(&mut all_mutations);
+        status!(
+            args,
+            "{} Re-testing {} mutations matching the report's {} survivors (--only-survivors)",
+            "ℹ".cyan(),
+            all_mutations.len().to_string().cyan(),
+            survivors.len()
+        );
+        if missing > 0 {
+            println!(
+                "{} {} surviving mutants no longer match a mutation; their code was removed or moved",
+                "⚠".yellow(),
+                missing
+            );
+        }
+    }
+
+    if let Some(operators) = &args.operators {
+        let categories = operator_categories(operators);
+        all_mutations.retain(|m| categories.contains(&m.operator.category()));
+        print_empty_operator_categories(operators, &all_mutations);
+    }
+
+    if let Some(changed) = &changed_lines {
+        all_mutations.retain(|m| changed.contains(m));
+    }
+
+    if all_mutations.is_empty() {
+        status!(
+            args,
+            "\n{}",
+            "No mutations generated. Your code might be too simple or already well-tested!"
+                .yellow()
+        );
+        return Ok((MutationResult::default(), Vec::new()));
+    }
+
+    // Cap each file once only the mutants that could be tested are left,
+    // then apply the global cap before sampling
+    let mut capped_files = Vec::new();
+    if let Some(max) = args.max_mutations_per_file {
+        let (kept, capped) = mutation::spread_mutations_per_file(all_mutations, max);
+        all_mutations = kept;
+        for (file, generated) in capped {
+            let capped = CappedFile {
+                file: file
+                    .strip_prefix(&args.path)
+                    .unwrap_or(&file)
+                    .display()
+                    .to_string(),
+                generated,
+                kept: max,
+            };
+            status!(
+                args,
+                "{} Capped {} at {} of {} mutations (--max-mutations-per-file); its score is based on a subset",
+                "ℹ".cyan(),
+                capped.file.cyan(),
+                capped.kept,
+                capped.generated
+            );
+            capped_files.push(capped);
+        }
+    }
+
+    let capped_from = args
+        .max_mutations
+        .filter(|&max| all_mutations.len() > max)
+        .map(|max| {
+            let generated = all_mutations.len();
+            all_mutations = mutation::cap_mutations(&all_mutations, max);
+            status!(
+                args,
+                "{} Capped at {} of {} mutations (--max-mutations)",
+                "ℹ".cyan(),
+                max.to_string().cyan(),
+                generated
+            );
+            generated
+        });
+
+    // Apply sampling if requested
+    let sample = args.sample.or_else(|| {
+        args.preset
+            .map(|preset| preset.sample_size(all_mutations.len()))
+    });
+    let mut sample_seed = None;
+    let mutations_to_test = match sample {
+        Some(sample_size) if sample_size < all_mutations.len() => {
+            // Without --seed, pick one at random so the run can be reproduced
+            let seed = args.seed.unwrap_or_else(rand::random);
+            sample_seed = Some(seed);
+            match args.sample_strategy {
+                SampleStrategy::Uniform => {
+                    mutation::sample_mutations(&all_mutations, sample_size, seed)
+                }
+                SampleStrategy::Stratified => {
+                    mutation::sample_mutations_stratified(&all_mutations, sample_size, seed)
+                }
+            }
+        }
+        _ => all_mutations.clone(),
+    };
+
+    // Mutants on lines no test executes can't be killed; don't run them.
+    // Without coverage data, fall back to which files the tests import
+    let test_imports = (coverage.is_none() && !args.no_import_check)
+        .then(|| coverage::imports::TestImports::scan(&args.path))
+        .flatten();
+    let (mutations_to_test, uncovered): (Vec<Mutation>, Vec<Mutation>) =
+        if let Some(coverage) = &coverage {
+            mutations_to_test
+                .into_iter()
+                .partition(|m| coverage.is_covered(m))
+        } else if let Some(imports) = &test_imports {
+            mutations_to_test
+                .into_iter()
+                .partition(|m| imports.is_imported(m))
+        } else {
+            (mutations_to_test, Vec::new())
+        };
+    if !uncovered.is_empty() {
+        let reason = if coverage.is_some() {
+            "on uncovered lines marked as no coverage (--coverage-file)"
+        } else {
+            "in files no test imports marked as no coverage"
+        };
+        status!(
+            args,
+            "{} {} mutations {reason}",
+            "ℹ".cyan(),
+            uncovered.len().to_string().cyan()
+        );
+    }
+
+    // Step 3: Run mutation tests (or skip in dry-run mode)
+    let mut mutants_per_second = None;
+    let mut cached_count = 0;
+    let mut baseline = None;
+    let mut verification = None;
+    let results = if args.dry_run {
+        if args.quiet {
+            println!("{} mutations would be tested", mutations_to_test.len());
+        } else {
+            println!("\n{} Dry run mode - skipping test execution", "ℹ".cyan());
+            println!("  {} mutations would be tested\n", mutations_to_test.len());
+        }
+
+        // Print first few mutations as preview
+        for (i, m) in mutations_to_test
+            .iter()
+            .take(if args.quiet { 0 } else { 10 })
+            .enumerate()
+        {
+            println!(
+                "  {}. [{}:{}] {} → {}",
+                i + 1,
+                m.location
+                    .file
+                    .file_name()
+                    .unwrap_or_default()
+                    .to_string_lossy(),
+                m.location.start_line,
+                m.original,
+                m.mutated
+            );
+        }
+        if mutations_to_test.len() > 10 && !args.quiet {
+            println!("  ... and {} more", mutations_to_test.len() - 10);
+        }
+
+        // Return empty results for dry run
+        vec![]
+    } else if args.verify_mutations {
+        // Operators are checked against every mutant, covered or not
+        let checked: Vec<Mutation> = mutations_to_test.into_iter().chain(uncovered).collect();
+        let analyze = which::which("dart").is_ok();
+        if !analyze {
+            println!(
+                "{} dart not found on PATH; checking mutants with the parser only",
+                "⚠".yellow()
+            );
+        }
+        let verify_pb = create_spinner(
+            &multi_progress,
+            &format!("Checking that {} mutants compile...", checked.len()),
+        );
+        let invalid = runner::verify_mutations(&args.path, &checked, analyze).await;
+        verify_pb.finish_and_clear();
+        println!(
+            "{} {} of {} mutants don't compile",
+            if invalid.is_empty() {
+                "✓".green()
+            } else {
+                "✗".red()
+            },
+            invalid.len(),
+            checked.len()
+        );
+        for row in report::verification_by_operator(&checked, &invalid) {
+            status!(
+                args,
+                "  {:>5} of {:<5} {}",
+                row.invalid.len().to_string().red(),
+                row.checked,
+                row.operator
+            );
+        }
+        verification = Some((checked, invalid));
+        vec![]
+    } else {
+        let test_command = runner::TestCommand::parse(&args.test_command)?
+            .with_env(&args.test_env)
+            .with_dart_defines(&args.dart_define)
+            .with_test_map(args.test_map.clone());
+
+        // Without test names every failure would silently count as a kill
+        let kill_filter = runner::KillFilter::new(&args.killing_tests, &args.non_killing_tests)?;
+        if kill_filter.is_active() && !test_command.reports_test_names() {
+            anyhow::bail!(
+                "--killing-tests and --non-killing-tests need the failing test names from `dart test --reporter json`, which `{test_command}` doesn't report"
+            );
+        }
+
+        if args.warmup {
+            progress.on_phase_change(Phase::Warmup);
+            let warmup_pb = create_spinner(&multi_progress, "Warming up test caches...");
+            let elapsed = runner::run_warmup(&args.path, &test_command).await?;
+            warmup_pb.finish_with_message(format!(
+                "{} Warm-up run finished in {:.1}s",
+                "✓".green(),
+                elapsed.as_secs_f64()
+            ));
+        }
+
+        progress.on_phase_change(Phase::Baseline);
+        let baseline_pb = create_spinner(&multi_progress, "Running tests without mutations...");
+        let baseline_run = runner::run_baseline(&args.path, &test_command).await?;
+        let timeout = args.timeout.map_or_else(
+            || runner::auto_timeout(baseline_run.duration, args.timeout_factor),
+            Duration::from_secs,
+        );
+        if baseline_run.passed() {
+            baseline_pb.finish_with_message(format!(
+                "{} Baseline tests passed in {:.1}s, timing out mutants after {:.1}s",
+                "✓".green(),
+                baseline_run.duration.as_secs_f64(),
+                timeout.as_secs_f64()
+            ));
+        } else if args.skip_baseline_check {
+            baseline_pb.finish_with_message(format!(
+                "{} Baseline tests fail (exit code {}), continuing (--skip-baseline-check)",
+                "⚠".yellow(),
+                baseline_run.exit_code
+            ));
+        } else {
+            baseline_pb.finish_and_clear();
+            return Err(RunError::BaselineFailed {
+                command: test_command.to_string(),
+                exit_code: baseline_run.exit_code,
+                output: baseline_run.output.trim_end().to_owned(),
+            }
+            .into());
+        }
+        baseline = Some(baseline_run.duration);
+
+        // Reuse results for mutants in files unchanged since the last run.
+        // Survivors are re-tested for new tests, which the cache can't see
+        let cache_path = args.path.join(&args.cache_file);
+        let mut incremental = (args.incremental && survivors.is_none()).then(|| {
+            let file_hashes = cache::FileHashes::compute(&dart_files).with_tests(&args.path);
+            let mut result_cache = cache::ResultCache::load(&cache_path);
+            result_cache.invalidate(&file_hashes);
+            (result_cache, file_hashes)
+        });
+        let (cached_results, mutations_to_test) = match &incremental {
+            Some((result_cache, file_hashes)) => {
+                result_cache.partition(mutations_to_test, file_hashes)
+            }
+            None => (Vec::new(), mutations_to_test),
+        };
+        cached_count = cached_results.len();
+        if cached_count > 0 {
+            status!(
+                args,
+                "{} Reusing {} cached results (--incremental)",
+                "ℹ".cyan(),
+                cached_count.to_string().cyan()
+            );
+        }
+
+        // Without sandboxes, mutants of one file are tested one at a time
+        if !args.sandbox {
+            if args.concurrency_per_file.is_some_and(|slots| slots > 1) {
+                println!(
+                    "{} --concurrency-per-file needs --sandbox; mutants of one file are tested one at a time",
+                    "⚠".yellow()
+                );
+            }
+            if let Some((file, count)) = runner::dominant_file(&mutations_to_test, args.parallel) {
+                status!(
+                    args,
+                    "{} {} has {} of {} mutants, which are tested one at a time; --sandbox tests them in parallel",
+                    "ℹ".cyan(),
+                    file.strip_prefix(&args.path).unwrap_or(file).display(),
+                    count,
+                    mutations_to_test.len()
+                );
+            }
+        }
+
+        progress.on_phase_change(Phase::Testing {
+            mutants: mutations_to_test.len(),
+        });
+        let test_start = Instant::now();
+        let mut results = runner::run_mutation_tests(
+            &args.path,
+            &mutations_to_test,
+            &test_command,
+            runner::RunLimits {
+                parallel_jobs: args.parallel,
+                timeout,
+                max_duration: args.max_duration.map(Duration::from_secs),
+                sandbox: args.sandbox,
+                concurrency_per_file: args.concurrency_per_file,
+                reuse_vm: args.reuse_vm,
+            },
+            runner::Retries {
+                rerun_killed: args.rerun_killed,
+                confirm_survivors: args.confirm_survivors,
+            },
+            &kill_filter,
+            progress.clone(),
+        )
+        .await?;
+
+        let tested = results
+            .iter()
+            .filter(|r| r.status != MutantStatus::Pending)
+            .count();
+        // With every result cached there is no throughput to report
+        mutants_per_second =
+            (tested > 0).then(|| tested as f64 / test_start.elapsed().as_secs_f64());
+
+        if let Some((result_cache, file_hashes)) = &mut incremental {
+            result_cache.record(&results, file_hashes);
+            result_cache.save(&cache_path)?;
+        }
+        results.extend(cached_results);
+
+        results.extend(uncovered.into_iter().map(|mutation| MutantTestResult {
+            mutation,
+            status: MutantStatus::NoCoverage,
+            duration: Duration::ZERO,
+            output: None,
+            error: None,
+            retries: 0,
+            killed_by: None,
+        }));
+        results
+    };
+
+    // Step 4: Generate reports
+    progress.on_phase_change(Phase::Reporting);
+    let report_pb = create_spinner(&multi_progress, "Generating reports...");
+
+    // Create output directories once; report writers assume they exist
+    std::fs::create_dir_all(&args.output).with_context(|| {
+        format!(
+            "Failed to create output directory: {}",
+            args.output.display()
+        )
+    })?;
+    if let Some(badge_dir) = args.badge_output.as_deref().and_then(Path::parent) {
+        std::fs::create_dir_all(badge_dir).with_context(|| {
+            format!("Failed to create badge directory: {}", badge_dir.display())
+        })?;
+    }
+
+    let mut mutation_result = MutationResult::from_results(&results, args.timeout_policy);
+    mutation_result.capped_from = capped_from;
+    mutation_result.capped_files = capped_files;
+    mutation_result.sample_seed = sample_seed;
+    mutation_result.mutants_per_second = mutants_per_second;
+    mutation_result.cached = cached_count;
+    mutation_result.baseline_secs = baseline.map(|d| d.as_secs_f64());
+    if let Some(thresholds) = &file_thresholds {
+        mutation_result.file_threshold_violations =
+            thresholds.violations(&results, &args.path, args.timeout_policy);
+    }
+
+    let html_path = (args.html && !args.no_html).then(|| args.output.join("mutation-report.html"));
+    if let Some(html_path) = &html_path {
+        match args.output_format {
+            OutputFormat::Html => generate_html_report(
+                &mutation_result,
+                &results,
+                &dart_files,
+                html_path,
+                &report_options,
+            )?,
+            OutputFormat::StrykerHtml => {
+                generate_stryker_html_report(
+                    &mutation_result,
+                    &results,
+                    html_path,
+                    &report_options,
+                )?;
+            }
+        }
+        report_pb.set_message(format!(
+            "{} HTML report: {}",
+            "✓".green(),
+            html_path.display().to_string().cyan()
+        ));
+    }
+
+    if args.json {
+        let json_path = args.output.join("mutation-report.json");
+        generate_json_report(&mutation_result, &results, &json_path, &report_options)?;
+    }
+
+    if args.junit {
+        let junit_path = args.output.join("junit.xml");
+        generate_junit_report(&mutation_result, &results, &junit_path, &report_options)?;
+    }
+
+    if args.cobertura {
+        let cobertura_path = args.output.join("mutation-cobertura.xml");
+        generate_cobertura_report(&mutation_result, &results, &cobertura_path, &report_options)?;
+    }
+
+    if args.sarif {
+        let sarif_path = args.output.join("mutation-report.sarif");
+        generate_sarif_report(&results, &sarif_path, &report_options)?;
+    }
+
+    if args.ai_report {
+        let ai_path = args.output.join("mutation-report-ai.md");
+        generate_ai_report(&mutation_result, &results, &ai_path, &report_options)?;
+    }
+
+    if let Some((checked, invalid)) = &verification {
+        let verification_path = args.output.join("mutation-verification.md");
+        generate_verification_report(checked, invalid, &verification_path, &report_options)?;
+    }
+
+    if let Some(badge_path) = &args.badge_output {
+        generate_badge(&mutation_result, badge_path, &report_options)?;
+    }
+
+    if let Some(baseline_path) = &args.diff_report {
+        let diff_path = args.output.join("mutation-report-diff.md");
+        generate_diff_report(
+            &mutation_result,
+            &results,
+            baseline_path,
+            &diff_path,
+            &report_options,
+            args.timeout_policy,
+        )?;
+    }
+
+    report_pb.finish_with_message(format!("{} Reports generated", "✓".green()));
+
+    if args.summary_detail && !results.is_empty() {
+        println!(
+            "\n{}\n\n{}",
+            "  Per-file results".bright_white().bold(),
+            report::format_file_breakdown(&results, &report_options)
+        );
+    }
+    if !args.quiet {
+        if let Some(timing) = report::format_timing_breakdown(&results, &report_options) {
+            println!(
+                "\n{}\n\n{}",
+                "  Where the time went".bright_white().bold(),
+                timing
+            );
+        }
+    }
+
+    if args.open {
+        match &html_path {
+            Some(html_path) => open_in_browser(html_path),
+            None => println!(
+                "{} --open ignored: no HTML report was generated (--no-html)",
+                "⚠".yellow()
+            ),
+        }
+    }
+
+    progress.on_phase_change(Phase::Done);
+    Ok((mutation_result, results))
+}
+
+/// Launch the platform browser on a report without waiting for it to exit
+fn open_in_browser(path: &Path) {
+    let mut command = if cfg!(target_os = "macos") {
+        tokio::process::Command::new("open")
+    } else if cfg!(target_os = "windows") {
+        let mut command = tokio::process::Command::new("cmd");
+        // `start` treats its first quoted argument as the window title
+        command.args(["/c", "start", ""]);
+        command
+    } else {
+        tokio::process::Command::new("xdg-open")
+    };
+    let spawned = command
+        .arg(path)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn();
+    if let Err(e) = spawned {
+        println!(
+            "{} Could not open {} in a browser: {e}",
+            "⚠".yellow(),
+            path.display()
+        );
+    }
+}
+
+/// Parser settings derived from the command line
+fn parse_options(args: &Args) -> parser::ParseOptions {
+    parser::ParseOptions {
+        skip_asserts: args.skip_asserts,
+        include_print_calls: args.include_print_calls,
+        // Type-level null-safety mutants only when explicitly requested
+        nullable_types: args.operators.as_ref().is_some_and(|ops| {
+            ops.iter()
+                .any(|op| MutatorCategory::from_str(op) == Some(MutatorCategory::NullSafety))
+        }),
+    }
+}
+
+/// Suffixes of generated files to skip; none with --include-generated
+fn generated_suffixes(args: &Args) -> &[String] {
+    if args.include_generated {
+        &[]
+    } else {
+        &args.generated_suffixes
+    }
+}
+
+/// Print each `--operators` category with the operators it enables
+fn list_operators() {
+    for category in MutatorCategory::ALL {
+        println!("{}", category.as_str().bold());
+        for operator in MutationOperator::ALL
+            .iter()
+            .filter(|op| op.category() == category)
+        {
+            println!("  {:<28} {}", format!("{operator:?}"), operator.name());
+        }
+        println!();
+    }
+    println!(
+        "Select categories with {}",
+        "--operators arithmetic,null_safety".cyan()
+    );
+}
+
+/// Print everything needed to write a test that kills one mutant: where it
+/// is, the surrounding source, the change, and a test hint
+fn explain_mutation(args: &Args, id: &str) -> Result<()> {
+    let parse_options = parse_options(args);
+
+    let mut matches = Vec::new();
+    for file in parser::discover_dart_files(
+        &args.path,
+        &args.glob,
+        &args.exclude,
+        generated_suffixes(args),
+    )? {
+        let mutations = parser::parse_and_find_mutations(&file, &parse_options)?;
+        matches.extend(mutations.into_iter().filter(|m| m.id.starts_with(id)));
+    }
+
+    let mutation = match matches.as_slice() {
+        [] => anyhow::bail!("No mutation found with id {id} in {}", args.path.display()),
+        [mutation] => mutation,
+        _ => anyhow::bail!(
+            "Id {id} is ambiguous: it matches {} mutations",
+            matches.len()
+        ),
+    };
+
+    let loc = &mutation.location;
+    let source = std::fs::read_to_string(&loc.file)
+        .with_context(|| format!("Failed to read file: {}", loc.file.display()))?;
+
+    println!("{} Mutation {}\n", "🔍".cyan(), mutation.id.bright_white());
+    println!(
+        "  File:      {}:{}:{}",
+        loc.file.display().to_string().cyan(),
+        loc.start_line,
+        loc.start_col
+    );
+    println!("  Operator:  {}", mutation.operator.name());
+    println!(
+        "  Change:    {} → {}\n",
+        mutation.original.red(),
+        mutation.mutated.green()
+    );
+
+    // Whole lines touched by the mutation, before and after
+    let line_start = source[..loc.byte_start].rfind('\n').map_or(0, |i| i + 1);
+    let line_end = source[loc.byte_end..]
+        .find('\n')
+        .map_or(source.len(), |i| loc.byte_end + i);
+    let mutated_lines = format!(
+        "{}{}{}",
+        &source[line_start..loc.byte_start],
+        mutation.mutated,
+        &source[loc.byte_end..line_end]
+    );
+
+    const CONTEXT_LINES: usize = 3;
+    let lines: Vec<&str> = source.lines().collect();
+    let first = loc.start_line.saturating_sub(CONTEXT_LINES).max(1);
+    let last = (loc.end_line + CONTEXT_LINES).min(lines.len());
+
+    for (number, line) in (first..loc.start_line).zip(&lines[first - 1..]) {
+        println!("  {number:>5} |   {line}");
+    }
+    for (number, line) in (loc.start_line..).zip(source[line_start..line_end].lines()) {
+        println!("{}", format!("  {number:>5} | - {line}").red());
+    }
+    for (number, line) in (loc.start_line..).zip(mutated_lines.lines()) {
+        println!("{}", format!("  {number:>5} | + {line}").green());
+    }
+    for (number, line) in (loc.end_line + 1..=last).zip(&lines[loc.end_line..]) {
+        println!("  {number:>5} |   {line}");
+    }
+
+    println!(
+        "\n  {} {}",
+        "Test hint:".bright_white(),
+        report::generate_test_hint(&mutation.operator, &mutation.original, &mutation.mutated)
+    );
+
+    Ok(())
+}
+
+/// Categories selected by `--operators`, warning about names that don't match
+fn operator_categories(requested: &[String]) -> Vec<MutatorCategory> {
+    requested
+        .iter()
+        .filter_map(|name| {
+            let category = MutatorCategory::from_str(name);
+            if category.is_none() {
+                let valid: Vec<&str> = MutatorCategory::ALL.iter().map(|c| c.as_str()).collect();
+                println!(
+                    "{} Unknown operator '{}' ignored (expected one of: {})",
+                    "⚠".yellow(),
+                    name,
+                    valid.join(", ")
+                );
+            }
+            category
+        })
+        .collect()
+}
+
+/// Tell the user which requested operator categories produced no mutants
+fn print_empty_operator_categories(requested: &[String], mutations: &[Mutation]) {
+    let counts = mutation::count_by_category(mutations);
+
+    requested
+        .iter()
+        .filter_map(|name| MutatorCategory::from_str(name))
+        .filter(|category| !counts.contains_key(category))
+        .for_each(|category| {
+            println!(
+                "{} {}: 0 mutants (no {} ops found)",
+                "⚠".yellow(),
+                category.as_str(),
+                category.as_str().replace('_', " ")
+            );
+        });
+}
+
+/// The one line `--quiet` prints, e.g.
+/// `Mutation score: 82.5% (33 killed, 5 survived, 40 total)`
+fn score_line(result: &MutationResult) -> String {
+    format!(
+        "Mutation score: {:.1}% ({} killed, {} survived, {} total)",
+        result.mutation_score, result.killed, result.survived, result.total
+    )
+}
+
+/// Score bands from `--threshold-high` and `--threshold-low`
+const fn thresholds(args: &Args) -> Thresholds {
+    Thresholds {
+        high: args.threshold_high,
+        low: args.threshold_low,
+    }
+}
+
+fn print_summary(
+    result: &MutationResult,
+    duration: Duration,
+    categories_below: &[(MutatorCategory, f64, f64)],
+    thresholds: Thresholds,
+) {
+    println!("\n{}", "═".repeat(70).bright_cyan());
+    println!(
+        "{}",
+        "                        MUTATION TESTING RESULTS                        "
+            .bright_white()
+            .bold()
+    );
+    println!("{}\n", "═".repeat(70).bright_cyan());
+
+    // Score display with color based on threshold
+    let score_color = if result.mutation_score >= f64::from(thresholds.high) {
+        "green"
+    } else if result.mutation_score >= f64::from(thresholds.low) {
+        "yellow"
+    } else {
+        "red"
+    };
+
+    let score_bar = create_score_bar(result.mutation_score, thresholds);
+    println!("  Mutation Score: {}", score_bar);
+    println!(
+        "  {:.1}%\n",
+        match score_color {
+            "green" => format!("{:.1}%", result.mutation_score).green(),
+            "yellow" => format!("{:.1}%", result.mutation_score).yellow(),
+            _ => format!("{:.1}%", result.mutation_score).red(),
+        }
+    );
+
+    println!("  {} Killed:      {}", "●".green(), result.killed);
+    println!("  {} Survived:    {}", "●".red(), result.survived);
+    if result.likely_equivalent > 0 {
+        println!(
+            "    {}",
+            format!("{} likely equivalent", result.likely_equivalent).dimmed()
+        );
+    }
+    println!("  {} Timeout:     {}", "●".yellow(), result.timeout);
+    println!("  {} No Coverage: {}", "●".dimmed(), result.no_coverage);
+    println!("  {} Errors:      {}\n", "●".magenta(), result.errors);
+
+    println!(
+        "  Total Mutants: {}",
+        result.total.to_string().bright_white()
+    );
+    if result.pending > 0 {
+        println!(
+            "  {}",
+            format!(
+                "Budget exceeded, tested {} of {} mutants (--max-duration)",
+                result.total - result.pending,
+                result.total
+            )
+            .yellow()
+        );
+    }
+    if let Some(generated) = result.capped_from {
+        println!(
+            "  Capped From:   {} generated (--max-mutations)",
+            generated.to_string().bright_white()
+        );
+    }
+    if !result.capped_files.is_empty() {
+        println!(
+            "  Capped Files:  {} (--max-mutations-per-file)",
+            result.capped_files.len().to_string().bright_white()
+        );
+    }
+    if let Some(seed) = result.sample_seed {
+        println!(
+            "  Sample Seed:   {} (reproduce with --seed)",
+            seed.to_string().bright_white()
+        );
+    }
+    if let Some(secs) = result.baseline_secs {
+        println!("  Baseline Run:  {}", format!("{secs:.1}s").bright_white());
+    }
+    if result.cached > 0 {
+        println!(
+            "  From Cache:    {}",
+            result.cached.to_string().bright_white()
+        );
+    }
+    if let Some(rate) = result.mutants_per_second {
+        println!(
+            "  Throughput:    {}",
+            format!("{rate:.2} mutants/s").bright_white()
+        );
+    }
+    println!(
+        "  Time Elapsed:  {}\n",
+        format!("{:.2}s", duration.as_secs_f64()).bright_white()
+    );
+
+    if !result.file_threshold_violations.is_empty() {
+        println!("  {}", "Files below their threshold:".red().bold());
+        for v in &result.file_threshold_violations {
+            println!(
+                "  {} {}: {:.1}% < {:.0}% ({})",
+                "✗".red(),
+                v.file,
+                v.score,
+                v.threshold,
+                v.pattern.dimmed()
+            );
+        }
+        println!();
+    }
+
+    if !categories_below.is_empty() {
+        println!("  {}", "Categories below their threshold:".red().bold());
+        for (category, score, threshold) in categories_below {
+            println!(
+                "  {} {}: {:.1}% < {:.0}%",
+                "✗".red(),
+                category.as_str(),
+                score,
+                threshold
+            );
+        }
+        println!();
+    }
+
+    println!("{}", "═".repeat(70).bright_cyan());
+}
+
+fn create_score_bar(score: f64, thresholds: Thresholds) -> String {
+    let width = 40;
+    let filled = ((score / 100.0) * width as f64) as usize;
+    let empty = width - filled;
+
+    let bar = format!("{}{}", "█".repeat(filled), "░".repeat(empty));
+
+    if score >= f64::from(thresholds.high) {
+        bar.green().to_string()
+    } else if score >= f64::from(thresholds.low) {
+        bar.yellow().to_string()
+    } else {
+        bar.red().to_string()
+    }
+}
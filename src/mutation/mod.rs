@@ -3,18 +3,30 @@
 //! This module defines the different kinds of mutations that can be applied
 //! to Dart source code, inspired by Stryker's comprehensive operator set.
 
+#[cfg(test)]
+pub mod testing;
+
+use crate::mutators::MutatorCategory;
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
 
 /// Location of a mutation in source code
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SourceLocation {
+    /// File the mutation is in
     pub file: PathBuf,
+    /// First line, 1-based
     pub start_line: usize,
+    /// Column on the first line, 1-based
     pub start_col: usize,
+    /// Last line, 1-based
     pub end_line: usize,
+    /// Column on the last line, 1-based
     pub end_col: usize,
+    /// Byte offset of the replaced text
     pub byte_start: usize,
+    /// Byte offset just past the replaced text
     pub byte_end: usize,
 }
 
@@ -66,6 +78,11 @@ pub struct Mutation {
     /// AI confidence score (0.0 - 1.0) if AI suggested
     #[serde(default)]
     pub ai_confidence: Option<f64>,
+
+    /// Whether a heuristic judged this mutant to behave exactly like the
+    /// original code (see [`flag_likely_equivalent`])
+    #[serde(default)]
+    pub likely_equivalent: bool,
 }
 
 impl Mutation {
@@ -80,17 +97,13 @@ impl Mutation {
         replacement: String,
         operator: MutationOperator,
     ) -> Self {
-        let id = format!(
-            "{:x}",
-            md5::compute(format!(
-                "{}:{}:{}:{}",
-                file_path.display(),
-                line,
-                original,
-                replacement
-            ))
-        );
+        let id = mutation_id(&file_path, line, column, &original, &replacement);
         let description = format!("{}: {} → {}", operator.name(), original, replacement);
+        // A removed `?? fallback` can run over several lines
+        let (end_line, end_col) = match original.rsplit_once('\n') {
+            Some((before, last)) => (line + before.matches('\n').count() + 1, last.len() + 1),
+            None => (line, column + original.len()),
+        };
 
         Self {
             id,
@@ -98,8 +111,8 @@ impl Mutation {
                 file: file_path.clone(),
                 start_line: line,
                 start_col: column,
-                end_line: line,
-                end_col: column + original.len(),
+                end_line,
+                end_col,
                 byte_start,
                 byte_end,
             },
@@ -110,15 +123,18 @@ impl Mutation {
             replacements: vec![replacement],
             ai_suggested: false,
             ai_confidence: None,
+            likely_equivalent: false,
         }
     }
 
-    /// Apply this mutation to the given source code
+    /// Apply this mutation to the given source code. Source that no longer
+    /// holds [`Mutation::original`] at the mutation's byte offsets is
+    /// returned unchanged, since splicing at stale offsets corrupts it
     pub fn apply(&self, source: &str) -> String {
-        // Validate byte indices
-        if self.location.byte_start > source.len() || self.location.byte_end > source.len() {
+        if !self.matches(source) {
             tracing::warn!(
-                "Mutation byte indices out of bounds: start={}, end={}, source_len={}",
+                "Mutation {} does not match the source at bytes {}..{} (source is {} bytes)",
+                self.id,
                 self.location.byte_start,
                 self.location.byte_end,
                 source.len()
@@ -132,109 +148,227 @@ impl Mutation {
         result.push_str(source.get(self.location.byte_end..).unwrap_or_default());
         result
     }
+
+    /// Whether `source` holds this mutation's original code at its byte
+    /// offsets, i.e. the file hasn't changed since the mutation was found
+    pub fn matches(&self, source: &str) -> bool {
+        source.get(self.location.byte_start..self.location.byte_end) == Some(self.original.as_str())
+    }
+
+    /// Whether both mutations change some of the same bytes of the same file.
+    /// Overlapping mutations can't be applied together: the first shifts or
+    /// removes the code the second expects at its offsets
+    pub fn overlaps(&self, other: &Self) -> bool {
+        let (a, b) = (&self.location, &other.location);
+        a.file == b.file && a.byte_start.max(b.byte_start) < a.byte_end.min(b.byte_end)
+    }
 }
 
-/// Categories of mutation operators
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
-pub enum MutationOperator {
-    // General categories (used by parser)
-    Arithmetic,
-    Comparison,
-    Logical,
-    Boolean,
-    Unary,
-    Assignment,
-    NullSafety,
-    String,
-    Collection,
-    Conditional,
-    Return,
-    Async,
-    Literal,
-    Bitwise,
-    Other,
-
-    // Specific arithmetic mutations
-    ArithmeticAddToSub,
-    ArithmeticSubToAdd,
-    ArithmeticMulToDiv,
-    ArithmeticDivToMul,
-    ArithmeticModToMul,
-
-    // Specific comparison mutations
-    ComparisonLtToLte,
-    ComparisonLtToGt,
-    ComparisonLtToGte,
-    ComparisonLteToLt,
-    ComparisonLteToGt,
-    ComparisonLteToGte,
-    ComparisonGtToGte,
-    ComparisonGtToLt,
-    ComparisonGtToLte,
-    ComparisonGteToGt,
-    ComparisonGteToLt,
-    ComparisonGteToLte,
-    ComparisonEqToNeq,
-    ComparisonNeqToEq,
-
-    // Specific logical mutations
-    LogicalAndToOr,
-    LogicalOrToAnd,
-    LogicalNotRemoval,
-
-    // Specific boolean mutations
-    BooleanTrueToFalse,
-    BooleanFalseToTrue,
-
-    // Specific unary mutations
-    UnaryMinusRemoval,
-    UnaryPlusMinus,
-    UnaryIncrementToDecrement,
-    UnaryDecrementToIncrement,
-    UnaryPreToPost,
-    UnaryPostToPre,
-
-    // Specific assignment mutations
-    AssignmentAddToSub,
-    AssignmentSubToAdd,
-    AssignmentMulToDiv,
-    AssignmentDivToMul,
-
-    // Dart Null Safety
-    NullCoalescingRemoval,  // ?? → left operand
-    NullAwareAccessRemoval, // ?. → .
-    NullAssertionRemoval,   // x! → x
-    NullCheckToTrue,        // x != null → true
-    NullCheckToFalse,       // x == null → false
-
-    // String mutations
-    StringEmptyToNonEmpty,
-    StringNonEmptyToEmpty,
-
-    // Collection mutations
-    CollectionEmptyCheck,    // isEmpty → isNotEmpty
-    CollectionNotEmptyCheck, // isNotEmpty → isEmpty
-    CollectionAddRemoval,    // .add() → nothing
-    CollectionFirstToLast,   // .first → .last
-    CollectionLastToFirst,   // .last → .first
-
-    // Control Flow mutations
-    ControlFlowIfConditionTrue,
-    ControlFlowIfConditionFalse,
-    ControlFlowRemoveElse,
-    ControlFlowBreakRemoval,
-    ControlFlowContinueRemoval,
-    ControlFlowReturnRemoval,
-
-    // Async mutations
-    AsyncAwaitRemoval,
-    AsyncFutureValueToError,
-
-    // Method Calls
-    MethodCallRemoval,
-
-    // AI-Suggested (custom mutations)
-    AiSuggested,
+/// Declare [`MutationOperator`] along with [`MutationOperator::ALL`], so the
+/// list can't miss a variant
+macro_rules! mutation_operators {
+    (
+        $(#[$enum_attr:meta])*
+        pub enum $name:ident {
+            $($(#[$attr:meta])* $variant:ident,)*
+        }
+    ) => {
+        $(#[$enum_attr])*
+        pub enum $name {
+            $($(#[$attr])* $variant,)*
+        }
+
+        impl $name {
+            /// Every operator, in declaration order
+            pub const ALL: [Self; [$($name::$variant),*].len()] = [$(Self::$variant),*];
+        }
+    };
+}
+
+mutation_operators! {
+    /// Categories of mutation operators
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+    pub enum MutationOperator {
+        // General categories (used by parser)
+        /// Arithmetic Operator
+        Arithmetic,
+        /// Comparison Operator
+        Comparison,
+        /// Logical Operator
+        Logical,
+        /// Boolean Literal
+        Boolean,
+        /// Unary Operator
+        Unary,
+        /// Assignment Operator
+        Assignment,
+        /// Null Safety Operator
+        NullSafety,
+        /// String Literal
+        String,
+        /// Collection Operation
+        Collection,
+        /// Conditional
+        Conditional,
+        /// Return Statement
+        Return,
+        /// Async Operation
+        Async,
+        /// Literal Value
+        Literal,
+        /// Bitwise Operator
+        Bitwise,
+        /// Other
+        Other,
+
+        // Specific arithmetic mutations
+        /// Arithmetic: + → -
+        ArithmeticAddToSub,
+        /// Arithmetic: - → +
+        ArithmeticSubToAdd,
+        /// Arithmetic: * → /
+        ArithmeticMulToDiv,
+        /// Arithmetic: / → *
+        ArithmeticDivToMul,
+        /// Arithmetic: % → *
+        ArithmeticModToMul,
+
+        // Specific comparison mutations
+        /// Comparison: < → <=
+        ComparisonLtToLte,
+        /// Comparison: < → >
+        ComparisonLtToGt,
+        /// Comparison: < → >=
+        ComparisonLtToGte,
+        /// Comparison: <= → <
+        ComparisonLteToLt,
+        /// Comparison: <= → >
+        ComparisonLteToGt,
+        /// Comparison: <= → >=
+        ComparisonLteToGte,
+        /// Comparison: > → >=
+        ComparisonGtToGte,
+        /// Comparison: > → <
+        ComparisonGtToLt,
+        /// Comparison: > → <=
+        ComparisonGtToLte,
+        /// Comparison: >= → >
+        ComparisonGteToGt,
+        /// Comparison: >= → <
+        ComparisonGteToLt,
+        /// Comparison: >= → <=
+        ComparisonGteToLte,
+        /// Comparison: == → !=
+        ComparisonEqToNeq,
+        /// Comparison: != → ==
+        ComparisonNeqToEq,
+
+        // Specific logical mutations
+        /// Logical: && → ||
+        LogicalAndToOr,
+        /// Logical: || → &&
+        LogicalOrToAnd,
+        /// Logical: !x → x
+        LogicalNotRemoval,
+        /// Logical: x → !x
+        LogicalNotInsertion,
+
+        // Specific boolean mutations
+        /// Boolean: true → false
+        BooleanTrueToFalse,
+        /// Boolean: false → true
+        BooleanFalseToTrue,
+
+        // Specific unary mutations
+        /// Unary: -x → x
+        UnaryMinusRemoval,
+        /// Unary: +x → -x
+        UnaryPlusMinus,
+        /// Unary: ++ → --
+        UnaryIncrementToDecrement,
+        /// Unary: -- → ++
+        UnaryDecrementToIncrement,
+        /// Unary: ++x → x++
+        UnaryPreToPost,
+        /// Unary: x++ → ++x
+        UnaryPostToPre,
+
+        // Specific assignment mutations
+        /// Assignment: += → -=
+        AssignmentAddToSub,
+        /// Assignment: -= → +=
+        AssignmentSubToAdd,
+        /// Assignment: *= → /=
+        AssignmentMulToDiv,
+        /// Assignment: /= → *=
+        AssignmentDivToMul,
+
+        // Dart Null Safety
+        /// ?? → left operand
+        NullCoalescingRemoval,
+        /// ?. → .
+        NullAwareAccessRemoval,
+        /// x! → x
+        NullAssertionRemoval,
+        /// x != null → true
+        NullCheckToTrue,
+        /// x == null → false
+        NullCheckToFalse,
+        /// T? x → T x
+        NullableTypeRemoval,
+
+        // String mutations
+        /// String: '' → 'mutated'
+        StringEmptyToNonEmpty,
+        /// String: 'x' → ''
+        StringNonEmptyToEmpty,
+
+        // Collection mutations
+        /// isEmpty → isNotEmpty
+        CollectionEmptyCheck,
+        /// isNotEmpty → isEmpty
+        CollectionNotEmptyCheck,
+        /// .add() → nothing
+        CollectionAddRemoval,
+        /// .first → .last
+        CollectionFirstToLast,
+        /// .last → .first
+        CollectionLastToFirst,
+
+        // Control Flow mutations
+        /// Control: if(x) → if(true)
+        ControlFlowIfConditionTrue,
+        /// Control: if(x) → if(false)
+        ControlFlowIfConditionFalse,
+        /// Control: else removal
+        ControlFlowRemoveElse,
+        /// Control: while(x) → while(false)
+        ControlFlowLoopConditionFalse,
+        /// Control: while(x) → while(true)
+        ControlFlowLoopConditionTrue,
+        /// Control: break removal
+        ControlFlowBreakRemoval,
+        /// Control: continue removal
+        ControlFlowContinueRemoval,
+        /// Control: return removal
+        ControlFlowReturnRemoval,
+        /// return expr → return 0/false/''
+        ControlFlowReturnToDefault,
+
+        // Async mutations
+        /// Async: await removal
+        AsyncAwaitRemoval,
+        /// Async: Future.value → Future.error
+        AsyncFutureValueToError,
+
+        // Method Calls
+        /// Method: call removal
+        MethodCallRemoval,
+
+        // AI-Suggested (custom mutations)
+        /// AI Suggested
+        AiSuggested,
+    }
 }
 
 impl MutationOperator {
@@ -285,6 +419,7 @@ impl MutationOperator {
             Self::LogicalAndToOr => "Logical: && → ||",
             Self::LogicalOrToAnd => "Logical: || → &&",
             Self::LogicalNotRemoval => "Logical: !x → x",
+            Self::LogicalNotInsertion => "Logical: x → !x",
 
             // Boolean
             Self::BooleanTrueToFalse => "Boolean: true → false",
@@ -310,6 +445,7 @@ impl MutationOperator {
             Self::NullAssertionRemoval => "Null: x! → x",
             Self::NullCheckToTrue => "Null: x != null → true",
             Self::NullCheckToFalse => "Null: x == null → false",
+            Self::NullableTypeRemoval => "Null: T? → T",
 
             // String
             Self::StringEmptyToNonEmpty => "String: '' → 'mutated'",
@@ -326,9 +462,12 @@ impl MutationOperator {
             Self::ControlFlowIfConditionTrue => "Control: if(x) → if(true)",
             Self::ControlFlowIfConditionFalse => "Control: if(x) → if(false)",
             Self::ControlFlowRemoveElse => "Control: else removal",
+            Self::ControlFlowLoopConditionFalse => "Control: loop condition → false",
+            Self::ControlFlowLoopConditionTrue => "Control: loop condition → true",
             Self::ControlFlowBreakRemoval => "Control: break removal",
             Self::ControlFlowContinueRemoval => "Control: continue removal",
             Self::ControlFlowReturnRemoval => "Control: return removal",
+            Self::ControlFlowReturnToDefault => "Control: return x → return default",
 
             // Async
             Self::AsyncAwaitRemoval => "Async: await removal",
@@ -341,12 +480,253 @@ impl MutationOperator {
             Self::AiSuggested => "AI Suggested",
         }
     }
+
+    /// Whether this is one of the general category tags rather than a
+    /// specific operator
+    pub const fn is_general(&self) -> bool {
+        matches!(
+            self,
+            Self::Arithmetic
+                | Self::Comparison
+                | Self::Logical
+                | Self::Boolean
+                | Self::Unary
+                | Self::Assignment
+                | Self::NullSafety
+                | Self::String
+                | Self::Collection
+                | Self::Conditional
+                | Self::Return
+                | Self::Async
+                | Self::Literal
+                | Self::Bitwise
+                | Self::Other
+        )
+    }
+
+    /// Get the category this operator belongs to (as used by `--operators`)
+    pub fn category(&self) -> MutatorCategory {
+        match self {
+            Self::Arithmetic
+            | Self::ArithmeticAddToSub
+            | Self::ArithmeticSubToAdd
+            | Self::ArithmeticMulToDiv
+            | Self::ArithmeticDivToMul
+            | Self::ArithmeticModToMul => MutatorCategory::Arithmetic,
+
+            Self::Comparison
+            | Self::ComparisonLtToLte
+            | Self::ComparisonLtToGt
+            | Self::ComparisonLtToGte
+            | Self::ComparisonLteToLt
+            | Self::ComparisonLteToGt
+            | Self::ComparisonLteToGte
+            | Self::ComparisonGtToGte
+            | Self::ComparisonGtToLt
+            | Self::ComparisonGtToLte
+            | Self::ComparisonGteToGt
+            | Self::ComparisonGteToLt
+            | Self::ComparisonGteToLte
+            | Self::ComparisonEqToNeq
+            | Self::ComparisonNeqToEq => MutatorCategory::Comparison,
+
+            Self::Logical
+            | Self::LogicalAndToOr
+            | Self::LogicalOrToAnd
+            | Self::LogicalNotRemoval
+            | Self::LogicalNotInsertion => MutatorCategory::Logical,
+
+            Self::Boolean | Self::BooleanTrueToFalse | Self::BooleanFalseToTrue => {
+                MutatorCategory::Boolean
+            }
+
+            Self::Unary
+            | Self::UnaryMinusRemoval
+            | Self::UnaryPlusMinus
+            | Self::UnaryIncrementToDecrement
+            | Self::UnaryDecrementToIncrement
+            | Self::UnaryPreToPost
+            | Self::UnaryPostToPre => MutatorCategory::Unary,
+
+            Self::Assignment
+            | Self::AssignmentAddToSub
+            | Self::AssignmentSubToAdd
+            | Self::AssignmentMulToDiv
+            | Self::AssignmentDivToMul => MutatorCategory::Assignment,
+
+            Self::NullSafety
+            | Self::NullCoalescingRemoval
+            | Self::NullAwareAccessRemoval
+            | Self::NullAssertionRemoval
+            | Self::NullCheckToTrue
+            | Self::NullCheckToFalse
+            | Self::NullableTypeRemoval => MutatorCategory::NullSafety,
+
+            Self::String | Self::StringEmptyToNonEmpty | Self::StringNonEmptyToEmpty => {
+                MutatorCategory::String
+            }
+
+            Self::Collection
+            | Self::CollectionEmptyCheck
+            | Self::CollectionNotEmptyCheck
+            | Self::CollectionAddRemoval
+            | Self::CollectionFirstToLast
+            | Self::CollectionLastToFirst => MutatorCategory::Collection,
+
+            Self::Conditional
+            | Self::Return
+            | Self::ControlFlowIfConditionTrue
+            | Self::ControlFlowIfConditionFalse
+            | Self::ControlFlowRemoveElse
+            | Self::ControlFlowLoopConditionFalse
+            | Self::ControlFlowLoopConditionTrue
+            | Self::ControlFlowBreakRemoval
+            | Self::ControlFlowContinueRemoval
+            | Self::ControlFlowReturnRemoval
+            | Self::ControlFlowReturnToDefault
+            | Self::MethodCallRemoval => MutatorCategory::ControlFlow,
+
+            Self::Bitwise => MutatorCategory::Bitwise,
+
+            Self::Async | Self::AsyncAwaitRemoval | Self::AsyncFutureValueToError => {
+                MutatorCategory::Async
+            }
+
+            Self::Literal | Self::Other | Self::AiSuggested => MutatorCategory::Other,
+        }
+    }
 }
 
-/// Sample a subset of mutations for quicker testing
-pub fn sample_mutations(mutations: &[Mutation], count: usize) -> Vec<Mutation> {
+/// Stable id of a mutant: a hash of its position and the change it makes.
+/// The column keeps two identical changes on one line (`a && b && c`)
+/// apart
+pub(crate) fn mutation_id(
+    file: &Path,
+    line: usize,
+    column: usize,
+    original: &str,
+    replacement: &str,
+) -> String {
+    format!(
+        "{:x}",
+        md5::compute(format!(
+            "{}:{line}:{column}:{original}:{replacement}",
+            file.display()
+        ))
+    )
+}
+
+/// Drop mutants whose id was already seen, keeping the first. The same
+/// change can be generated twice, e.g. when `--ai` suggests a mutant the
+/// parser already found, and testing it twice would skew the score.
+pub fn dedup_mutations(mutations: &mut Vec<Mutation>) {
+    let mut seen = HashSet::new();
+    mutations.retain(|m| seen.insert(m.id.clone()));
+}
+
+/// Count mutations per operator category
+pub fn count_by_category(mutations: &[Mutation]) -> HashMap<MutatorCategory, usize> {
+    mutations.iter().fold(HashMap::new(), |mut counts, m| {
+        *counts.entry(m.operator.category()).or_insert(0) += 1;
+        counts
+    })
+}
+
+/// Deterministically cap mutations at `max`, taking them round-robin across
+/// files so every file keeps a share of the budget. Source order is preserved.
+pub fn cap_mutations(mutations: &[Mutation], max: usize) -> Vec<Mutation> {
+    if max >= mutations.len() {
+        return mutations.to_vec();
+    }
+
+    let mut file_index: HashMap<&PathBuf, usize> = HashMap::new();
+    let mut by_file: Vec<Vec<usize>> = Vec::new();
+    for (idx, mutation) in mutations.iter().enumerate() {
+        let slot = *file_index
+            .entry(&mutation.location.file)
+            .or_insert_with(|| {
+                by_file.push(Vec::new());
+                by_file.len() - 1
+            });
+        by_file[slot].push(idx);
+    }
+
+    let mut selected = Vec::with_capacity(max);
+    let mut round = 0;
+    while selected.len() < max {
+        for indices in &by_file {
+            if let Some(&idx) = indices.get(round) {
+                selected.push(idx);
+                if selected.len() == max {
+                    break;
+                }
+            }
+        }
+        round += 1;
+    }
+
+    selected.sort_unstable();
+    selected
+        .into_iter()
+        .map(|idx| mutations[idx].clone())
+        .collect()
+}
+
+/// Keep `max` of one file's mutations, picked at even steps through it so
+/// every part of the file is still tested. Source order is preserved.
+pub fn spread_mutations(mutations: Vec<Mutation>, max: usize) -> Vec<Mutation> {
+    let total = mutations.len();
+    if max >= total {
+        return mutations;
+    }
+    mutations
+        .into_iter()
+        .enumerate()
+        // The first index of each of `max` equal steps
+        .filter(|(idx, _)| idx * max % total < max)
+        .map(|(_, mutation)| mutation)
+        .collect()
+}
+
+/// Apply `spread_mutations` to each file with more than `max` mutations.
+/// Returns the kept mutations, grouped by file in order of first
+/// appearance, and each capped file with the number it had.
+pub fn spread_mutations_per_file(
+    mutations: Vec<Mutation>,
+    max: usize,
+) -> (Vec<Mutation>, Vec<(PathBuf, usize)>) {
+    let mut file_index: HashMap<PathBuf, usize> = HashMap::new();
+    let mut by_file: Vec<Vec<Mutation>> = Vec::new();
+    for mutation in mutations {
+        let slot = *file_index
+            .entry(mutation.location.file.clone())
+            .or_insert_with(|| {
+                by_file.push(Vec::new());
+                by_file.len() - 1
+            });
+        by_file[slot].push(mutation);
+    }
+
+    let mut kept = Vec::new();
+    let mut capped = Vec::new();
+    for mut mutations in by_file {
+        if mutations.len() > max {
+            capped.push((mutations[0].location.file.clone(), mutations.len()));
+            // AI suggestions are appended after the parsed mutations
+            mutations.sort_by_key(|m| (m.location.start_line, m.location.byte_start));
+        }
+        kept.extend(spread_mutations(mutations, max));
+    }
+    (kept, capped)
+}
+
+/// Sample a subset of mutations for quicker testing. The same `seed` picks
+/// the same mutants from the same input
+pub fn sample_mutations(mutations: &[Mutation], count: usize, seed: u64) -> Vec<Mutation> {
+    use rand::rngs::StdRng;
     use rand::seq::SliceRandom;
-    let mut rng = rand::thread_rng();
+    use rand::SeedableRng;
+    let mut rng = StdRng::seed_from_u64(seed);
 
     if count >= mutations.len() {
         return mutations.to_vec();
@@ -357,3 +737,392 @@ pub fn sample_mutations(mutations: &[Mutation], count: usize) -> Vec<Mutation> {
     sampled.truncate(count);
     sampled
 }
+
+/// Sample like [`sample_mutations`], but split `count` across operator
+/// categories in proportion to their size, with at least one mutant from
+/// every category when `count` allows. Keeps the input order
+pub fn sample_mutations_stratified(
+    mutations: &[Mutation],
+    count: usize,
+    seed: u64,
+) -> Vec<Mutation> {
+    use rand::rngs::StdRng;
+    use rand::seq::SliceRandom;
+    use rand::SeedableRng;
+    let mut rng = StdRng::seed_from_u64(seed);
+
+    if count >= mutations.len() {
+        return mutations.to_vec();
+    }
+
+    // Group in `MutatorCategory::ALL` order so the seed alone decides the pick
+    let mut groups: Vec<Vec<usize>> = MutatorCategory::ALL
+        .iter()
+        .map(|&category| {
+            (0..mutations.len())
+                .filter(|&idx| mutations[idx].operator.category() == category)
+                .collect::<Vec<_>>()
+        })
+        .filter(|group| !group.is_empty())
+        .collect();
+    for group in &mut groups {
+        group.shuffle(&mut rng);
+    }
+
+    let quotas = if count < groups.len() {
+        // Too small to cover every category: one each from a random few
+        groups.shuffle(&mut rng);
+        (0..groups.len()).map(|i| usize::from(i < count)).collect()
+    } else {
+        stratum_quotas(&groups, count)
+    };
+
+    let mut selected: Vec<usize> = groups
+        .iter()
+        .zip(quotas)
+        .flat_map(|(group, quota)| group[..quota].iter().copied())
+        .collect();
+    selected.sort_unstable();
+    selected
+        .into_iter()
+        .map(|idx| mutations[idx].clone())
+        .collect()
+}
+
+/// One mutant per group, then the rest of `count` in proportion to what
+/// each group has left, rounding by largest remainder
+fn stratum_quotas(groups: &[Vec<usize>], count: usize) -> Vec<usize> {
+    let spare: Vec<usize> = groups.iter().map(|g| g.len() - 1).collect();
+    let spare_total: usize = spare.iter().sum();
+    let budget = count - groups.len();
+
+    let mut quotas: Vec<usize> = spare
+        .iter()
+        .map(|&s| 1 + budget * s / spare_total.max(1))
+        .collect();
+    let mut leftover = count - quotas.iter().sum::<usize>();
+
+    let mut by_remainder: Vec<usize> = (0..groups.len()).collect();
+    by_remainder.sort_by_key(|&i| std::cmp::Reverse(budget * spare[i] % spare_total.max(1)));
+    for i in by_remainder {
+        if leftover == 0 {
+            break;
+        }
+        if quotas[i] < groups[i].len() {
+            quotas[i] += 1;
+            leftover -= 1;
+        }
+    }
+    quotas
+}
+
+/// Flag mutants that cannot change behaviour, so reports can set them apart
+/// from genuine survivors.
+///
+/// The heuristics are deliberately conservative and only look at an
+/// arithmetic operator whose right operand is a bare identity literal:
+///
+/// - `x + 0` ↔ `x - 0` (also `+=`/`-=`)
+/// - `x * 1` ↔ `x / 1` (also `*=`/`/=`)
+///
+/// and at a comparison boundary in a conditional that picks one of its two
+/// operands, where both branches give the same value at the boundary:
+///
+/// - `a < b ? a : b` ↔ `a <= b ? a : b` (also `>`/`>=`, and `? b : a`)
+///
+/// The literal or conditional must end the operand, i.e. be followed by `;`,
+/// `)`, `,`, `]`, `}` or the end of the source, so `x + 0 * y` is never
+/// flagged. Operands of the conditional must be plain names, and its
+/// condition must start an expression, so `x + a < b ? a : b` isn't
+/// flagged. A left literal (`0 - x`, `1 / x`) changes the result and is not
+/// flagged either. String literals used as map keys are not flagged: a
+/// lookup elsewhere usually depends on the exact key, so the mutant is
+/// rarely equivalent.
+pub fn flag_likely_equivalent(mutations: &mut [Mutation], source: &str) {
+    for mutation in mutations {
+        mutation.likely_equivalent =
+            is_identity_operand(mutation, source) || is_min_max_boundary(mutation, source);
+    }
+}
+
+fn is_identity_operand(mutation: &Mutation, source: &str) -> bool {
+    let identity = match mutation.operator {
+        MutationOperator::ArithmeticAddToSub
+        | MutationOperator::ArithmeticSubToAdd
+        | MutationOperator::AssignmentAddToSub
+        | MutationOperator::AssignmentSubToAdd => 0.0,
+        MutationOperator::ArithmeticMulToDiv
+        | MutationOperator::ArithmeticDivToMul
+        | MutationOperator::AssignmentMulToDiv
+        | MutationOperator::AssignmentDivToMul => 1.0,
+        _ => return false,
+    };
+    let Some(operand) = source.get(mutation.location.byte_end..) else {
+        return false;
+    };
+    let operand = operand.trim_start();
+    let literal_len = operand
+        .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .unwrap_or(operand.len());
+    let (literal, rest) = operand.split_at(literal_len);
+
+    ends_operand(rest)
+        && literal
+            .parse::<f64>()
+            .is_ok_and(|value| (value - identity).abs() < f64::EPSILON)
+}
+
+/// Whether a boundary change of the comparison in `a < b ? a : b` or
+/// `a < b ? b : a` (and `<=`, `>`, `>=`) is the whole mutant
+fn is_min_max_boundary(mutation: &Mutation, source: &str) -> bool {
+    if !matches!(
+        mutation.operator,
+        MutationOperator::ComparisonLtToLte
+            | MutationOperator::ComparisonLteToLt
+            | MutationOperator::ComparisonGtToGte
+            | MutationOperator::ComparisonGteToGt
+    ) {
+        return false;
+    }
+    let loc = &mutation.location;
+    let (Some(before), Some(after)) = (source.get(..loc.byte_start), source.get(loc.byte_end..))
+    else {
+        return false;
+    };
+
+    let before = before.trim_end();
+    let (before, left) = before.split_at(
+        before
+            .rfind(|c: char| !is_name_char(c))
+            .map_or(0, |i| i + 1),
+    );
+    let before = before.trim_end();
+    let starts_expression = before.ends_with(['(', '=', ',', '>', ':'])
+        || before
+            .strip_suffix("return")
+            .is_some_and(|rest| rest.chars().next_back().map_or(true, char::is_whitespace));
+
+    let (right, rest) = leading_name(after);
+    let Some(rest) = rest.trim_start().strip_prefix('?') else {
+        return false;
+    };
+    let (first, rest) = leading_name(rest);
+    let Some(rest) = rest.trim_start().strip_prefix(':') else {
+        return false;
+    };
+    let (second, rest) = leading_name(rest);
+
+    starts_expression
+        && !left.is_empty()
+        && !right.is_empty()
+        && left != right
+        && ((first, second) == (left, right) || (first, second) == (right, left))
+        && ends_operand(rest)
+}
+
+/// The name or member access that `text` starts with, after whitespace,
+/// and what follows it
+fn leading_name(text: &str) -> (&str, &str) {
+    let text = text.trim_start();
+    text.split_at(text.find(|c: char| !is_name_char(c)).unwrap_or(text.len()))
+}
+
+const fn is_name_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || matches!(c, '_' | '$' | '.')
+}
+
+/// Whether nothing but `;`, `)`, `,`, `]`, `}` or the end of the source
+/// follows an operand
+fn ends_operand(rest: &str) -> bool {
+    rest.trim_start()
+        .chars()
+        .next()
+        .map_or(true, |c| matches!(c, ';' | ')' | ',' | ']' | '}'))
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::testing::mutant;
+    use super::*;
+
+    fn mutation_at(line: usize, operator: MutationOperator) -> Mutation {
+        mutant().line(line).operator(operator).mutation()
+    }
+
+    #[test]
+    fn test_apply_leaves_source_alone_when_offsets_are_stale() {
+        let mutation = mutant().at(10).column(11).mutation();
+        let source = "int x = 1 + 2;";
+        assert!(mutation.matches(source));
+        assert_eq!(mutation.apply(source), "int x = 1 - 2;");
+
+        // A line inserted above moves the `+`
+        let edited = "int y;\nint x = 1 + 2;";
+        assert!(!mutation.matches(edited));
+        assert_eq!(mutation.apply(edited), edited);
+        assert_eq!(mutation.apply("int x"), "int x");
+    }
+
+    #[test]
+    fn test_overlapping_spans() {
+        let span = |file: &str, start, end| {
+            mutant()
+                .file(file)
+                .span(start, end)
+                .column(start + 1)
+                .change(&"x".repeat(end - start), "")
+                .mutation()
+        };
+        let outer = span("lib/a.dart", 10, 30);
+
+        assert!(outer.overlaps(&span("lib/a.dart", 15, 16)));
+        assert!(outer.overlaps(&span("lib/a.dart", 25, 40)));
+        assert!(!outer.overlaps(&span("lib/a.dart", 30, 31)));
+        assert!(!outer.overlaps(&span("lib/a.dart", 5, 10)));
+        assert!(!outer.overlaps(&span("lib/b.dart", 15, 16)));
+    }
+
+    #[test]
+    fn test_operator_list_covers_every_category_once() {
+        let mut names: Vec<&str> = MutationOperator::ALL.iter().map(|op| op.name()).collect();
+        names.sort_unstable();
+        names.dedup();
+        assert_eq!(names.len(), MutationOperator::ALL.len());
+
+        for category in MutatorCategory::ALL {
+            assert!(
+                MutationOperator::ALL
+                    .iter()
+                    .any(|op| op.category() == category),
+                "{category:?} has no operators"
+            );
+        }
+    }
+
+    #[test]
+    fn test_every_operator_is_selectable_by_its_category() {
+        for operator in MutationOperator::ALL {
+            let category = operator.category();
+            assert!(MutatorCategory::ALL.contains(&category));
+            assert_eq!(
+                MutatorCategory::from_str(category.as_str()),
+                Some(category),
+                "--operators {} doesn't select {}",
+                category.as_str(),
+                operator.name()
+            );
+        }
+    }
+
+    #[test]
+    fn test_dedup_keeps_first_of_each_id() {
+        let parsed = mutation_at(3, MutationOperator::ArithmeticAddToSub);
+        let mut suggested = parsed.clone();
+        suggested.operator = MutationOperator::AiSuggested;
+        suggested.ai_suggested = true;
+        let other_line = mutation_at(4, MutationOperator::ArithmeticAddToSub);
+        let mut mutations = vec![parsed, suggested, other_line];
+
+        dedup_mutations(&mut mutations);
+
+        assert_eq!(mutations.len(), 2);
+        assert!(!mutations[0].ai_suggested);
+        assert_eq!(mutations[1].location.start_line, 4);
+    }
+
+    #[test]
+    fn test_spread_keeps_mutants_from_the_whole_file() {
+        let mutations: Vec<Mutation> = (1..=10)
+            .map(|line| mutation_at(line, MutationOperator::ArithmeticAddToSub))
+            .collect();
+        let lines = |max| -> Vec<usize> {
+            spread_mutations(mutations.clone(), max)
+                .iter()
+                .map(|m| m.location.start_line)
+                .collect()
+        };
+
+        assert_eq!(lines(4), vec![1, 4, 6, 9]);
+        assert_eq!(lines(5), vec![1, 3, 5, 7, 9]);
+        assert_eq!(lines(1), vec![1]);
+        assert_eq!(lines(20).len(), 10);
+    }
+
+    #[test]
+    fn test_spread_per_file_caps_only_large_files() {
+        let mut mutations: Vec<Mutation> = (1..=6)
+            .map(|line| mutation_at(line, MutationOperator::ArithmeticAddToSub))
+            .collect();
+        let mut other = mutation_at(1, MutationOperator::ArithmeticAddToSub);
+        other.location.file = PathBuf::from("lib/other.dart");
+        mutations.insert(2, other);
+
+        let (kept, capped) = spread_mutations_per_file(mutations, 3);
+
+        let kept: Vec<(&str, usize)> = kept
+            .iter()
+            .map(|m| (m.location.file.to_str().unwrap(), m.location.start_line))
+            .collect();
+        assert_eq!(
+            kept,
+            vec![
+                ("lib/calc.dart", 1),
+                ("lib/calc.dart", 3),
+                ("lib/calc.dart", 5),
+                ("lib/other.dart", 1)
+            ]
+        );
+        assert_eq!(capped, vec![(PathBuf::from("lib/calc.dart"), 6)]);
+    }
+
+    #[test]
+    fn test_same_seed_samples_same_mutants() {
+        let mutations: Vec<Mutation> = (1..=50)
+            .map(|line| mutation_at(line, MutationOperator::ArithmeticAddToSub))
+            .collect();
+        let lines = |sample: Vec<Mutation>| -> Vec<usize> {
+            sample.iter().map(|m| m.location.start_line).collect()
+        };
+
+        let first = lines(sample_mutations(&mutations, 10, 42));
+
+        assert_eq!(first.len(), 10);
+        assert_eq!(first, lines(sample_mutations(&mutations, 10, 42)));
+        assert_ne!(first, lines(sample_mutations(&mutations, 10, 43)));
+    }
+
+    #[test]
+    fn test_stratified_sample_covers_rare_categories() {
+        // 90 arithmetic, 8 comparison, 2 null-safety mutants. After one each,
+        // the other 7 split 6.4 / 0.5 / 0.1, rounded by largest remainder
+        let mutations: Vec<Mutation> = (1..=100)
+            .map(|line| {
+                let operator = match line {
+                    1..=90 => MutationOperator::ArithmeticAddToSub,
+                    91..=98 => MutationOperator::ComparisonGtToGte,
+                    _ => MutationOperator::NullAssertionRemoval,
+                };
+                mutation_at(line, operator)
+            })
+            .collect();
+        let count = |sample: &[Mutation], category: MutatorCategory| {
+            sample
+                .iter()
+                .filter(|m| m.operator.category() == category)
+                .count()
+        };
+
+        for seed in 0..20 {
+            let sample = sample_mutations_stratified(&mutations, 10, seed);
+
+            assert_eq!(sample.len(), 10);
+            assert_eq!(count(&sample, MutatorCategory::Arithmetic), 7);
+            assert_eq!(count(&sample, MutatorCategory::Comparison), 2);
+            assert_eq!(count(&sample, MutatorCategory::NullSafety), 1);
+            assert!(sample
+                .windows(2)
+                .all(|w| w[0].location.start_line < w[1].location.start_line));
+        }
+    }
+}
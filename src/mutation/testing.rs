@@ -0,0 +1,152 @@
+//! Mutants for tests
+//!
+//! [`mutant`] starts from a killed `+` → `-` at the start of line 1 of
+//! `lib/calc.dart`; a test sets only what it checks.
+
+use super::{MutantStatus, Mutation, MutationOperator};
+use crate::runner::MutantTestResult;
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// A mutant under construction
+#[derive(Debug, Clone)]
+pub struct MutantBuilder {
+    file: PathBuf,
+    byte_start: usize,
+    byte_end: Option<usize>,
+    line: usize,
+    column: usize,
+    original: String,
+    mutated: String,
+    operator: MutationOperator,
+    status: MutantStatus,
+    duration: Duration,
+    killed_by: Option<String>,
+}
+
+/// A killed `+` → `-` at the start of `lib/calc.dart`
+pub fn mutant() -> MutantBuilder {
+    MutantBuilder {
+        file: PathBuf::from("lib/calc.dart"),
+        byte_start: 0,
+        byte_end: None,
+        line: 1,
+        column: 1,
+        original: "+".to_owned(),
+        mutated: "-".to_owned(),
+        operator: MutationOperator::ArithmeticAddToSub,
+        status: MutantStatus::Killed,
+        duration: Duration::ZERO,
+        killed_by: None,
+    }
+}
+
+impl MutantBuilder {
+    /// The mutated file
+    pub fn file(mut self, file: impl Into<PathBuf>) -> Self {
+        self.file = file.into();
+        self
+    }
+
+    /// The 1-based line the mutant starts on
+    pub fn line(mut self, line: usize) -> Self {
+        self.line = line;
+        self
+    }
+
+    /// The 1-based column the mutant starts at
+    pub fn column(mut self, column: usize) -> Self {
+        self.column = column;
+        self
+    }
+
+    /// The byte offset of the replaced text, which ends `original.len()`
+    /// bytes later unless [`Self::span`] says otherwise
+    pub fn at(mut self, byte_start: usize) -> Self {
+        self.byte_start = byte_start;
+        self
+    }
+
+    /// The byte range of the replaced text
+    pub fn span(mut self, byte_start: usize, byte_end: usize) -> Self {
+        self.byte_start = byte_start;
+        self.byte_end = Some(byte_end);
+        self
+    }
+
+    /// The replaced text and its replacement
+    pub fn change(mut self, original: &str, mutated: &str) -> Self {
+        self.original = original.to_owned();
+        self.mutated = mutated.to_owned();
+        self
+    }
+
+    /// Place the mutant at the first occurrence of its original text in
+    /// `source`, with the line and column to match
+    ///
+    /// # Panics
+    ///
+    /// If `source` doesn't contain the original text
+    #[allow(clippy::unwrap_used)]
+    pub fn found_in(mut self, source: &str) -> Self {
+        let start = source.find(&self.original).unwrap();
+        let line_start = source[..start].rfind('\n').map_or(0, |i| i + 1);
+        self.byte_start = start;
+        self.byte_end = None;
+        self.line = source[..start].matches('\n').count() + 1;
+        self.column = start - line_start + 1;
+        self
+    }
+
+    /// The operator that made the mutant
+    pub const fn operator(mut self, operator: MutationOperator) -> Self {
+        self.operator = operator;
+        self
+    }
+
+    /// The outcome of testing the mutant
+    pub const fn status(mut self, status: MutantStatus) -> Self {
+        self.status = status;
+        self
+    }
+
+    /// The time spent testing the mutant
+    pub const fn duration(mut self, duration: Duration) -> Self {
+        self.duration = duration;
+        self
+    }
+
+    /// The test that killed the mutant
+    pub fn killed_by(mut self, test: &str) -> Self {
+        self.killed_by = Some(test.to_owned());
+        self
+    }
+
+    /// The mutation
+    pub fn mutation(&self) -> Mutation {
+        Mutation::new(
+            self.file.clone(),
+            self.byte_start,
+            self.byte_end
+                .unwrap_or(self.byte_start + self.original.len()),
+            self.line,
+            self.column,
+            self.original.clone(),
+            self.mutated.clone(),
+            self.operator,
+        )
+    }
+
+    /// The result of testing the mutation
+    pub fn result(&self) -> MutantTestResult {
+        MutantTestResult {
+            mutation: self.mutation(),
+            status: self.status,
+            duration: self.duration,
+            output: None,
+            error: None,
+            retries: 0,
+            killed_by: self.killed_by.clone(),
+        }
+    }
+}
@@ -0,0 +1,176 @@
+//! Dart libraries split across `part` files
+//!
+//! A part file (`part of 'model.dart';`) shares the scope of the library
+//! that includes it with `part 'model.g.dart';`. It can't be analyzed on
+//! its own, and tests only reach it through that library, so a mutant in
+//! one file of a library is checked against all of them.
+
+use super::parse_dart;
+use std::path::{Path, PathBuf};
+use tree_sitter::Node;
+use walkdir::WalkDir;
+
+/// The library a `part of` directive names
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum PartOf {
+    /// `part of 'model.dart';`
+    Uri(String),
+    /// `part of app.model;`, matching a `library app.model;` directive
+    Name(String),
+}
+
+/// Every file of `library`, which `file` belongs to: the defining file
+/// first, then its parts. A file with no `part` or `part of` directives is
+/// a library of its own.
+pub fn library_files(file: &Path, library: &Path) -> Vec<PathBuf> {
+    let mut files = vec![library.to_path_buf()];
+    files.extend(part_files(library));
+    if !files.iter().any(|f| same_file(f, file)) {
+        files.push(file.to_path_buf());
+    }
+    files
+}
+
+/// The library a part file belongs to; `None` if `file` isn't a part or its
+/// library can't be found
+pub fn owning_library(file: &Path, project_root: &Path) -> Option<PathBuf> {
+    let source = std::fs::read_to_string(file).ok()?;
+    match part_of(&source)? {
+        PartOf::Uri(uri) if is_relative_uri(&uri) => {
+            Some(file.parent().unwrap_or(file).join(uri)).filter(|library| library.is_file())
+        }
+        // Named (or package:) libraries: find the file that lists this part
+        PartOf::Uri(_) | PartOf::Name(_) => WalkDir::new(project_root)
+            .into_iter()
+            .filter_entry(|entry| {
+                entry.depth() == 0 || !entry.file_name().to_string_lossy().starts_with('.')
+            })
+            .filter_map(Result::ok)
+            .map(walkdir::DirEntry::into_path)
+            .filter(|path| path.extension().is_some_and(|ext| ext == "dart"))
+            .find(|library| part_files(library).iter().any(|part| same_file(part, file))),
+    }
+}
+
+/// The files a library includes with relative `part` directives
+fn part_files(library: &Path) -> Vec<PathBuf> {
+    let Ok(source) = std::fs::read_to_string(library) else {
+        return Vec::new();
+    };
+    let dir = library.parent().unwrap_or(library);
+    directives(&source, "part_directive")
+        .into_iter()
+        .filter_map(|directive| directive.uri)
+        .filter(|uri| is_relative_uri(uri))
+        .map(|uri| dir.join(uri))
+        .collect()
+}
+
+fn part_of(source: &str) -> Option<PartOf> {
+    let directive = directives(source, "part_of_directive").into_iter().next()?;
+    directive
+        .uri
+        .map(PartOf::Uri)
+        .or_else(|| directive.name.map(PartOf::Name))
+}
+
+/// The URI or dotted name of a top-level directive
+struct Directive {
+    uri: Option<String>,
+    name: Option<String>,
+}
+
+fn directives(source: &str, kind: &str) -> Vec<Directive> {
+    let Ok(tree) = parse_dart(source) else {
+        return Vec::new();
+    };
+    let root = tree.root_node();
+    let mut cursor = root.walk();
+    let text = |node: Node<'_>| node.utf8_text(source.as_bytes()).ok().map(str::to_owned);
+    root.children(&mut cursor)
+        .filter(|node| node.kind() == kind)
+        .map(|node| {
+            let mut cursor = node.walk();
+            let children: Vec<Node<'_>> = node.named_children(&mut cursor).collect();
+            let child = |kind: &str| children.iter().find(|c| c.kind() == kind).copied();
+            Directive {
+                uri: child("uri")
+                    .and_then(text)
+                    .map(|uri| uri.trim_matches(|c| c == '\'' || c == '"').to_owned()),
+                name: child("dotted_identifier_list").and_then(text),
+            }
+        })
+        .collect()
+}
+
+/// `dart:` and `package:` URIs aren't paths relative to the file
+fn is_relative_uri(uri: &str) -> bool {
+    !uri.contains(':')
+}
+
+fn same_file(a: &Path, b: &Path) -> bool {
+    match (a.canonicalize(), b.canonicalize()) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => a == b,
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parts_belong_to_their_library() {
+        let dir = tempfile::tempdir().unwrap();
+        for (file, source) in [
+            (
+                "lib/src/model.dart",
+                "import 'dart:math';\npart 'model.g.dart';\npart 'parts/extra.dart';\n",
+            ),
+            (
+                "lib/src/model.g.dart",
+                "part of 'model.dart';\nint x = 1;\n",
+            ),
+            ("lib/src/parts/extra.dart", "part of \"../model.dart\";\n"),
+            (
+                "lib/legacy.dart",
+                "library app.legacy;\npart 'legacy_part.dart';\n",
+            ),
+            ("lib/legacy_part.dart", "part of app.legacy;\n"),
+            ("lib/plain.dart", "int y = 2;\n"),
+        ] {
+            let path = dir.path().join(file);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, source).unwrap();
+        }
+        let root = dir.path();
+        let names = |file: &str| -> Vec<String> {
+            let file = root.join(file);
+            let library = owning_library(&file, root).unwrap_or_else(|| file.clone());
+            library_files(&file, &library)
+                .iter()
+                .map(|f| {
+                    let f = f.canonicalize().unwrap();
+                    let root = root.canonicalize().unwrap();
+                    f.strip_prefix(root).unwrap().display().to_string()
+                })
+                .collect()
+        };
+
+        let model = [
+            "lib/src/model.dart",
+            "lib/src/model.g.dart",
+            "lib/src/parts/extra.dart",
+        ];
+        assert_eq!(names("lib/src/model.dart"), model);
+        assert_eq!(names("lib/src/model.g.dart"), model);
+        assert_eq!(names("lib/src/parts/extra.dart"), model);
+        assert_eq!(
+            names("lib/legacy_part.dart"),
+            ["lib/legacy.dart", "lib/legacy_part.dart"]
+        );
+        assert_eq!(names("lib/plain.dart"), ["lib/plain.dart"]);
+        assert!(owning_library(&root.join("lib/plain.dart"), root).is_none());
+    }
+}
@@ -3,40 +3,56 @@
 //! This module parses Dart source files and identifies locations where
 //! mutations can be applied safely and meaningfully.
 
-use crate::mutation::{Mutation, MutationOperator};
+pub mod library;
+mod switch_expression;
+
+use crate::mutation::{flag_likely_equivalent, Mutation, MutationOperator};
 use anyhow::{Context, Result};
+use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
 use tree_sitter::{Node, Parser, Tree};
 use walkdir::WalkDir;
 
 /// Discover all Dart files in the given path, excluding specified patterns
-pub fn discover_dart_files(path: &Path, exclude_patterns: &[String]) -> Result<Vec<PathBuf>> {
+/// and files whose names end in one of `generated_suffixes`
+pub fn discover_dart_files(
+    path: &Path,
+    include_glob: &str,
+    exclude_patterns: &[String],
+    generated_suffixes: &[String],
+) -> Result<Vec<PathBuf>> {
+    let include = glob::Pattern::new(include_glob)
+        .with_context(|| format!("Invalid --glob pattern: {include_glob}"))?;
     let mut files = Vec::new();
 
+    // Sorted so mutation order, and with it capping and sampling, doesn't
+    // depend on the file system's directory order
     for entry in WalkDir::new(path)
         .follow_links(true)
+        .sort_by_file_name()
         .into_iter()
         .filter_map(|e| e.ok())
     {
         let file_path = entry.path();
 
-        // Only include .dart files
-        if file_path.extension().map_or(false, |ext| ext == "dart") {
+        // Only include .dart files matching the include glob, relative to the project
+        let relative = file_path.strip_prefix(path).unwrap_or(file_path);
+        if file_path.extension().map_or(false, |ext| ext == "dart")
+            && include.matches_path(relative)
+        {
             let path_str = file_path.to_string_lossy();
 
             // Check exclusion patterns
-            let excluded = exclude_patterns.iter().any(|pattern| {
-                glob::Pattern::new(pattern)
-                    .map(|p| p.matches(&path_str))
-                    .unwrap_or(false)
-            });
+            let excluded = exclude_patterns
+                .iter()
+                .any(|pattern| glob::Pattern::new(pattern).map_or(false, |p| p.matches(&path_str)));
 
             if !excluded {
-                // Skip generated files by convention
+                // Skip generated files by suffix
                 let filename = file_path.file_name().unwrap_or_default().to_string_lossy();
-                if !filename.ends_with(".g.dart")
-                    && !filename.ends_with(".freezed.dart")
-                    && !filename.ends_with(".mocks.dart")
+                if !generated_suffixes
+                    .iter()
+                    .any(|suffix| filename.ends_with(suffix.as_str()))
                 {
                     files.push(file_path.to_path_buf());
                 }
@@ -47,21 +63,107 @@ pub fn discover_dart_files(path: &Path, exclude_patterns: &[String]) -> Result<V
     Ok(files)
 }
 
+/// Options controlling which parts of a file are mutated
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ParseOptions {
+    /// Leave expressions inside `assert(...)` clauses alone, including
+    /// those in constructor initializer lists
+    pub skip_asserts: bool,
+    /// Remove `?` from nullable variable and parameter types. Opt-in, since
+    /// many of these mutants fail to compile.
+    pub nullable_types: bool,
+    /// Also remove `print(...)`/`debugPrint(...)` statements, which are
+    /// usually equivalent mutants
+    pub include_print_calls: bool,
+}
+
 /// Parse a Dart file and find all possible mutation locations
-pub fn parse_and_find_mutations(file_path: &Path) -> Result<Vec<Mutation>> {
+pub fn parse_and_find_mutations(file_path: &Path, options: &ParseOptions) -> Result<Vec<Mutation>> {
     let source = std::fs::read_to_string(file_path)
         .with_context(|| format!("Failed to read file: {}", file_path.display()))?;
 
     let tree = parse_dart(&source)?;
     let mut mutations = Vec::new();
 
-    find_mutations_in_tree(&tree, &source, file_path, &mut mutations);
+    find_mutations_in_tree(&tree, &source, file_path, options, &mut mutations);
 
+    let mut mutations = normalize_mutations(mutations);
+    remove_suppressed(&mut mutations, &source);
+    flag_likely_equivalent(&mut mutations, &source);
     Ok(mutations)
 }
 
+/// Drop mutations on lines opted out with a suppression comment:
+/// `// dart_mutant:disable-line`, `// dart_mutant:disable-next-line`, or a
+/// `// dart_mutant:disable` ... `// dart_mutant:enable` region
+fn remove_suppressed(mutations: &mut Vec<Mutation>, source: &str) {
+    let disabled = disabled_lines(source);
+    if !disabled.is_empty() {
+        mutations.retain(|m| !disabled.contains(&m.location.start_line));
+    }
+}
+
+/// 1-based line numbers covered by suppression comments
+fn disabled_lines(source: &str) -> HashSet<usize> {
+    let mut disabled = HashSet::new();
+    let mut in_region = false;
+
+    for (index, line) in source.lines().enumerate() {
+        let line_number = index + 1;
+        let directive = line.find("dart_mutant:").and_then(|pos| {
+            line[..pos]
+                .trim_end()
+                .ends_with("//")
+                .then(|| line[pos + "dart_mutant:".len()..].split_whitespace().next())
+                .flatten()
+        });
+
+        match directive {
+            Some("disable-line") => {
+                disabled.insert(line_number);
+            }
+            Some("disable-next-line") => {
+                disabled.insert(line_number + 1);
+            }
+            Some("disable") => in_region = true,
+            Some("enable") => in_region = false,
+            _ => {}
+        }
+        if in_region {
+            disabled.insert(line_number);
+        }
+    }
+
+    disabled
+}
+
+/// Collapse mutations that make the same replacement over the same byte
+/// range, keeping the most specific operator tag
+fn normalize_mutations(mutations: Vec<Mutation>) -> Vec<Mutation> {
+    let mut seen: HashMap<(usize, usize, String), usize> = HashMap::new();
+    let mut normalized: Vec<Mutation> = Vec::with_capacity(mutations.len());
+
+    for mutation in mutations {
+        let key = (
+            mutation.location.byte_start,
+            mutation.location.byte_end,
+            mutation.mutated.clone(),
+        );
+        if let Some(&idx) = seen.get(&key) {
+            if normalized[idx].operator.is_general() && !mutation.operator.is_general() {
+                normalized[idx] = mutation;
+            }
+        } else {
+            seen.insert(key, normalized.len());
+            normalized.push(mutation);
+        }
+    }
+
+    normalized
+}
+
 /// Parse Dart source code into a tree-sitter AST
-fn parse_dart(source: &str) -> Result<Tree> {
+pub fn parse_dart(source: &str) -> Result<Tree> {
     let mut parser = Parser::new();
     parser
         .set_language(&tree_sitter_dart::language())
@@ -77,38 +179,58 @@ fn find_mutations_in_tree(
     tree: &Tree,
     source: &str,
     file_path: &Path,
+    options: &ParseOptions,
     mutations: &mut Vec<Mutation>,
 ) {
     let root = tree.root_node();
-    find_mutations_in_node(root, source, file_path, mutations);
+    find_mutations_in_node(root, source, file_path, options, mutations);
 }
 
 fn find_mutations_in_node(
     node: Node<'_>,
     source: &str,
     file_path: &Path,
+    options: &ParseOptions,
     mutations: &mut Vec<Mutation>,
 ) {
     let node_kind = node.kind();
 
     // Match different node types for mutation opportunities
     match node_kind {
+        // Assertions (statements and constructor initializers) encode
+        // invariants, and are mutated unless `--skip-asserts` is set
+        "assertion" if options.skip_asserts => return,
+
         // Binary expressions: arithmetic, comparison, logical
         "binary_expression" | "multiplicative_expression" | "additive_expression" => {
             find_binary_mutations(&node, source, file_path, mutations);
         }
 
-        "relational_expression" | "equality_expression" => {
+        "relational_expression" => {
             find_comparison_mutations(&node, source, file_path, mutations);
         }
 
+        "equality_expression" => {
+            find_comparison_mutations(&node, source, file_path, mutations);
+            find_null_check_mutation(&node, source, file_path, mutations);
+        }
+
         "logical_and_expression" | "logical_or_expression" => {
             find_logical_mutations(&node, source, file_path, mutations);
         }
 
+        // Bitwise and shift operators; `&&`/`||` are logical_* nodes above
+        "bitwise_and_expression"
+        | "bitwise_or_expression"
+        | "bitwise_xor_expression"
+        | "shift_expression" => {
+            find_bitwise_mutations(&node, source, file_path, mutations);
+        }
+
         // Unary expressions: !, -, ++, --
         "unary_expression" | "prefix_expression" | "postfix_expression" => {
             find_unary_mutations(&node, source, file_path, mutations);
+            find_increment_position_mutation(&node, source, file_path, mutations);
         }
 
         // Boolean literals
@@ -116,18 +238,116 @@ fn find_mutations_in_node(
             mutations.push(create_boolean_mutation(&node, source, file_path));
         }
 
+        // Collection properties: .isEmpty, .isNotEmpty, .first, .last
+        "unconditional_assignable_selector" => {
+            find_collection_property_mutation(&node, source, file_path, mutations);
+        }
+
+        "conditional_assignable_selector" => {
+            find_collection_property_mutation(&node, source, file_path, mutations);
+            find_null_aware_access_mutation(&node, file_path, mutations);
+        }
+
+        // Compound assignments: +=, -=, *=, /=
+        "assignment_expression" => {
+            find_compound_assignment_mutation(&node, file_path, mutations);
+        }
+
         // Null-aware operators
         "if_null_expression" => {
             find_null_coalescing_mutation(&node, source, file_path, mutations);
         }
 
-        "conditional_member_access" => {
-            find_null_aware_access_mutation(&node, source, file_path, mutations);
+        // Null assertions: `user!.name`, `value!`
+        "selector" => {
+            find_null_assertion_mutation(&node, source, file_path, mutations);
+        }
+
+        // Nullable type annotations: `int? x`
+        "?" if options.nullable_types => {
+            find_nullable_type_mutation(&node, file_path, mutations);
         }
 
         // If statements
         "if_statement" => {
             find_if_statement_mutations(&node, source, file_path, mutations);
+            find_else_removal_mutation(&node, source, file_path, mutations);
+            find_condition_negation_mutation(&node, source, file_path, mutations);
+        }
+
+        // `break;` / `continue;` inside loops
+        "break_statement" | "continue_statement" => {
+            find_loop_jump_removal(&node, source, file_path, mutations);
+        }
+
+        // Switch statements: swap the bodies of neighbouring cases
+        "switch_block" => {
+            find_switch_case_swaps(&node, source, file_path, mutations);
+        }
+
+        // The grammar reads the `switch` of a switch expression as a name
+        "identifier" if get_node_text(&node, source) == "switch" => {
+            find_switch_expression_swaps(&node, source, file_path, mutations);
+        }
+
+        // Ternaries: force the condition either way and swap the branches
+        "conditional_expression" => {
+            find_conditional_expression_mutations(&node, source, file_path, mutations);
+            find_condition_negation_mutation(&node, source, file_path, mutations);
+        }
+
+        // Loop conditions: never run, or never stop
+        "while_statement" | "do_statement" => {
+            find_loop_condition_mutations(&node, source, file_path, mutations);
+            find_condition_negation_mutation(&node, source, file_path, mutations);
+        }
+
+        "for_loop_parts" => {
+            find_loop_condition_mutations(&node, source, file_path, mutations);
+        }
+
+        // Collection literals: drop each element in turn
+        "list_literal" | "set_or_map_literal" => {
+            find_collection_element_removals(&node, source, file_path, mutations);
+        }
+
+        "spread_element" => {
+            find_null_aware_spread_mutation(&node, file_path, mutations);
+        }
+
+        // `if (x) value` inside a collection literal
+        "if_element" => {
+            find_if_element_mutations(&node, source, file_path, mutations);
+        }
+
+        // `Future.value(x)` → `Future.error(x)`
+        "member_access" | "constructor_invocation" => {
+            find_future_value_mutation(&node, source, file_path, mutations);
+        }
+
+        // `await foo()` → `foo()`; `await for` is a for_statement instead
+        "await_expression" => {
+            find_await_removal_mutation(&node, source, file_path, mutations);
+        }
+
+        // Calls whose result is discarded: `list.sort();`
+        "expression_statement" => {
+            find_call_removal_mutation(&node, source, file_path, options, mutations);
+        }
+
+        // `buffer..write(a)..write(b)`: drop one section
+        "cascade_section" => {
+            find_cascade_section_removal(&node, source, file_path, mutations);
+        }
+
+        // Guard clauses: `if (x < 0) return 0;`
+        "return_statement" => {
+            find_early_return_removal(&node, source, file_path, mutations);
+        }
+
+        // Function bodies with a primitive return type
+        "function_body" => {
+            find_return_default_mutations(&node, source, file_path, mutations);
         }
 
         // String literals
@@ -141,7 +361,7 @@ fn find_mutations_in_node(
     // Recurse into children
     let mut cursor = node.walk();
     for child in node.children(&mut cursor) {
-        find_mutations_in_node(child, source, file_path, mutations);
+        find_mutations_in_node(child, source, file_path, options, mutations);
     }
 }
 
@@ -231,6 +451,70 @@ fn find_comparison_mutations(
     }
 }
 
+fn find_bitwise_mutations(
+    node: &Node<'_>,
+    source: &str,
+    file_path: &Path,
+    mutations: &mut Vec<Mutation>,
+) {
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        let text = get_node_text(&child, source);
+
+        let replacement = match text {
+            "&" => "|",
+            "|" => "&",
+            "^" => "&",
+            "<<" => ">>",
+            ">>" => "<<",
+            _ => continue,
+        };
+
+        mutations.push(Mutation::new(
+            file_path.to_path_buf(),
+            child.start_byte(),
+            child.end_byte(),
+            child.start_position().row + 1,
+            child.start_position().column + 1,
+            text.to_owned(),
+            replacement.to_owned(),
+            MutationOperator::Bitwise,
+        ));
+    }
+}
+
+/// `x != null` -> `true`, `x == null` -> `false` (either operand order)
+fn find_null_check_mutation(
+    node: &Node<'_>,
+    source: &str,
+    file_path: &Path,
+    mutations: &mut Vec<Mutation>,
+) {
+    let (Some(left), Some(operator), Some(right)) = (node.child(0), node.child(1), node.child(2))
+    else {
+        return;
+    };
+    if left.kind() != "null_literal" && right.kind() != "null_literal" {
+        return;
+    }
+
+    let (replacement, mutation_operator) = match get_node_text(&operator, source) {
+        "!=" => ("true", MutationOperator::NullCheckToTrue),
+        "==" => ("false", MutationOperator::NullCheckToFalse),
+        _ => return,
+    };
+    mutations.push(Mutation::new(
+        file_path.to_path_buf(),
+        node.start_byte(),
+        node.end_byte(),
+        node.start_position().row + 1,
+        node.start_position().column + 1,
+        get_node_text(node, source).to_owned(),
+        replacement.to_owned(),
+        mutation_operator,
+    ));
+}
+
 fn find_logical_mutations(
     node: &Node<'_>,
     source: &str,
@@ -312,6 +596,66 @@ fn find_unary_mutations(
     }
 }
 
+/// `++x` <-> `x++`, only where the expression's value is read: as a
+/// statement or a for-loop update both forms behave the same
+fn find_increment_position_mutation(
+    node: &Node<'_>,
+    source: &str,
+    file_path: &Path,
+    mutations: &mut Vec<Mutation>,
+) {
+    let is_postfix = node.kind() == "postfix_expression";
+    let (operator, operand) = if is_postfix {
+        (node.child(1), node.child(0))
+    } else {
+        (node.child(0), node.child(1))
+    };
+    let (Some(operator), Some(operand)) = (operator, operand) else {
+        return;
+    };
+
+    let op = get_node_text(&operator, source);
+    if !matches!(op, "++" | "--") {
+        return;
+    }
+    // `x`, not `this.x` or `a[i]`
+    let simple = operand.kind() == "identifier"
+        || (operand.kind() == "assignable_expression"
+            && operand.named_child_count() == 1
+            && operand
+                .named_child(0)
+                .is_some_and(|n| n.kind() == "identifier"));
+    if !simple {
+        return;
+    }
+    let value_unused = node.parent().is_some_and(|parent| {
+        parent.kind() == "expression_statement"
+            || parent
+                .child_by_field_name("update")
+                .is_some_and(|update| update.id() == node.id())
+    });
+    if value_unused {
+        return;
+    }
+
+    let name = get_node_text(&operand, source);
+    let (replacement, mutation_operator) = if is_postfix {
+        (format!("{}{}", op, name), MutationOperator::UnaryPostToPre)
+    } else {
+        (format!("{}{}", name, op), MutationOperator::UnaryPreToPost)
+    };
+    mutations.push(Mutation::new(
+        file_path.to_path_buf(),
+        node.start_byte(),
+        node.end_byte(),
+        node.start_position().row + 1,
+        node.start_position().column + 1,
+        get_node_text(node, source).to_owned(),
+        replacement,
+        mutation_operator,
+    ));
+}
+
 fn create_boolean_mutation(node: &Node<'_>, source: &str, file_path: &Path) -> Mutation {
     let original = get_node_text(node, source);
     let (replacement, operator) = if original == "true" {
@@ -332,52 +676,141 @@ fn create_boolean_mutation(node: &Node<'_>, source: &str, file_path: &Path) -> M
     )
 }
 
+/// Swap `isEmpty`/`isNotEmpty` and `first`/`last`. Only the exact property
+/// name matches, so `firstWhere` and `lastIndexOf` are left alone.
+fn find_collection_property_mutation(
+    node: &Node<'_>,
+    source: &str,
+    file_path: &Path,
+    mutations: &mut Vec<Mutation>,
+) {
+    let Some(name) = node.named_child(0).filter(|n| n.kind() == "identifier") else {
+        return;
+    };
+    let (mutated, operator) = match get_node_text(&name, source) {
+        "isEmpty" => ("isNotEmpty", MutationOperator::CollectionEmptyCheck),
+        "isNotEmpty" => ("isEmpty", MutationOperator::CollectionNotEmptyCheck),
+        "first" => ("last", MutationOperator::CollectionFirstToLast),
+        "last" => ("first", MutationOperator::CollectionLastToFirst),
+        _ => return,
+    };
+
+    mutations.push(Mutation::new(
+        file_path.to_path_buf(),
+        name.start_byte(),
+        name.end_byte(),
+        name.start_position().row + 1,
+        name.start_position().column + 1,
+        get_node_text(&name, source).to_owned(),
+        mutated.to_owned(),
+        operator,
+    ));
+}
+
+fn find_compound_assignment_mutation(
+    node: &Node<'_>,
+    file_path: &Path,
+    mutations: &mut Vec<Mutation>,
+) {
+    let Some(op) = node.child_by_field_name("operator") else {
+        return;
+    };
+    let (mutated, operator) = match op.kind() {
+        "+=" => ("-=", MutationOperator::AssignmentAddToSub),
+        "-=" => ("+=", MutationOperator::AssignmentSubToAdd),
+        "*=" => ("/=", MutationOperator::AssignmentMulToDiv),
+        "/=" => ("*=", MutationOperator::AssignmentDivToMul),
+        _ => return,
+    };
+
+    mutations.push(Mutation::new(
+        file_path.to_path_buf(),
+        op.start_byte(),
+        op.end_byte(),
+        op.start_position().row + 1,
+        op.start_position().column + 1,
+        op.kind().to_owned(),
+        mutated.to_owned(),
+        operator,
+    ));
+}
+
 fn find_null_coalescing_mutation(
     node: &Node<'_>,
     source: &str,
     file_path: &Path,
     mutations: &mut Vec<Mutation>,
 ) {
-    // x ?? y -> x (remove fallback)
-    if let Some(left) = node.child(0) {
-        let left_text = get_node_text(&left, source);
-        let full_text = get_node_text(node, source);
+    // x ?? y -> x (remove fallback). Only `?? y` is replaced, so the span
+    // doesn't cover mutants in `x`
+    let mut cursor = node.walk();
+    let Some(op) = node
+        .children(&mut cursor)
+        .find(|child| child.kind() == "??")
+    else {
+        return;
+    };
 
-        mutations.push(Mutation::new(
-            file_path.to_path_buf(),
-            node.start_byte(),
-            node.end_byte(),
-            node.start_position().row + 1,
-            node.start_position().column + 1,
-            full_text.to_owned(),
-            left_text.to_owned(),
-            MutationOperator::NullCoalescingRemoval,
-        ));
-    }
+    mutations.push(Mutation::new(
+        file_path.to_path_buf(),
+        op.start_byte(),
+        node.end_byte(),
+        op.start_position().row + 1,
+        op.start_position().column + 1,
+        source[op.start_byte()..node.end_byte()].to_owned(),
+        String::new(),
+        MutationOperator::NullCoalescingRemoval,
+    ));
 }
 
 fn find_null_aware_access_mutation(
     node: &Node<'_>,
-    source: &str,
     file_path: &Path,
     mutations: &mut Vec<Mutation>,
 ) {
-    let text = get_node_text(node, source);
+    // x?.y -> x.y, replacing just this selector's `?.` so chained and
+    // nested accesses each get their own mutant
+    let mut cursor = node.walk();
+    let Some(op) = node
+        .children(&mut cursor)
+        .find(|child| child.kind() == "?.")
+    else {
+        return;
+    };
 
-    // x?.y -> x.y
-    if text.contains("?.") {
-        let replacement = text.replace("?.", ".");
-        mutations.push(Mutation::new(
-            file_path.to_path_buf(),
-            node.start_byte(),
-            node.end_byte(),
-            node.start_position().row + 1,
-            node.start_position().column + 1,
-            text.to_owned(),
-            replacement,
-            MutationOperator::NullAwareAccessRemoval,
-        ));
+    mutations.push(Mutation::new(
+        file_path.to_path_buf(),
+        op.start_byte(),
+        op.end_byte(),
+        op.start_position().row + 1,
+        op.start_position().column + 1,
+        "?.".to_owned(),
+        ".".to_owned(),
+        MutationOperator::NullAwareAccessRemoval,
+    ));
+}
+
+fn find_null_assertion_mutation(
+    node: &Node<'_>,
+    source: &str,
+    file_path: &Path,
+    mutations: &mut Vec<Mutation>,
+) {
+    // The postfix `!` is a selector of its own; `!x` and `!=` never are
+    if get_node_text(node, source) != "!" {
+        return;
     }
+
+    mutations.push(Mutation::new(
+        file_path.to_path_buf(),
+        node.start_byte(),
+        node.end_byte(),
+        node.start_position().row + 1,
+        node.start_position().column + 1,
+        "!".to_owned(),
+        String::new(),
+        MutationOperator::NullAssertionRemoval,
+    ));
 }
 
 fn find_if_statement_mutations(
@@ -421,68 +854,1990 @@ fn find_if_statement_mutations(
     }
 }
 
-fn find_string_mutation(
+/// Force a `while`, `do` or `for` condition to `false`, so the loop body
+/// never runs (or runs once for `do`), and to `true`, so the loop only ends
+/// through a `break`, `return` or `throw`. A mutant that loops forever is
+/// stopped by the mutant timeout and counts as killed. Loops without a
+/// condition (`for (;;)`, `for (x in xs)`) and literal `true`/`false`
+/// conditions are skipped; the step of a `for` loop is covered by the
+/// `++`/`--` and compound assignment operators.
+fn find_loop_condition_mutations(
     node: &Node<'_>,
     source: &str,
     file_path: &Path,
     mutations: &mut Vec<Mutation>,
 ) {
-    let text = get_node_text(node, source);
-
-    // Skip interpolated strings
-    if text.contains('$') {
+    let Some(condition) = node.child_by_field_name("condition") else {
+        return;
+    };
+    if matches!(unwrap_parentheses(condition).kind(), "true" | "false") {
         return;
     }
 
-    let quote_char = if text.starts_with('\'') { '\'' } else { '"' };
-    let inner = text
-        .trim_start_matches(quote_char)
-        .trim_end_matches(quote_char);
-
-    if inner.is_empty() {
-        // Empty -> non-empty
+    // `while` and `do` conditions include their parentheses, `for` ones don't
+    let parenthesized = condition.kind() == "parenthesized_expression";
+    for (value, operator) in [
+        ("false", MutationOperator::ControlFlowLoopConditionFalse),
+        ("true", MutationOperator::ControlFlowLoopConditionTrue),
+    ] {
         mutations.push(Mutation::new(
             file_path.to_path_buf(),
-            node.start_byte(),
-            node.end_byte(),
-            node.start_position().row + 1,
-            node.start_position().column + 1,
-            text.to_owned(),
-            format!("{}mutated{}", quote_char, quote_char),
-            MutationOperator::StringEmptyToNonEmpty,
+            condition.start_byte(),
+            condition.end_byte(),
+            condition.start_position().row + 1,
+            condition.start_position().column + 1,
+            get_node_text(&condition, source).to_owned(),
+            if parenthesized {
+                format!("({value})")
+            } else {
+                value.to_owned()
+            },
+            operator,
         ));
-    } else {
-        // Non-empty -> empty
+    }
+}
+
+/// Remove the first and the last element of a list, set or map literal,
+/// with the comma that separates it from its neighbour. Elements in between
+/// are left alone, so a long data table doesn't flood the run with mutants.
+/// A lone element of an untyped `{...}` literal is kept, since `{}` is a map
+/// rather than an empty set
+fn find_collection_element_removals(
+    node: &Node<'_>,
+    source: &str,
+    file_path: &Path,
+    mutations: &mut Vec<Mutation>,
+) {
+    if node.has_error() {
+        return;
+    }
+    let mut cursor = node.walk();
+    let elements: Vec<Node<'_>> = node
+        .named_children(&mut cursor)
+        .filter(|n| {
+            !matches!(
+                n.kind(),
+                "type_arguments" | "comment" | "documentation_comment"
+            )
+        })
+        .collect();
+    let untyped_set_or_map = node.kind() == "set_or_map_literal"
+        && node
+            .named_child(0)
+            .map_or(true, |n| n.kind() != "type_arguments");
+    if elements.len() == 1 && untyped_set_or_map {
+        return;
+    }
+    let Some(closing) = node.child(node.child_count().saturating_sub(1)) else {
+        return;
+    };
+
+    let last = elements.len().saturating_sub(1);
+    for (i, element) in elements.iter().enumerate() {
+        if i != 0 && i != last {
+            continue;
+        }
+        // `a, ` before a neighbour, `, b` at the end, `x,` when alone
+        let (start, end) = match (elements.get(i + 1), i.checked_sub(1)) {
+            (Some(next), _) => (element.start_byte(), next.start_byte()),
+            (None, Some(prev)) => (elements[prev].end_byte(), element.end_byte()),
+            (None, None) => (element.start_byte(), closing.start_byte()),
+        };
         mutations.push(Mutation::new(
             file_path.to_path_buf(),
-            node.start_byte(),
-            node.end_byte(),
-            node.start_position().row + 1,
-            node.start_position().column + 1,
-            text.to_owned(),
-            format!("{}{}", quote_char, quote_char),
-            MutationOperator::StringNonEmptyToEmpty,
+            start,
+            end,
+            element.start_position().row + 1,
+            element.start_position().column + 1,
+            source[start..end].to_owned(),
+            String::new(),
+            MutationOperator::Collection,
         ));
     }
 }
 
-#[cfg(test)]
-#[allow(clippy::unwrap_used)]
-mod tests {
-    use super::*;
+/// `...?items` → `...items`, so a null collection throws instead of adding
+/// nothing
+fn find_null_aware_spread_mutation(
+    node: &Node<'_>,
+    file_path: &Path,
+    mutations: &mut Vec<Mutation>,
+) {
+    // The grammar splits `...?` into `...` and `?`
+    let (Some(spread), Some(question)) = (node.child(0), node.child(1)) else {
+        return;
+    };
+    if spread.kind() != "..." || question.kind() != "?" {
+        return;
+    }
 
-    #[test]
+    mutations.push(Mutation::new(
+        file_path.to_path_buf(),
+        spread.start_byte(),
+        question.end_byte(),
+        spread.start_position().row + 1,
+        spread.start_position().column + 1,
+        "...?".to_owned(),
+        "...".to_owned(),
+        MutationOperator::Collection,
+    ));
+}
+
+/// Force the condition of `if (x) value` in a collection literal to `false`
+/// and `true`, so the element is always left out or always added
+fn find_if_element_mutations(
+    node: &Node<'_>,
+    source: &str,
+    file_path: &Path,
+    mutations: &mut Vec<Mutation>,
+) {
+    let Some(condition) = node.child_by_field_name("condition") else {
+        return;
+    };
+    if condition.kind() != "parenthesized_expression"
+        || matches!(unwrap_parentheses(condition).kind(), "true" | "false")
+    {
+        return;
+    }
+
+    for value in ["(false)", "(true)"] {
+        mutations.push(Mutation::new(
+            file_path.to_path_buf(),
+            condition.start_byte(),
+            condition.end_byte(),
+            condition.start_position().row + 1,
+            condition.start_position().column + 1,
+            get_node_text(&condition, source).to_owned(),
+            value.to_owned(),
+            MutationOperator::Collection,
+        ));
+    }
+}
+
+/// Replace an unlabeled `break;` or `continue;` that targets a loop with `{}`.
+/// Labeled jumps are skipped, and so is a `break` that ends a switch case.
+fn find_loop_jump_removal(
+    node: &Node<'_>,
+    source: &str,
+    file_path: &Path,
+    mutations: &mut Vec<Mutation>,
+) {
+    if node.named_child_count() > 0 || node.has_error() {
+        return;
+    }
+    let is_break = node.kind() == "break_statement";
+
+    let mut current = node.parent();
+    let in_loop = loop {
+        match current.map(|n| n.kind()) {
+            Some("for_statement" | "while_statement" | "do_statement") => break true,
+            Some("switch_block") if is_break => break false,
+            Some("function_body" | "function_expression" | "lambda_expression") | None => {
+                break false
+            }
+            _ => current = current.and_then(|n| n.parent()),
+        }
+    };
+    if !in_loop {
+        return;
+    }
+
+    mutations.push(Mutation::new(
+        file_path.to_path_buf(),
+        node.start_byte(),
+        node.end_byte(),
+        node.start_position().row + 1,
+        node.start_position().column + 1,
+        get_node_text(node, source).to_owned(),
+        "{}".to_owned(),
+        if is_break {
+            MutationOperator::ControlFlowBreakRemoval
+        } else {
+            MutationOperator::ControlFlowContinueRemoval
+        },
+    ));
+}
+
+/// Swap the bodies of each pair of adjacent cases in a switch statement:
+/// `case 1: a(); break; case 2: b(); break;` ->
+/// `case 1: b(); break; case 2: a(); break;`. A case without statements
+/// shares the next case's body, so pairs involving one are skipped.
+///
+/// A `break` that ends a case is never removed: Dart has no fall-through
+/// from a case with statements, so the mutant would be equivalent (Dart 3)
+/// or fail to compile (Dart 2). Switch expressions parse as errors and are
+/// handled by `find_switch_expression_swaps`.
+fn find_switch_case_swaps(
+    node: &Node<'_>,
+    source: &str,
+    file_path: &Path,
+    mutations: &mut Vec<Mutation>,
+) {
+    if node.has_error() {
+        return;
+    }
+
+    // The statements after each run of labels
+    let mut bodies: Vec<Vec<Node<'_>>> = Vec::new();
+    let mut cursor = node.walk();
+    let mut in_labels = false;
+    for child in node.named_children(&mut cursor) {
+        if child.kind() == "switch_label" {
+            if !in_labels {
+                bodies.push(Vec::new());
+            }
+            in_labels = true;
+        } else if let Some(body) = bodies.last_mut() {
+            in_labels = false;
+            body.push(child);
+        }
+    }
+
+    let span = |body: &[Node<'_>]| {
+        let statements: Vec<&Node<'_>> = body
+            .iter()
+            .filter(|n| !matches!(n.kind(), "comment" | "documentation_comment"))
+            .collect();
+        Some((
+            statements.first()?.start_byte(),
+            statements.last()?.end_byte(),
+        ))
+    };
+    for pair in bodies.windows(2) {
+        let (Some((first_start, first_end)), Some((second_start, second_end))) =
+            (span(&pair[0]), span(&pair[1]))
+        else {
+            continue;
+        };
+        let Some(first) = pair[0].iter().find(|n| n.start_byte() == first_start) else {
+            continue;
+        };
+
+        mutations.push(Mutation::new(
+            file_path.to_path_buf(),
+            first_start,
+            second_end,
+            first.start_position().row + 1,
+            first.start_position().column + 1,
+            source[first_start..second_end].to_owned(),
+            format!(
+                "{}{}{}",
+                &source[second_start..second_end],
+                &source[first_end..second_start],
+                &source[first_start..first_end]
+            ),
+            MutationOperator::Conditional,
+        ));
+    }
+}
+
+/// Swap the results of each pair of adjacent arms in a switch expression:
+/// `switch (x) { 1 => 'one', _ => 'many' }` ->
+/// `switch (x) { 1 => 'many', _ => 'one' }`. The grammar can't parse switch
+/// expressions, so the arms come from scanning the source text.
+fn find_switch_expression_swaps(
+    node: &Node<'_>,
+    source: &str,
+    file_path: &Path,
+    mutations: &mut Vec<Mutation>,
+) {
+    let Some(results) = switch_expression::arm_results(source, node.start_byte()) else {
+        return;
+    };
+
+    for pair in results.windows(2) {
+        let (first, second) = (&pair[0], &pair[1]);
+        if source[first.clone()] == source[second.clone()] {
+            continue;
+        }
+        let line_start = source[..first.start].rfind('\n').map_or(0, |i| i + 1);
+        mutations.push(Mutation::new(
+            file_path.to_path_buf(),
+            first.start,
+            second.end,
+            source[..first.start].matches('\n').count() + 1,
+            first.start - line_start + 1,
+            source[first.start..second.end].to_owned(),
+            format!(
+                "{}{}{}",
+                &source[second.clone()],
+                &source[first.end..second.start],
+                &source[first.clone()]
+            ),
+            MutationOperator::Conditional,
+        ));
+    }
+}
+
+/// `if (c) {..} else {..}` -> `if (c) {..}`. In an `else if` chain only the
+/// final `else` is removed, when its own if_statement is visited.
+fn find_else_removal_mutation(
+    node: &Node<'_>,
+    source: &str,
+    file_path: &Path,
+    mutations: &mut Vec<Mutation>,
+) {
+    let (Some(consequence), Some(alternative)) = (
+        node.child_by_field_name("consequence"),
+        node.child_by_field_name("alternative"),
+    ) else {
+        return;
+    };
+    if alternative.kind() == "if_statement" || node.has_error() {
+        return;
+    }
+
+    // Start right after the then-branch so the whitespace before `else` goes too
+    let start = consequence.end_byte();
+    let end = alternative.end_byte();
+    let mut cursor = node.walk();
+    let Some(keyword) = node.children(&mut cursor).find(|c| c.kind() == "else") else {
+        return;
+    };
+
+    mutations.push(Mutation::new(
+        file_path.to_path_buf(),
+        start,
+        end,
+        keyword.start_position().row + 1,
+        keyword.start_position().column + 1,
+        source[start..end].to_owned(),
+        String::new(),
+        MutationOperator::ControlFlowRemoveElse,
+    ));
+}
+
+/// `c ? a : b` -> `true ? a : b`, `false ? a : b` and `c ? b : a`
+fn find_conditional_expression_mutations(
+    node: &Node<'_>,
+    source: &str,
+    file_path: &Path,
+    mutations: &mut Vec<Mutation>,
+) {
+    if node.has_error() {
+        return;
+    }
+    let (Some(condition), Some(consequence), Some(alternative)) = (
+        node.named_child(0),
+        node.child_by_field_name("consequence"),
+        node.child_by_field_name("alternative"),
+    ) else {
+        return;
+    };
+
+    let cond_text = get_node_text(&condition, source);
+    for forced in ["true", "false"] {
+        mutations.push(Mutation::new(
+            file_path.to_path_buf(),
+            condition.start_byte(),
+            condition.end_byte(),
+            condition.start_position().row + 1,
+            condition.start_position().column + 1,
+            cond_text.to_owned(),
+            forced.to_owned(),
+            MutationOperator::Conditional,
+        ));
+    }
+
+    // Keep the original ` : ` spacing between the swapped branches
+    let branches = &source[consequence.start_byte()..alternative.end_byte()];
+    let separator = &source[consequence.end_byte()..alternative.start_byte()];
+    mutations.push(Mutation::new(
+        file_path.to_path_buf(),
+        consequence.start_byte(),
+        alternative.end_byte(),
+        consequence.start_position().row + 1,
+        consequence.start_position().column + 1,
+        branches.to_owned(),
+        format!(
+            "{}{}{}",
+            get_node_text(&alternative, source),
+            separator,
+            get_node_text(&consequence, source)
+        ),
+        MutationOperator::Conditional,
+    ));
+}
+
+/// Strip redundant parentheses: `((x))` -> `x`
+fn unwrap_parentheses(node: Node<'_>) -> Node<'_> {
+    match node.named_child(0) {
+        Some(inner)
+            if node.kind() == "parenthesized_expression" && node.named_child_count() == 1 =>
+        {
+            unwrap_parentheses(inner)
+        }
+        _ => node,
+    }
+}
+
+fn find_condition_negation_mutation(
+    node: &Node<'_>,
+    source: &str,
+    file_path: &Path,
+    mutations: &mut Vec<Mutation>,
+) {
+    // Without type information, only a bare identifier used directly as a
+    // condition is known to be boolean: if (isEnabled) -> if (!isEnabled)
+    let condition = if node.kind() == "conditional_expression" {
+        node.named_child(0)
+    } else {
+        node.child_by_field_name("condition")
+    };
+
+    let Some(identifier) = condition
+        .map(unwrap_parentheses)
+        .filter(|n| n.kind() == "identifier")
+    else {
+        return;
+    };
+
+    let text = get_node_text(&identifier, source);
+    mutations.push(Mutation::new(
+        file_path.to_path_buf(),
+        identifier.start_byte(),
+        identifier.end_byte(),
+        identifier.start_position().row + 1,
+        identifier.start_position().column + 1,
+        text.to_owned(),
+        format!("!{}", text),
+        MutationOperator::LogicalNotInsertion,
+    ));
+}
+
+/// `Future.value(x)` and `Future<T>.value(x)` -> `.error(x)`. The argument is
+/// kept as is; a call without one is skipped since `Future.error` needs it.
+fn find_future_value_mutation(
+    node: &Node<'_>,
+    source: &str,
+    file_path: &Path,
+    mutations: &mut Vec<Mutation>,
+) {
+    let mut cursor = node.walk();
+    let children: Vec<Node<'_>> = node.children(&mut cursor).collect();
+    let text = |i: usize| children.get(i).map(|c| get_node_text(c, source));
+    if text(0) != Some("Future") {
+        return;
+    }
+
+    // member_access: Future, selector(.value), selector((x))
+    // constructor_invocation: Future, type_arguments?, ., value, arguments
+    let (name, arguments) = if node.kind() == "member_access" {
+        if text(1) != Some(".value") {
+            return;
+        }
+        (
+            children[1].named_child(0).and_then(|s| s.named_child(0)),
+            children.get(2).map(|c| get_node_text(c, source)),
+        )
+    } else {
+        let name = children.iter().find(|c| c.kind() == "identifier").copied();
+        let arguments = children
+            .iter()
+            .find(|c| c.kind() == "arguments")
+            .map(|c| get_node_text(c, source));
+        (name, arguments)
+    };
+    let Some(name) = name.filter(|n| get_node_text(n, source) == "value") else {
+        return;
+    };
+    if !arguments.is_some_and(|args| args.starts_with('(') && args.trim() != "()") {
+        return;
+    }
+
+    mutations.push(Mutation::new(
+        file_path.to_path_buf(),
+        name.start_byte(),
+        name.end_byte(),
+        name.start_position().row + 1,
+        name.start_position().column + 1,
+        "value".to_owned(),
+        "error".to_owned(),
+        MutationOperator::AsyncFutureValueToError,
+    ));
+}
+
+/// Strip the `await` keyword and the whitespace after it
+fn find_await_removal_mutation(
+    node: &Node<'_>,
+    source: &str,
+    file_path: &Path,
+    mutations: &mut Vec<Mutation>,
+) {
+    let (Some(keyword), Some(operand)) = (node.child(0), node.named_child(0)) else {
+        return;
+    };
+    if keyword.kind() != "await" {
+        return;
+    }
+
+    mutations.push(Mutation::new(
+        file_path.to_path_buf(),
+        keyword.start_byte(),
+        operand.start_byte(),
+        keyword.start_position().row + 1,
+        keyword.start_position().column + 1,
+        source[keyword.start_byte()..operand.start_byte()].to_owned(),
+        String::new(),
+        MutationOperator::AsyncAwaitRemoval,
+    ));
+}
+
+/// Replace a statement that only calls a method, such as `list.sort();` or
+/// `logger.info(x);`, with an empty block. Assignments, declarations and
+/// awaited calls are different node kinds and never match.
+fn find_call_removal_mutation(
+    node: &Node<'_>,
+    source: &str,
+    file_path: &Path,
+    options: &ParseOptions,
+    mutations: &mut Vec<Mutation>,
+) {
+    if node.has_error() || node.named_child_count() != 1 {
+        return;
+    }
+    let Some(call) = node.named_child(0).filter(|n| n.kind() == "member_access") else {
+        return;
+    };
+    let ends_in_call = call
+        .named_child_count()
+        .checked_sub(1)
+        .and_then(|last| call.named_child(last))
+        .filter(|last| last.kind() == "selector")
+        .and_then(|selector| selector.named_child(0))
+        .is_some_and(|part| part.kind() == "argument_part");
+    if !ends_in_call {
+        return;
+    }
+
+    let is_print = call.named_child_count() == 2
+        && call
+            .named_child(0)
+            .is_some_and(|callee| matches!(&source[callee.byte_range()], "print" | "debugPrint"));
+    if is_print && !options.include_print_calls {
+        return;
+    }
+
+    mutations.push(Mutation::new(
+        file_path.to_path_buf(),
+        node.start_byte(),
+        node.end_byte(),
+        node.start_position().row + 1,
+        node.start_position().column + 1,
+        source[node.byte_range()].to_owned(),
+        "{}".to_owned(),
+        MutationOperator::MethodCallRemoval,
+    ));
+}
+
+/// Drop one section of a cascade: `buffer..write(a)..write(b)` →
+/// `buffer..write(a)`. Every section starts with its own `..`, so the rest
+/// stay valid. A leading `?..` is kept for the section after it, which
+/// would otherwise lose the null check.
+fn find_cascade_section_removal(
+    node: &Node<'_>,
+    source: &str,
+    file_path: &Path,
+    mutations: &mut Vec<Mutation>,
+) {
+    if node.has_error() {
+        return;
+    }
+    let Some(prefix) = node.child(0) else {
+        return;
+    };
+    let next_prefix = node
+        .next_named_sibling()
+        .filter(|next| next.kind() == "cascade_section")
+        .and_then(|next| next.child(0));
+
+    // `obj?..a()..b()` → `obj?..b()`
+    let (start, end, position) = match next_prefix {
+        Some(next_prefix) if prefix.kind() == "?.." => (
+            prefix.end_byte(),
+            next_prefix.end_byte(),
+            prefix.end_position(),
+        ),
+        _ => (node.start_byte(), node.end_byte(), node.start_position()),
+    };
+
+    mutations.push(Mutation::new(
+        file_path.to_path_buf(),
+        start,
+        end,
+        position.row + 1,
+        position.column + 1,
+        source[start..end].to_owned(),
+        String::new(),
+        MutationOperator::MethodCallRemoval,
+    ));
+}
+
+/// Remove a guarded early return: `if (x < 0) return 0;` → `if (x < 0) {}`.
+/// The return must sit inside an `if`, and the function-level statement
+/// holding it must be followed by more code, so execution still reaches a
+/// later return and the mutant compiles. A function's final return is never
+/// removed.
+fn find_early_return_removal(
+    node: &Node<'_>,
+    source: &str,
+    file_path: &Path,
+    mutations: &mut Vec<Mutation>,
+) {
+    if node.has_error() {
+        return;
+    }
+
+    let mut guarded = false;
+    let mut current = *node;
+    while let Some(parent) = current.parent() {
+        match parent.kind() {
+            "if_statement" => guarded = true,
+            "block" if parent.parent().is_some_and(|p| p.kind() == "function_body") => {
+                let mut next = current.next_named_sibling();
+                while next.is_some_and(|n| n.kind() == "comment") {
+                    next = next.and_then(|n| n.next_named_sibling());
+                }
+                if guarded && next.is_some() {
+                    mutations.push(Mutation::new(
+                        file_path.to_path_buf(),
+                        node.start_byte(),
+                        node.end_byte(),
+                        node.start_position().row + 1,
+                        node.start_position().column + 1,
+                        source[node.byte_range()].to_owned(),
+                        "{}".to_owned(),
+                        MutationOperator::ControlFlowReturnRemoval,
+                    ));
+                }
+                return;
+            }
+            "function_body" | "function_expression" | "lambda_expression" => return,
+            _ => {}
+        }
+        current = parent;
+    }
+}
+
+/// Turn `T? x` into `T x` for variable, field and parameter types. The `?`
+/// of a conditional expression follows an expression, not a type, and
+/// return types are left alone.
+fn find_nullable_type_mutation(node: &Node<'_>, file_path: &Path, mutations: &mut Vec<Mutation>) {
+    let follows_type = node
+        .prev_sibling()
+        .is_some_and(|prev| matches!(prev.kind(), "type_identifier" | "type_arguments"));
+    let in_declaration = node.parent().is_some_and(|parent| {
+        matches!(
+            parent.kind(),
+            "declaration"
+                | "formal_parameter"
+                | "initialized_variable_definition"
+                | "type_arguments"
+        )
+    });
+    if !follows_type || !in_declaration {
+        return;
+    }
+
+    mutations.push(Mutation::new(
+        file_path.to_path_buf(),
+        node.start_byte(),
+        node.end_byte(),
+        node.start_position().row + 1,
+        node.start_position().column + 1,
+        "?".to_owned(),
+        String::new(),
+        MutationOperator::NullableTypeRemoval,
+    ));
+}
+
+/// Default value for a function's declared primitive return type, if any.
+/// Nullable, generic and async return types are skipped.
+fn declared_return_default(body: &Node<'_>, source: &str) -> Option<&'static str> {
+    let signature = body.prev_named_sibling()?;
+    let signature = if signature.kind() == "method_signature" {
+        let mut cursor = signature.walk();
+        let inner = signature
+            .named_children(&mut cursor)
+            .find(|c| matches!(c.kind(), "function_signature" | "getter_signature"));
+        inner?
+    } else {
+        signature
+    };
+    if !matches!(signature.kind(), "function_signature" | "getter_signature") {
+        return None;
+    }
+
+    // `int? f()` and `List<int> f()` put `?`/type arguments after the type
+    let return_type = signature.child(0)?;
+    if return_type.kind() != "type_identifier"
+        || !matches!(return_type.next_sibling()?.kind(), "identifier" | "get")
+    {
+        return None;
+    }
+
+    // `async`/`sync*` bodies return a Future/Iterable, not the declared type
+    if !matches!(body.child(0)?.kind(), "=>" | "block") {
+        return None;
+    }
+
+    match get_node_text(&return_type, source) {
+        "int" | "num" => Some("0"),
+        "double" => Some("0.0"),
+        "bool" => Some("false"),
+        "String" => Some("''"),
+        _ => None,
+    }
+}
+
+/// Collect the values returned by a function body, skipping nested closures
+fn collect_returned_values<'a>(node: Node<'a>, values: &mut Vec<Node<'a>>) {
+    let mut cursor = node.walk();
+    for child in node.named_children(&mut cursor) {
+        match child.kind() {
+            "return_statement" => {
+                if let Some(value) = child.named_child(0) {
+                    values.push(value);
+                }
+            }
+            "function_expression" | "lambda_expression" | "local_function_declaration" => {}
+            _ => collect_returned_values(child, values),
+        }
+    }
+}
+
+/// Replace returned values with the default of the declared primitive return
+/// type, e.g. `int compute() => heavyMath();` → `int compute() => 0;`
+fn find_return_default_mutations(
+    node: &Node<'_>,
+    source: &str,
+    file_path: &Path,
+    mutations: &mut Vec<Mutation>,
+) {
+    let Some(default) = declared_return_default(node, source) else {
+        return;
+    };
+
+    let mut values = Vec::new();
+    match node.child(0).map(|c| c.kind()) {
+        Some("=>") => values.extend(node.named_child(0)),
+        _ => collect_returned_values(*node, &mut values),
+    }
+
+    for value in values {
+        let text = get_node_text(&value, source);
+        // Syntax the grammar can't parse (e.g. Dart 3 switch expressions)
+        // has no reliable extent to replace
+        if text == default || value.has_error() {
+            continue;
+        }
+        mutations.push(Mutation::new(
+            file_path.to_path_buf(),
+            value.start_byte(),
+            value.end_byte(),
+            value.start_position().row + 1,
+            value.start_position().column + 1,
+            text.to_owned(),
+            default.to_owned(),
+            MutationOperator::ControlFlowReturnToDefault,
+        ));
+    }
+}
+
+fn find_string_mutation(
+    node: &Node<'_>,
+    source: &str,
+    file_path: &Path,
+    mutations: &mut Vec<Mutation>,
+) {
+    let text = get_node_text(node, source);
+
+    // Skip adjacent literals and interpolated strings
+    let Some(literal) = simple_string_literal(text) else {
+        return;
+    };
+    if !literal.raw && literal.content.contains('$') {
+        return;
+    }
+
+    let (prefix, quote) = (if literal.raw { "r" } else { "" }, literal.quote);
+    if literal.content.is_empty() {
+        // Empty -> non-empty
+        mutations.push(Mutation::new(
+            file_path.to_path_buf(),
+            node.start_byte(),
+            node.end_byte(),
+            node.start_position().row + 1,
+            node.start_position().column + 1,
+            text.to_owned(),
+            format!("{prefix}{quote}mutated{quote}"),
+            MutationOperator::StringEmptyToNonEmpty,
+        ));
+    } else {
+        // Non-empty -> empty
+        mutations.push(Mutation::new(
+            file_path.to_path_buf(),
+            node.start_byte(),
+            node.end_byte(),
+            node.start_position().row + 1,
+            node.start_position().column + 1,
+            text.to_owned(),
+            format!("{prefix}{quote}{quote}"),
+            MutationOperator::StringNonEmptyToEmpty,
+        ));
+    }
+}
+
+/// A string literal split into its parts
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct StringLiteral<'a> {
+    /// Written `r'...'`, so backslashes and `$` are plain characters
+    pub raw: bool,
+    /// The delimiter: `'`, `"`, `'''` or `"""`
+    pub quote: &'a str,
+    /// Everything between the delimiters, escapes left as written
+    pub content: &'a str,
+}
+
+/// Split a string literal that is a single segment. Adjacent literals
+/// (`'a' 'b'`), which the grammar parses as one node, give `None`: the
+/// first unescaped closing quote has to be the last one, or rewriting the
+/// "content" would splice across two strings
+pub(crate) fn simple_string_literal(text: &str) -> Option<StringLiteral<'_>> {
+    let (raw, rest) = match text.strip_prefix('r') {
+        Some(rest) => (true, rest),
+        None => (false, text),
+    };
+    let quote = ["'''", "\"\"\"", "'", "\""]
+        .into_iter()
+        .find(|quote| rest.starts_with(quote))?;
+    let content = rest.get(quote.len()..)?.strip_suffix(quote)?;
+
+    let mut chars = content.char_indices();
+    while let Some((i, c)) = chars.next() {
+        if c == '\\' && !raw {
+            chars.next();
+        } else if content[i..].starts_with(quote) {
+            return None;
+        }
+    }
+    Some(StringLiteral {
+        raw,
+        quote,
+        content,
+    })
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::mutation::testing::mutant;
+
+    fn find_mutations(source: &str) -> Vec<Mutation> {
+        find_mutations_with(source, &ParseOptions::default())
+    }
+
+    fn find_mutations_with(source: &str, options: &ParseOptions) -> Vec<Mutation> {
+        let tree = parse_dart(source).unwrap();
+        let mut mutations = Vec::new();
+        find_mutations_in_tree(
+            &tree,
+            source,
+            Path::new("test.dart"),
+            options,
+            &mut mutations,
+        );
+        mutations
+    }
+
+    fn with_operator(mutations: &[Mutation], operator: MutationOperator) -> Vec<&Mutation> {
+        mutations
+            .iter()
+            .filter(|m| m.operator == operator)
+            .collect()
+    }
+
+    fn is_valid_dart(source: &str) -> bool {
+        !parse_dart(source).unwrap().root_node().has_error()
+    }
+
+    #[test]
     fn test_parse_simple_dart() {
         let source = r#"
-            void main() {
-                var x = 1 + 2;
+            void main() {
+                var x = 1 + 2;
+                if (x > 0) {
+                    print(x);
+                }
+            }
+        "#;
+
+        let tree = parse_dart(source).unwrap();
+        assert!(!tree.root_node().has_error());
+    }
+
+    #[test]
+    fn test_negates_bare_boolean_conditions() {
+        let source = r#"
+            void main() {
+                if (isEnabled) {}
+                while ((running)) {}
+                do {} while (active);
+                var x = ready ? 1 : 2;
+                if (count > 0) {}
+            }
+        "#;
+
+        let mutations = find_mutations(source);
+        let negations = with_operator(&mutations, MutationOperator::LogicalNotInsertion);
+        let replacements: Vec<&str> = negations.iter().map(|m| m.mutated.as_str()).collect();
+
+        assert_eq!(
+            replacements,
+            vec!["!isEnabled", "!running", "!active", "!ready"]
+        );
+        assert!(negations.iter().all(|m| is_valid_dart(&m.apply(source))));
+    }
+
+    #[test]
+    fn test_asserts_mutated_unless_skipped() {
+        let source = r#"
+            class Range {
+                final int lo;
+                final int hi;
+                final int span;
+                Range(this.lo, this.hi)
+                    : assert(lo <= hi),
+                      span = hi - lo;
+                void check() {
+                    assert(lo >= 0);
+                }
+            }
+        "#;
+
+        let mutations = find_mutations(source);
+        assert!(mutations.iter().any(|m| m.original == "<="));
+        assert!(mutations.iter().any(|m| m.original == ">="));
+        assert!(mutations.iter().all(|m| is_valid_dart(&m.apply(source))));
+
+        let options = ParseOptions {
+            skip_asserts: true,
+            ..ParseOptions::default()
+        };
+        let skipped = find_mutations_with(source, &options);
+        assert!(skipped.iter().all(|m| m.original != "<="));
+        assert!(skipped.iter().all(|m| m.original != ">="));
+        assert!(
+            skipped.iter().any(|m| m.original == "-"),
+            "field initializers are always mutated"
+        );
+    }
+
+    #[test]
+    fn test_null_aware_mutants_replace_only_their_operator() {
+        let source = "void f() {\n  var x = a?.b?.c(d + 1) ?? (y + 1) ??\n      'fallback';\n}\n";
+        let mutations = normalize_mutations(find_mutations(source));
+
+        let coalescing = with_operator(&mutations, MutationOperator::NullCoalescingRemoval);
+        assert_eq!(coalescing.len(), 2);
+        // Removing the outer fallback leaves exactly the left operand
+        assert_eq!(
+            coalescing[0].apply(source),
+            "void f() {\n  var x = a?.b?.c(d + 1) ;\n}\n"
+        );
+        assert_eq!(coalescing[0].location.start_line, 2);
+        assert_eq!(coalescing[0].location.end_line, 3);
+        assert_eq!(coalescing[0].location.end_col, 17);
+        // Mutants in the left operand are outside the span
+        let in_left = mutations
+            .iter()
+            .find(|m| {
+                m.original == "+" && m.location.byte_start < coalescing[0].location.byte_start
+            })
+            .unwrap();
+        assert!(!coalescing[0].overlaps(in_left));
+
+        let accesses = with_operator(&mutations, MutationOperator::NullAwareAccessRemoval);
+        assert_eq!(accesses.len(), 2);
+        assert!(accesses
+            .iter()
+            .all(|m| m.original == "?." && m.mutated == "."));
+        assert!(!accesses[0].overlaps(accesses[1]));
+        assert!(accesses[1].apply(source).contains("a?.b.c(d + 1)"));
+
+        assert!(mutations.iter().all(|m| is_valid_dart(&m.apply(source))));
+    }
+
+    #[test]
+    fn test_normalize_keeps_most_specific_operator() {
+        let plus = mutant().file("test.dart").at(10).column(11);
+        let mutation = |operator| plus.clone().operator(operator).mutation();
+        let other_replacement = plus
+            .clone()
+            .change("+", "*")
+            .operator(MutationOperator::Arithmetic)
+            .mutation();
+
+        let normalized = normalize_mutations(vec![
+            mutation(MutationOperator::Arithmetic),
+            mutation(MutationOperator::ArithmeticAddToSub),
+            mutation(MutationOperator::Arithmetic),
+            other_replacement,
+        ]);
+
+        assert_eq!(normalized.len(), 2);
+        assert_eq!(normalized[0].operator, MutationOperator::ArithmeticAddToSub);
+        assert_eq!(normalized[1].mutated, "*");
+    }
+
+    #[test]
+    fn test_each_mutant_has_its_own_id() {
+        // Both `&&` and both `+` make the same change on the same line, and
+        // overlapping handlers generate some of them more than once
+        let source = "bool f(a, b, c) => a && b && c;\nint g(x) => x + 1 + 2;\n";
+        let mut mutations = normalize_mutations(find_mutations(source));
+        let before = mutations.len();
+
+        crate::mutation::dedup_mutations(&mut mutations);
+
+        assert_eq!(mutations.len(), before);
+        assert_eq!(
+            with_operator(&mutations, MutationOperator::LogicalAndToOr).len(),
+            2
+        );
+        let additions: Vec<_> = mutations
+            .iter()
+            .filter(|m| m.original == "+" && m.mutated == "-")
+            .collect();
+        assert_eq!(additions.len(), 2);
+        assert_ne!(additions[0].id, additions[1].id);
+    }
+
+    #[test]
+    fn test_return_values_mutated_to_type_default() {
+        let source = r#"
+            int compute() => heavyMath();
+            bool check(int x) {
+                var f = () { return 2; };
+                return x > 1;
+            }
+            class A {
+                String name() { return 'a' + b; }
+                double get ratio => 1.5 * x;
+                int? maybe() => 1;
+                List<int> items() { return [1]; }
+                Future<int> later() async => 1;
+                int zero() => 0;
+            }
+        "#;
+
+        let mutations = find_mutations(source);
+        let defaults = with_operator(&mutations, MutationOperator::ControlFlowReturnToDefault);
+
+        let pairs: Vec<(&str, &str)> = defaults
+            .iter()
+            .map(|m| (m.original.as_str(), m.mutated.as_str()))
+            .collect();
+        assert_eq!(
+            pairs,
+            vec![
+                ("heavyMath()", "0"),
+                ("x > 1", "false"),
+                ("'a' + b", "''"),
+                ("1.5 * x", "0.0"),
+            ]
+        );
+        assert!(defaults.iter().all(|m| is_valid_dart(&m.apply(source))));
+    }
+
+    #[test]
+    fn test_switch_expression_arm_results_are_swapped() {
+        // The bundled grammar predates Dart 3 and parses switch expressions
+        // as ERROR nodes, so arms come from the text and only self-contained
+        // literals inside them get node-level mutations
+        let source = r#"
+            String label(int x) => switch (x) {
+                1 => 'one',
+                2 => 'two', // pair
+                _ => 'many',
+            };
+        "#;
+
+        let mutations = find_mutations(source);
+
+        assert!(with_operator(&mutations, MutationOperator::ControlFlowReturnToDefault).is_empty());
+        let swaps: Vec<(&str, &str)> = with_operator(&mutations, MutationOperator::Conditional)
+            .iter()
+            .map(|m| (m.original.as_str(), m.mutated.as_str()))
+            .collect();
+        assert_eq!(
+            swaps,
+            vec![
+                (
+                    "'one',\n                2 => 'two'",
+                    "'two',\n                2 => 'one'"
+                ),
+                (
+                    "'two', // pair\n                _ => 'many'",
+                    "'many', // pair\n                _ => 'two'"
+                ),
+            ]
+        );
+        assert_eq!(swaps.len() + 3, mutations.len());
+
+        // The mutants still scan as switch expressions with the arms swapped
+        let mutant = mutations
+            .iter()
+            .find(|m| m.operator == MutationOperator::Conditional)
+            .unwrap()
+            .apply(source);
+        let start = mutant.find("switch").unwrap();
+        let results: Vec<&str> = switch_expression::arm_results(&mutant, start)
+            .unwrap()
+            .into_iter()
+            .map(|r| &mutant[r])
+            .collect();
+        assert_eq!(results, vec!["'two'", "'one'", "'many'"]);
+    }
+
+    #[test]
+    fn test_swaps_adjacent_switch_case_bodies() {
+        let source = r#"
+            int score(String grade) {
+                var s = 0;
+                switch (grade) {
+                    case 'A':
+                        s = 4;
+                        break;
+                    case 'B':
+                    case 'C':
+                        // Shared body
+                        s = 2;
+                        break;
+                    case 'D':
+                    default:
+                        s = 0;
+                }
+                return s;
+            }
+        "#;
+
+        let swaps: Vec<(String, String)> =
+            with_operator(&find_mutations(source), MutationOperator::Conditional)
+                .iter()
+                .map(|m| (m.original.clone(), m.mutated.clone()))
+                .collect();
+        let squash = |s: &str| s.split_whitespace().collect::<Vec<_>>().join(" ");
+        let swaps: Vec<(String, String)> =
+            swaps.iter().map(|(o, m)| (squash(o), squash(m))).collect();
+
+        assert_eq!(
+            swaps,
+            vec![
+                (
+                    "s = 4; break; case 'B': case 'C': // Shared body s = 2; break;".to_owned(),
+                    "s = 2; break; case 'B': case 'C': // Shared body s = 4; break;".to_owned()
+                ),
+                (
+                    "s = 2; break; case 'D': default: s = 0;".to_owned(),
+                    "s = 0; case 'D': default: s = 2; break;".to_owned()
+                ),
+            ]
+        );
+        assert!(find_mutations(source)
+            .iter()
+            .all(|m| is_valid_dart(&m.apply(source))));
+    }
+
+    #[test]
+    fn test_nullable_type_removal_is_opt_in() {
+        let source = r#"
+            class A {
+                String? name;
+                int? count() => null;
+                void f(int? x, {List<int?>? items}) {
+                    int? y = x;
+                    final w = x == null ? 1 : 2;
+                }
+            }
+        "#;
+
+        assert!(with_operator(
+            &find_mutations(source),
+            MutationOperator::NullableTypeRemoval
+        )
+        .is_empty());
+
+        let options = ParseOptions {
+            nullable_types: true,
+            ..ParseOptions::default()
+        };
+        let mutations = find_mutations_with(source, &options);
+        let removals = with_operator(&mutations, MutationOperator::NullableTypeRemoval);
+
+        // name, x, int? inside List, items, y; not the return type or ternary
+        assert_eq!(removals.len(), 5);
+        assert!(removals.iter().all(|m| is_valid_dart(&m.apply(source))));
+    }
+
+    #[test]
+    fn test_discarded_calls_are_removed() {
+        let source = r#"
+            void f(List<int> list) {
+                list.sort();
+                logger.info(list);
+                a.b.c();
+                print('sorted');
+                debugPrint('sorted');
+                final copy = list.toList();
+                total = sum(list);
+                list.length;
+            }
+        "#;
+
+        let mutations = find_mutations(source);
+        let removed: Vec<&str> = with_operator(&mutations, MutationOperator::MethodCallRemoval)
+            .iter()
+            .map(|m| m.original.as_str())
+            .collect();
+        assert_eq!(removed, ["list.sort();", "logger.info(list);", "a.b.c();"]);
+        assert!(mutations.iter().all(|m| is_valid_dart(&m.apply(source))));
+
+        let options = ParseOptions {
+            include_print_calls: true,
+            ..ParseOptions::default()
+        };
+        let mutations = find_mutations_with(source, &options);
+        assert_eq!(
+            with_operator(&mutations, MutationOperator::MethodCallRemoval).len(),
+            5
+        );
+    }
+
+    #[test]
+    fn test_await_removed_but_not_from_await_for() {
+        let source = r#"
+            Future<int> f(Stream<int> stream) async {
+                await save();
+                final x = await load(1);
+                await for (final e in stream) {
+                    print(e);
+                }
+                return await  other(x);
+            }
+        "#;
+
+        let mutations = find_mutations(source);
+        let removals = with_operator(&mutations, MutationOperator::AsyncAwaitRemoval);
+
+        assert_eq!(removals.len(), 3);
+        assert!(removals.iter().all(|m| m.original.starts_with("await")));
+        let last = removals.last().unwrap().apply(source);
+        assert!(last.contains("return other(x);"));
+        assert!(last.contains("await for"));
+        assert!(removals.iter().all(|m| is_valid_dart(&m.apply(source))));
+    }
+
+    #[test]
+    fn test_compound_assignment_mutations() {
+        let source = r#"
+            void f(int a, double d, List<int> xs) {
+                a += 1;
+                a -= 2;
+                d *= 3;
+                d /= 4;
+                a ~/= 5;
+                a ??= 6;
+                a = 7;
+                xs[0] += a;
+            }
+        "#;
+
+        let mutations = find_mutations(source);
+        let changes: Vec<(&str, &str)> = mutations
+            .iter()
+            .filter(|m| m.operator.category() == crate::mutators::MutatorCategory::Assignment)
+            .map(|m| (m.original.as_str(), m.mutated.as_str()))
+            .collect();
+
+        assert_eq!(
+            changes,
+            [
+                ("+=", "-="),
+                ("-=", "+="),
+                ("*=", "/="),
+                ("/=", "*="),
+                ("+=", "-=")
+            ]
+        );
+        assert!(mutations.iter().all(|m| is_valid_dart(&m.apply(source))));
+    }
+
+    #[test]
+    fn test_collection_property_mutations() {
+        let source = r#"
+            int f(List<int> xs, List<int>? ys) {
+                if (xs.isEmpty) return 0;
+                final a = xs.first;
+                final b = ys?.last;
+                final c = xs.firstWhere((e) => e > 1);
+                final d = xs.lastIndexOf(a);
+                return xs.isNotEmpty ? a : c + d;
+            }
+        "#;
+
+        let mutations = find_mutations(source);
+        let changes: Vec<(&str, &str)> = mutations
+            .iter()
+            .filter(|m| m.operator.category() == crate::mutators::MutatorCategory::Collection)
+            .map(|m| (m.original.as_str(), m.mutated.as_str()))
+            .collect();
+
+        assert_eq!(
+            changes,
+            [
+                ("isEmpty", "isNotEmpty"),
+                ("first", "last"),
+                ("last", "first"),
+                ("isNotEmpty", "isEmpty"),
+            ]
+        );
+        assert!(mutations.iter().all(|m| is_valid_dart(&m.apply(source))));
+    }
+
+    #[test]
+    fn test_early_return_removal_mutations() {
+        let source = r#"
+            int clamp(int x) {
+                if (x < 0) return 0;
+                for (final limit in [10, 20]) {
+                    if (x > limit) {
+                        return limit;
+                    }
+                }
+                return x;
+            }
+            int sign(int x) {
+                if (x < 0) return -1; else return 1;
+            }
+            void log(int x) {
+                if (x < 0) return;
+                print(x);
+            }
+        "#;
+
+        let mutations = find_mutations(source);
+        let removed: Vec<&str> =
+            with_operator(&mutations, MutationOperator::ControlFlowReturnRemoval)
+                .iter()
+                .map(|m| m.original.as_str())
+                .collect();
+
+        assert_eq!(removed, ["return 0;", "return limit;", "return;"]);
+        assert!(mutations.iter().all(|m| is_valid_dart(&m.apply(source))));
+    }
+
+    #[test]
+    fn test_conditional_expression_mutations() {
+        let source = r#"
+            int pick(int a, int b) {
+                return a > b ? a : b + 1;
+            }
+        "#;
+
+        let mutations = find_mutations(source);
+        let replacements: Vec<(&str, &str)> =
+            with_operator(&mutations, MutationOperator::Conditional)
+                .iter()
+                .map(|m| (m.original.as_str(), m.mutated.as_str()))
+                .collect();
+
+        assert_eq!(
+            replacements,
+            [
+                ("a > b", "true"),
+                ("a > b", "false"),
+                ("a : b + 1", "b + 1 : a")
+            ]
+        );
+        assert!(mutations.iter().all(|m| is_valid_dart(&m.apply(source))));
+    }
+
+    #[test]
+    fn test_bitwise_mutations_skip_logical_operators() {
+        let source = r#"
+            int hash(int a, int b, bool c) {
+                var x = (a & b) | (a ^ b);
+                var y = (a << 2) >> 1;
+                if (c && (a & 1) == 1 || c) return x;
+                return y;
+            }
+        "#;
+
+        let mutations = find_mutations(source);
+        let swaps: Vec<(&str, &str)> = with_operator(&mutations, MutationOperator::Bitwise)
+            .iter()
+            .map(|m| (m.original.as_str(), m.mutated.as_str()))
+            .collect();
+
+        assert_eq!(
+            swaps,
+            [
+                ("|", "&"),
+                ("&", "|"),
+                ("^", "&"),
+                (">>", "<<"),
+                ("<<", ">>"),
+                ("&", "|")
+            ]
+        );
+        assert!(mutations.iter().all(|m| is_valid_dart(&m.apply(source))));
+    }
+
+    #[test]
+    fn test_increment_position_swapped_only_when_value_is_used() {
+        let source = r#"
+            void f(int i, List<int> a) {
+                i++;
+                --i;
+                for (var j = 0; j < 3; j++) {}
+                var x = i++;
+                var y = --i;
+                a[i++] = this.i++;
+            }
+        "#;
+
+        let mutations = find_mutations(source);
+        let swap = |operator| -> Vec<(&str, &str)> {
+            with_operator(&mutations, operator)
+                .iter()
+                .map(|m| (m.original.as_str(), m.mutated.as_str()))
+                .collect()
+        };
+
+        assert_eq!(
+            swap(MutationOperator::UnaryPostToPre),
+            [("i++", "++i"), ("i++", "++i")]
+        );
+        assert_eq!(swap(MutationOperator::UnaryPreToPost), [("--i", "i--")]);
+        assert!(mutations.iter().all(|m| is_valid_dart(&m.apply(source))));
+    }
+
+    #[test]
+    fn test_else_removal_only_removes_final_else() {
+        let source = r#"
+            void f(int x) {
                 if (x > 0) {
-                    print(x);
+                    a();
+                } else if (x < 0) {
+                    b();
+                } else {
+                    c();
                 }
+                if (x == 1) a(); else b();
+                if (x == 2) a();
             }
         "#;
 
-        let tree = parse_dart(source).unwrap();
-        assert!(!tree.root_node().has_error());
+        let mutations = find_mutations(source);
+        let removed = with_operator(&mutations, MutationOperator::ControlFlowRemoveElse);
+
+        assert_eq!(removed.len(), 2);
+        assert!(removed[0].original.trim_start().starts_with("else {"));
+        assert_eq!(removed[1].original, " else b();");
+        assert!(mutations.iter().all(|m| is_valid_dart(&m.apply(source))));
+        assert!(removed[0].apply(source).contains("} else if (x < 0) {"));
+    }
+
+    #[test]
+    fn test_loop_break_and_continue_removed() {
+        // The grammar has no labeled statements, so `outer:` itself is left out
+        let source = r#"
+            int f(List<int> xs) {
+                var total = 0;
+                for (final x in xs) {
+                    if (x < 0) continue;
+                    if (x > 9) break outer;
+                    switch (x) {
+                        case 1:
+                            break;
+                        case 2:
+                            continue;
+                    }
+                    while (total > 100) {
+                        break;
+                    }
+                    total += x;
+                }
+                return total;
+            }
+        "#;
+
+        let mutations = find_mutations(source);
+        let lines = |operator| -> Vec<usize> {
+            with_operator(&mutations, operator)
+                .iter()
+                .map(|m| m.location.start_line)
+                .collect()
+        };
+
+        assert_eq!(lines(MutationOperator::ControlFlowContinueRemoval), [5, 11]);
+        assert_eq!(lines(MutationOperator::ControlFlowBreakRemoval), [14]);
+        assert!(mutations.iter().all(|m| is_valid_dart(&m.apply(source))));
+    }
+
+    #[test]
+    fn test_loop_conditions_forced_both_ways() {
+        let source = r#"
+            int f(List<int> xs, int n) {
+                var i = 0;
+                while (i < n) {
+                    i++;
+                }
+                do {
+                    i--;
+                } while ((i > 0));
+                for (var j = 0; j < xs.length; j++) {}
+                for (final x in xs) {}
+                for (;;) {
+                    break;
+                }
+                while (true) {
+                    return i;
+                }
+            }
+        "#;
+
+        let mutations = find_mutations(source);
+        let forced = |operator| -> Vec<(&str, &str)> {
+            with_operator(&mutations, operator)
+                .iter()
+                .map(|m| (m.original.as_str(), m.mutated.as_str()))
+                .collect()
+        };
+
+        assert_eq!(
+            forced(MutationOperator::ControlFlowLoopConditionFalse),
+            [
+                ("(i < n)", "(false)"),
+                ("((i > 0))", "(false)"),
+                ("j < xs.length", "false")
+            ]
+        );
+        assert_eq!(
+            forced(MutationOperator::ControlFlowLoopConditionTrue),
+            [
+                ("(i < n)", "(true)"),
+                ("((i > 0))", "(true)"),
+                ("j < xs.length", "true")
+            ]
+        );
+        // The step direction of a `for` loop comes from the `++`/`--` operators
+        assert!(
+            with_operator(&mutations, MutationOperator::UnaryIncrementToDecrement)
+                .iter()
+                .any(|m| m.location.start_line == 10 && m.mutated == "j--")
+        );
+        assert!(mutations.iter().all(|m| is_valid_dart(&m.apply(source))));
+    }
+
+    #[test]
+    fn test_collection_literal_mutations() {
+        let source = r#"
+            List<int> f(List<int>? extra, bool admin) {
+                final tags = {'a', 'b'};
+                final lone = {'only'};
+                final typed = <String>{'only'};
+                final byName = {'x': 1, if (admin) 'y': 2};
+                return [1, ...?extra, ...tags.map((t) => t.length), if (true) 3,];
+            }
+        "#;
+
+        let mutations = find_mutations(source);
+        let collection: Vec<(&str, &str)> = with_operator(&mutations, MutationOperator::Collection)
+            .iter()
+            .map(|m| (m.original.as_str(), m.mutated.as_str()))
+            .collect();
+
+        assert_eq!(
+            collection,
+            [
+                ("'a', ", ""),
+                (", 'b'", ""),
+                // `{'only'}` alone stays: `{}` would be a map
+                ("'only'", ""),
+                ("'x': 1, ", ""),
+                (", if (admin) 'y': 2", ""),
+                ("(admin)", "(false)"),
+                ("(admin)", "(true)"),
+                // Only the ends of a longer literal
+                ("1, ", ""),
+                (", if (true) 3", ""),
+                ("...?", "..."),
+            ]
+        );
+        assert!(mutations.iter().all(|m| is_valid_dart(&m.apply(source))));
+    }
+
+    #[test]
+    fn test_cascade_sections_removed_one_at_a_time() {
+        let source = r#"
+            List<int>? f(StringBuffer buffer, List<int>? xs) {
+                buffer..write('a')..write('b');
+                final b = StringBuffer()..write('c');
+                return xs?..add(1)..sort();
+            }
+        "#;
+
+        let mutations = find_mutations(source);
+        let removed: Vec<(&str, String)> =
+            with_operator(&mutations, MutationOperator::MethodCallRemoval)
+                .iter()
+                .filter(|m| m.mutated.is_empty())
+                .map(|m| (m.original.as_str(), m.apply(source)))
+                .collect();
+
+        let originals: Vec<&str> = removed.iter().map(|(original, _)| *original).collect();
+        assert_eq!(
+            originals,
+            [
+                "..write('a')",
+                "..write('b')",
+                "..write('c')",
+                "add(1)..",
+                "..sort()"
+            ]
+        );
+        assert!(removed[0].1.contains("buffer..write('b');"));
+        assert!(removed[2].1.contains("final b = StringBuffer();"));
+        // The null check moves to the section after a dropped `?..` one
+        assert!(removed[3].1.contains("return xs?..sort();"));
+        assert!(removed[4].1.contains("return xs?..add(1);"));
+        assert!(removed.iter().all(|(_, mutated)| is_valid_dart(mutated)));
+    }
+
+    #[test]
+    fn test_null_checks_replaced_with_constants() {
+        let source = r#"
+            bool f(int? x, int y) {
+                if (x != null && null == x) return true;
+                return x == null || y == 0;
+            }
+        "#;
+
+        let mutations = find_mutations(source);
+        let constants: Vec<(&str, &str)> = mutations
+            .iter()
+            .filter(|m| {
+                matches!(
+                    m.operator,
+                    MutationOperator::NullCheckToTrue | MutationOperator::NullCheckToFalse
+                )
+            })
+            .map(|m| (m.original.as_str(), m.mutated.as_str()))
+            .collect();
+
+        assert_eq!(
+            constants,
+            [
+                ("x != null", "true"),
+                ("null == x", "false"),
+                ("x == null", "false")
+            ]
+        );
+        assert!(mutations.iter().all(|m| is_valid_dart(&m.apply(source))));
+    }
+
+    #[test]
+    fn test_null_assertions_removed() {
+        let source = r#"
+            void f(User? user, int? value, bool flag, Map<String, int> map) {
+                print(user!.name);
+                var v = value!;
+                var w = !flag && value != 0;
+                var z = map['k']!;
+            }
+        "#;
+
+        let mutations = find_mutations(source);
+        let stripped: Vec<String> =
+            with_operator(&mutations, MutationOperator::NullAssertionRemoval)
+                .iter()
+                .map(|m| m.apply(source))
+                .collect();
+
+        assert_eq!(stripped.len(), 3);
+        assert!(stripped[0].contains("print(user.name);"));
+        assert!(stripped[1].contains("var v = value;"));
+        assert!(stripped[2].contains("var z = map['k'];"));
+        assert!(mutations.iter().all(|m| is_valid_dart(&m.apply(source))));
+    }
+
+    #[test]
+    fn test_future_value_mutated_to_error() {
+        let source = r#"
+            Future<int> f() {
+                return Future.value(1);
+            }
+            Future<int> g() => Future<int>.value(2);
+            Future<void> h() => Future.value();
+            int i(Box b) => b.value(3);
+        "#;
+
+        let mutations = find_mutations(source);
+        let errors: Vec<String> =
+            with_operator(&mutations, MutationOperator::AsyncFutureValueToError)
+                .iter()
+                .map(|m| m.apply(source))
+                .collect();
+
+        assert_eq!(errors.len(), 2);
+        assert!(errors[0].contains("return Future.error(1);"));
+        assert!(errors[1].contains("=> Future<int>.error(2);"));
+        assert!(mutations.iter().all(|m| is_valid_dart(&m.apply(source))));
+    }
+
+    #[test]
+    fn test_comments_are_not_mutated() {
+        let source = r#"
+            /// Returns [a] + [b] when `a > 0`.
+            int f(int a, int b) {
+                // a + b
+                /* return a - b == 0 ? true : false; */
+                return a * b;
+            }
+        "#;
+
+        let mutations = find_mutations(source);
+        let originals: Vec<&str> = mutations.iter().map(|m| m.original.as_str()).collect();
+
+        assert!(with_operator(&mutations, MutationOperator::ArithmeticAddToSub).is_empty());
+        assert!(with_operator(&mutations, MutationOperator::ArithmeticSubToAdd).is_empty());
+        assert!(!originals.contains(&">"));
+        assert!(originals.contains(&"*"));
+    }
+
+    #[test]
+    fn test_suppression_comments_drop_mutations() {
+        let source = r#"
+            int f(int a, int b) {
+                var c = a + b; // dart_mutant:disable-line
+                // dart_mutant:disable-next-line
+                var d = a - b;
+                var e = a * b;
+                // dart_mutant:disable
+                if (Platform.isWindows) {
+                    return a % b;
+                }
+                // dart_mutant:enable
+                final url = 'http://x'; // not dart_mutant:disable-line
+                return c / d;
+            }
+        "#;
+
+        let mut mutations = find_mutations(source);
+        remove_suppressed(&mut mutations, source);
+        let mut lines: Vec<usize> = mutations.iter().map(|m| m.location.start_line).collect();
+        lines.sort_unstable();
+        lines.dedup();
+
+        assert_eq!(lines, [6, 12, 13]);
+    }
+
+    #[test]
+    fn test_flags_identity_operands_as_likely_equivalent() {
+        let source = r#"
+            double f(double a, double b) {
+                a += 0;
+                b *= 1.0;
+                final c = a * 1 + b;
+                final d = (a - 0);
+                final e = a + 0 * b;
+                final g = 0 - a;
+                return a / 10 + c - d + e + g;
+            }
+        "#;
+
+        let mut mutations = find_mutations(source);
+        flag_likely_equivalent(&mut mutations, source);
+        let flagged: Vec<(usize, &str)> = mutations
+            .iter()
+            .filter(|m| m.likely_equivalent)
+            .map(|m| (m.location.start_line, m.original.as_str()))
+            .collect();
+
+        assert_eq!(flagged, [(3, "+="), (4, "*="), (6, "-")]);
+    }
+
+    #[test]
+    fn test_flags_min_max_boundaries_as_likely_equivalent() {
+        let source = r#"
+            int f(int a, int b, int c) {
+                final lo = a < b ? a : b;
+                final hi = (a >= b ? b : a);
+                final shifted = c + a < b ? a : b;
+                final other = a < b ? a : c;
+                return a <= b ? lo : hi;
+            }
+        "#;
+
+        let mut mutations = find_mutations(source);
+        flag_likely_equivalent(&mut mutations, source);
+        let flagged: Vec<(usize, &str, &str)> = mutations
+            .iter()
+            .filter(|m| m.likely_equivalent)
+            .map(|m| {
+                (
+                    m.location.start_line,
+                    m.original.as_str(),
+                    m.mutated.as_str(),
+                )
+            })
+            .collect();
+
+        assert_eq!(flagged, [(3, "<", "<="), (4, ">=", ">")]);
+    }
+
+    #[test]
+    fn test_discovery_applies_include_glob_then_excludes() {
+        let dir = tempfile::tempdir().unwrap();
+        for file in [
+            "lib/core.dart",
+            "lib/services/api.dart",
+            "lib/services/legacy/old.dart",
+            "lib/models.g.dart",
+            "test/core_test.dart",
+            "bin/main.dart",
+        ] {
+            let path = dir.path().join(file);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, "void main() {}").unwrap();
+        }
+        let generated = [".g.dart".to_owned()];
+        let discover = |glob: &str, exclude: &[String]| {
+            // No sort here: discovery order itself must be deterministic
+            discover_dart_files(dir.path(), glob, exclude, &generated)
+                .unwrap()
+                .iter()
+                .map(|f| {
+                    f.strip_prefix(dir.path())
+                        .unwrap()
+                        .to_string_lossy()
+                        .replace('\\', "/")
+                })
+                .collect::<Vec<String>>()
+        };
+
+        assert_eq!(
+            discover("lib/**/*.dart", &[]),
+            [
+                "lib/core.dart",
+                "lib/services/api.dart",
+                "lib/services/legacy/old.dart"
+            ]
+        );
+        assert_eq!(
+            discover("lib/services/**/*.dart", &["**/legacy/**".to_owned()]),
+            ["lib/services/api.dart"]
+        );
+        assert!(discover_dart_files(dir.path(), "lib/[", &[], &[]).is_err());
+        assert_eq!(
+            discover_dart_files(dir.path(), "lib/**/*.dart", &[], &[])
+                .unwrap()
+                .len(),
+            4
+        );
+    }
+
+    #[test]
+    fn test_string_mutants_keep_raw_prefixes_and_escapes() {
+        let source = r#"
+            void main() {
+                var a = 'it\'s';
+                var b = r'raw\n';
+                var c = r'';
+                var d = 'a' 'b';
+                var e = '' '';
+                var f = '''x''';
+                var g = "say \"hi\"";
+            }
+        "#;
+
+        let mutations = find_mutations(source);
+        let strings: Vec<(&str, &str)> = mutations
+            .iter()
+            .filter(|m| {
+                matches!(
+                    m.operator,
+                    MutationOperator::StringEmptyToNonEmpty
+                        | MutationOperator::StringNonEmptyToEmpty
+                )
+            })
+            .map(|m| (m.original.as_str(), m.mutated.as_str()))
+            .collect();
+
+        assert_eq!(
+            strings,
+            vec![
+                (r"'it\'s'", "''"),
+                (r"r'raw\n'", "r''"),
+                ("r''", "r'mutated'"),
+                ("'''x'''", "''''''"),
+                (r#""say \"hi\"""#, r#""""#),
+            ]
+        );
+        for mutation in &mutations {
+            assert!(
+                is_valid_dart(&mutation.apply(source)),
+                "{} -> {}",
+                mutation.original,
+                mutation.mutated
+            );
+        }
+    }
+
+    #[test]
+    fn test_splits_simple_string_literals() {
+        let literal = simple_string_literal(r"r'a\'").unwrap();
+        assert!(literal.raw);
+        assert_eq!((literal.quote, literal.content), ("'", r"a\"));
+
+        let literal = simple_string_literal(r#""""it's""""#).unwrap();
+        assert!(!literal.raw);
+        assert_eq!((literal.quote, literal.content), ("\"\"\"", "it's"));
+
+        assert!(simple_string_literal("'a' 'b'").is_none());
+        assert!(simple_string_literal("'' ''").is_none());
+        assert!(simple_string_literal("name").is_none());
+    }
+
+    #[test]
+    fn test_string_mutants_span_multi_line_triple_quotes() {
+        let source = "var a = '''\nline one\nline two\n''';\nvar b = r\"\"\"C:\\dir\"\"\";\nvar c = \"\"\"\"\"\";\n";
+
+        let mutations = find_mutations(source);
+        let strings: Vec<(&str, usize, usize)> = mutations
+            .iter()
+            .map(|m| {
+                (
+                    m.mutated.as_str(),
+                    m.location.start_line,
+                    m.location.end_line,
+                )
+            })
+            .collect();
+
+        assert_eq!(
+            strings,
+            vec![
+                ("''''''", 1, 4),
+                ("r\"\"\"\"\"\"", 5, 5),
+                ("\"\"\"mutated\"\"\"", 6, 6),
+            ]
+        );
+        for mutation in &mutations {
+            assert!(is_valid_dart(&mutation.apply(source)));
+        }
     }
 }
@@ -0,0 +1,268 @@
+//! Dart 3 switch expressions, which the bundled grammar can't parse
+//!
+//! tree-sitter-dart parses `switch (x) { 1 => 'one', _ => 'many' }` as
+//! error nodes, with `switch` as an identifier. The arms are found by
+//! scanning the source from there instead: brackets are matched outside
+//! strings and comments, and the body is split on top-level commas. Any
+//! text that doesn't scan as `pattern => result` arms is left alone.
+
+use std::ops::Range;
+
+/// The result expressions of the switch expression whose `switch` keyword
+/// starts at byte `start`, in source order and without surrounding
+/// whitespace. `None` unless every arm scans cleanly.
+pub fn arm_results(source: &str, start: usize) -> Option<Vec<Range<usize>>> {
+    let bytes = source.as_bytes();
+    if !source.get(start..)?.starts_with("switch") {
+        return None;
+    }
+    let mut i = skip_trivia(bytes, start + "switch".len());
+    if bytes.get(i) != Some(&b'(') {
+        return None;
+    }
+    i = skip_trivia(bytes, closing_bracket(bytes, i)? + 1);
+    if bytes.get(i) != Some(&b'{') {
+        return None;
+    }
+    let body = i + 1..closing_bracket(bytes, i)?;
+
+    let mut results = Vec::new();
+    for arm in split_top_level(bytes, body)? {
+        let arm = trim(bytes, arm);
+        if arm.is_empty() {
+            // Only a trailing comma leaves an empty arm
+            continue;
+        }
+        // A switch statement's `case 1:` or `default:`
+        let keyword_end = arm
+            .clone()
+            .find(|&j| !is_identifier_byte(bytes[j]))
+            .unwrap_or(arm.end);
+        if matches!(&source[arm.start..keyword_end], "case" | "default") {
+            return None;
+        }
+        let arrow = arm.start + arrow_offset(bytes, arm.clone())?;
+        let result = trim(bytes, arrow + 2..arm.end);
+        if result.is_empty() || arrow == arm.start {
+            return None;
+        }
+        results.push(result);
+    }
+    Some(results)
+}
+
+/// Split `range` at commas outside brackets, strings and comments
+fn split_top_level(bytes: &[u8], range: Range<usize>) -> Option<Vec<Range<usize>>> {
+    let mut arms = Vec::new();
+    let mut arm_start = range.start;
+    let mut i = range.start;
+    while i < range.end {
+        if let Some(next) = skip_literal(bytes, i) {
+            i = next;
+            continue;
+        }
+        match bytes[i] {
+            b'(' | b'[' | b'{' => i = closing_bracket(bytes, i)? + 1,
+            b')' | b']' | b'}' => return None,
+            b',' => {
+                arms.push(arm_start..i);
+                arm_start = i + 1;
+                i += 1;
+            }
+            _ => i += 1,
+        }
+    }
+    arms.push(arm_start..range.end);
+    Some(arms)
+}
+
+/// Offset of the first top-level `=>` in `range`
+fn arrow_offset(bytes: &[u8], range: Range<usize>) -> Option<usize> {
+    let mut i = range.start;
+    while i + 1 < range.end {
+        if let Some(next) = skip_literal(bytes, i) {
+            i = next;
+            continue;
+        }
+        match bytes[i] {
+            b'(' | b'[' | b'{' => i = closing_bracket(bytes, i)? + 1,
+            b'=' if bytes[i + 1] == b'>' => return Some(i - range.start),
+            _ => i += 1,
+        }
+    }
+    None
+}
+
+/// Index of the bracket closing the one at `open`
+fn closing_bracket(bytes: &[u8], open: usize) -> Option<usize> {
+    let mut expected = vec![closer(bytes[open])?];
+    let mut i = open + 1;
+    while i < bytes.len() {
+        if let Some(next) = skip_literal(bytes, i) {
+            i = next;
+            continue;
+        }
+        let byte = bytes[i];
+        if let Some(close) = closer(byte) {
+            expected.push(close);
+        } else if matches!(byte, b')' | b']' | b'}') {
+            if expected.pop() != Some(byte) {
+                return None;
+            }
+            if expected.is_empty() {
+                return Some(i);
+            }
+        }
+        i += 1;
+    }
+    None
+}
+
+const fn closer(open: u8) -> Option<u8> {
+    match open {
+        b'(' => Some(b')'),
+        b'[' => Some(b']'),
+        b'{' => Some(b'}'),
+        _ => None,
+    }
+}
+
+/// If a string literal or comment starts at `i`, the index just past it
+fn skip_literal(bytes: &[u8], i: usize) -> Option<usize> {
+    let rest = &bytes[i..];
+    if rest.starts_with(b"//") {
+        let end = rest.iter().position(|&b| b == b'\n').unwrap_or(rest.len());
+        return Some(i + end);
+    }
+    if rest.starts_with(b"/*") {
+        return Some(skip_block_comment(bytes, i));
+    }
+
+    // A raw prefix only counts where an identifier can't continue into it
+    let raw = rest.first() == Some(&b'r')
+        && matches!(rest.get(1), Some(b'\'' | b'"'))
+        && (i == 0 || !is_identifier_byte(bytes[i - 1]));
+    let quote_at = if raw { i + 1 } else { i };
+    let quote = *bytes.get(quote_at).filter(|b| matches!(b, b'\'' | b'"'))?;
+    let triple = bytes[quote_at..].starts_with(&[quote; 3]);
+    let delimiter_len = if triple { 3 } else { 1 };
+
+    let mut j = quote_at + delimiter_len;
+    while j < bytes.len() {
+        if bytes[j..].starts_with(&[quote; 3][..delimiter_len]) {
+            return Some(j + delimiter_len);
+        }
+        match bytes[j] {
+            b'\n' if !triple => return Some(j),
+            b'\\' if !raw => j += 2,
+            b'$' if !raw && bytes.get(j + 1) == Some(&b'{') => {
+                j = closing_bracket(bytes, j + 1).map_or(bytes.len(), |close| close + 1);
+            }
+            _ => j += 1,
+        }
+    }
+    Some(bytes.len())
+}
+
+/// Index just past the (possibly nested) block comment starting at `i`
+fn skip_block_comment(bytes: &[u8], i: usize) -> usize {
+    let mut depth = 0;
+    let mut j = i;
+    while j + 1 < bytes.len() {
+        if bytes[j..].starts_with(b"/*") {
+            depth += 1;
+            j += 2;
+        } else if bytes[j..].starts_with(b"*/") {
+            depth -= 1;
+            j += 2;
+            if depth == 0 {
+                return j;
+            }
+        } else {
+            j += 1;
+        }
+    }
+    bytes.len()
+}
+
+/// Skip whitespace and comments from `i`
+fn skip_trivia(bytes: &[u8], mut i: usize) -> usize {
+    loop {
+        while bytes.get(i).is_some_and(u8::is_ascii_whitespace) {
+            i += 1;
+        }
+        if bytes[i..].starts_with(b"//") || bytes[i..].starts_with(b"/*") {
+            i = skip_literal(bytes, i).unwrap_or(bytes.len());
+        } else {
+            return i;
+        }
+    }
+}
+
+const fn is_identifier_byte(byte: u8) -> bool {
+    byte.is_ascii_alphanumeric() || byte == b'_' || byte == b'$'
+}
+
+/// `range` without leading or trailing whitespace and comments
+fn trim(bytes: &[u8], range: Range<usize>) -> Range<usize> {
+    let start = skip_trivia(bytes, range.start).min(range.end);
+    let mut end = start;
+    let mut i = start;
+    while i < range.end {
+        if bytes[i].is_ascii_whitespace() {
+            i += 1;
+        } else if bytes[i..].starts_with(b"//") || bytes[i..].starts_with(b"/*") {
+            i = skip_literal(bytes, i).unwrap_or(range.end);
+        } else {
+            i = skip_literal(bytes, i).unwrap_or(i + 1);
+            end = i.min(range.end);
+        }
+    }
+    start..end
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    fn results(source: &str) -> Option<Vec<&str>> {
+        let start = source.find("switch").unwrap();
+        arm_results(source, start).map(|arms| arms.into_iter().map(|r| &source[r]).collect())
+    }
+
+    #[test]
+    fn test_finds_arm_results() {
+        let source = r#"
+            String label(int x) => switch (x) {
+                1 => 'one, or "1"', // a comma
+                2 when x.isEven => f(a, b),
+                _ => (int y) => y > 0 ? '}' : "${x}" // last
+            };
+        "#;
+
+        assert_eq!(
+            results(source).unwrap(),
+            vec![
+                r#"'one, or "1"'"#,
+                "f(a, b)",
+                r#"(int y) => y > 0 ? '}' : "${x}""#
+            ]
+        );
+    }
+
+    #[test]
+    fn test_rejects_text_that_is_not_a_switch_expression() {
+        // A switch statement
+        assert_eq!(
+            results("switch (x) { case 1: return 'a'; default: return 'b'; }"),
+            None
+        );
+        // An arm without a result
+        assert_eq!(results("switch (x) { 1 => , _ => 0 }"), None);
+        // A comma inside type arguments
+        assert_eq!(results("switch (x) { _ => <String, int>{} }"), None);
+        // Unbalanced brackets
+        assert_eq!(results("switch (x) { 1 => (2, _ => 0 }"), None);
+    }
+}
@@ -0,0 +1,153 @@
+//! Progress events from a run
+//!
+//! The pipeline reports what it is doing through a [`ProgressSink`] rather
+//! than drawing to the terminal itself, so a GUI or editor integration can
+//! follow a run as structured events. [`TerminalProgress`] is the progress
+//! bar the command line shows.
+
+use crate::mutation::{MutantStatus, Mutation};
+use crate::runner::MutantTestResult;
+use colored::Colorize;
+use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// Stage of the pipeline a run has entered
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Phase {
+    /// Finding the Dart files to mutate
+    Discovering,
+    /// Parsing files and generating mutants
+    Parsing {
+        /// Files to parse
+        files: usize,
+    },
+    /// Asking the `--ai` provider for extra mutation sites
+    AiSuggestions,
+    /// `--warmup` run of the test suite
+    Warmup,
+    /// Running the tests on the unmutated code
+    Baseline,
+    /// Testing mutants
+    Testing {
+        /// Mutants that will be tested
+        mutants: usize,
+    },
+    /// Writing reports
+    Reporting,
+    /// The run is over
+    Done,
+}
+
+/// Receives progress events. Mutants are tested in parallel, so the
+/// mutation callbacks arrive from several tasks at once and in no
+/// particular order. Every method does nothing by default
+pub trait ProgressSink: Send + Sync {
+    /// The run moved on to `phase`
+    fn on_phase_change(&self, phase: Phase) {
+        let _ = phase;
+    }
+
+    /// A mutant was applied and its tests are starting
+    fn on_mutation_started(&self, mutation: &Mutation) {
+        let _ = mutation;
+    }
+
+    /// A mutant's status is decided. Mutants left `Pending` by
+    /// `--max-duration` finish without starting
+    fn on_mutation_finished(&self, result: &MutantTestResult) {
+        let _ = result;
+    }
+}
+
+/// Ignores every event
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoProgress;
+
+impl ProgressSink for NoProgress {}
+
+/// Progress bar over the testing phase, with live killed and survived
+/// counts
+#[derive(Debug, Default)]
+pub struct TerminalProgress {
+    multi: MultiProgress,
+    testing: Mutex<Option<TestingBar>>,
+}
+
+#[derive(Debug)]
+struct TestingBar {
+    bar: ProgressBar,
+    killed: usize,
+    survived: usize,
+    tested: usize,
+}
+
+impl ProgressSink for TerminalProgress {
+    fn on_phase_change(&self, phase: Phase) {
+        let Ok(mut testing) = self.testing.lock() else {
+            return;
+        };
+        if let Some(done) = testing.take() {
+            done.bar.finish_with_message(format!(
+                "{} Tested {} mutations",
+                "✓".green(),
+                done.tested.to_string().cyan()
+            ));
+        }
+        if let Phase::Testing { mutants } = phase {
+            *testing = Some(TestingBar {
+                bar: create_progress_bar(&self.multi, mutants as u64, "Testing mutations"),
+                killed: 0,
+                survived: 0,
+                tested: 0,
+            });
+        }
+    }
+
+    fn on_mutation_finished(&self, result: &MutantTestResult) {
+        let Ok(mut testing) = self.testing.lock() else {
+            return;
+        };
+        let Some(testing) = testing.as_mut() else {
+            return;
+        };
+        match result.status {
+            MutantStatus::Killed | MutantStatus::Timeout => testing.killed += 1,
+            MutantStatus::Survived => testing.survived += 1,
+            _ => {}
+        }
+        if result.status != MutantStatus::Pending {
+            testing.tested += 1;
+        }
+        testing.bar.set_message(format!(
+            "killed: {} survived: {}",
+            testing.killed, testing.survived
+        ));
+        testing.bar.inc(1);
+    }
+}
+
+pub(crate) fn create_spinner(mp: &MultiProgress, message: &str) -> ProgressBar {
+    let pb = mp.add(ProgressBar::new_spinner());
+    pb.set_style(
+        ProgressStyle::default_spinner()
+            .template("{spinner:.cyan} {msg}")
+            .unwrap_or_else(|_| ProgressStyle::default_spinner())
+            .tick_chars("⠁⠂⠄⡀⢀⠠⠐⠈ "),
+    );
+    pb.set_message(message.to_string());
+    pb.enable_steady_tick(Duration::from_millis(100));
+    pb
+}
+
+pub(crate) fn create_progress_bar(mp: &MultiProgress, len: u64, message: &str) -> ProgressBar {
+    let pb = mp.add(ProgressBar::new(len));
+    pb.set_style(
+        ProgressStyle::default_bar()
+            .template("{spinner:.cyan} {msg} [{bar:40.cyan/blue}] {pos}/{len} ({eta})")
+            .unwrap_or_else(|_| ProgressStyle::default_bar())
+            .progress_chars("█▓▒░  "),
+    );
+    pb.set_message(message.to_string());
+    pb
+}
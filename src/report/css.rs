@@ -2,6 +2,16 @@
 //!
 //! This CSS matches the dart_mutant website's Toxic Lab theme.
 
+/// Appended to the report CSS for `--html-offline`: system font stacks in
+/// place of the Google Fonts the theme normally loads
+pub const SYSTEM_FONTS_CSS: &str = r#"
+:root {
+  --font-sans: system-ui, -apple-system, BlinkMacSystemFont, 'Segoe UI', Roboto, 'Helvetica Neue', Arial, sans-serif;
+  --font-mono: ui-monospace, 'SF Mono', Menlo, Consolas, 'Liberation Mono', monospace;
+  --font-display: var(--font-sans);
+}
+"#;
+
 /// Get the complete CSS for the HTML report
 pub fn get_report_css() -> &'static str {
     r#"/* ============================================
@@ -525,12 +535,26 @@ code {
   margin-bottom: var(--spacing-xs);
 }
 
+.mutant-tag {
+  margin-left: var(--spacing-xs);
+  padding: 0 var(--spacing-xs);
+  border: 1px solid var(--text-muted);
+  border-radius: var(--radius-sm);
+  font-size: 0.7rem;
+}
+
 .mutant-description {
   font-size: 0.9rem;
   color: var(--text-primary);
   margin-bottom: var(--spacing-sm);
 }
 
+.mutant-killer {
+  font-size: 0.8rem;
+  color: var(--text-muted);
+  margin-bottom: var(--spacing-sm);
+}
+
 .mutant-code {
   font-family: var(--font-mono);
   font-size: 0.85rem;
@@ -559,6 +583,120 @@ code {
   color: var(--color-killed);
 }
 
+.report-note {
+  margin-top: var(--spacing-md);
+  color: var(--text-secondary);
+  font-size: 0.85rem;
+  text-align: center;
+}
+
+/* Timing */
+.timing {
+  margin-top: var(--spacing-xl);
+}
+
+.timing-table {
+  width: 100%;
+  border-collapse: collapse;
+  margin-bottom: var(--spacing-lg);
+  background: var(--bg-card);
+  border: 1px solid var(--border-color);
+  border-radius: var(--radius-lg);
+  font-size: 0.9rem;
+}
+
+.timing-table th,
+.timing-table td {
+  padding: var(--spacing-sm) var(--spacing-md);
+  text-align: left;
+  border-bottom: 1px solid var(--border-color);
+}
+
+.timing-table th {
+  color: var(--text-secondary);
+  font-weight: 500;
+}
+
+.timing-table th:last-child,
+.timing-table .timing-secs {
+  text-align: right;
+  font-family: var(--font-mono);
+}
+
+/* Embedded source */
+.file-source {
+  margin-top: var(--spacing-md);
+  border: 1px solid var(--border-color);
+  border-radius: var(--radius-md);
+  background: var(--bg-darkest);
+}
+
+.file-source summary {
+  cursor: pointer;
+  padding: var(--spacing-sm) var(--spacing-md);
+  color: var(--text-secondary);
+  font-size: 0.85rem;
+}
+
+.source-code {
+  font-family: var(--font-mono);
+  font-size: 0.8rem;
+  overflow-x: auto;
+  padding: var(--spacing-sm) 0;
+}
+
+.source-line {
+  display: flex;
+  white-space: pre;
+}
+
+.source-line.has-mutant {
+  background: var(--bg-light);
+}
+
+.source-line.survived {
+  background: rgba(255, 107, 53, 0.12);
+}
+
+.line-number {
+  min-width: 3.5rem;
+  padding-right: var(--spacing-md);
+  text-align: right;
+  color: var(--text-muted);
+  user-select: none;
+}
+
+.line-marker {
+  min-width: 3rem;
+  user-select: none;
+}
+
+.mutant-snippet {
+  margin-top: var(--spacing-sm);
+  border: 1px solid var(--border-color);
+  border-radius: var(--radius-md);
+  background: var(--bg-darkest);
+}
+
+.mutant-snippet summary {
+  cursor: pointer;
+  padding: var(--spacing-xs) var(--spacing-md);
+  color: var(--text-secondary);
+  font-size: 0.8rem;
+}
+
+.mutant-token {
+  background: rgba(255, 107, 53, 0.3);
+  color: inherit;
+  border-radius: var(--radius-sm);
+}
+
+.source-omitted {
+  margin-top: var(--spacing-md);
+  color: var(--text-muted);
+  font-size: 0.85rem;
+}
+
 /* Hidden states */
 .mutant-item.hidden {
   display: none;
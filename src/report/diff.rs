@@ -0,0 +1,317 @@
+//! Score diff report against a baseline JSON report
+//!
+//! Produces a markdown summary suited to PR comments: per-file score deltas
+//! plus the mutants whose status changed relative to the baseline.
+
+use super::{
+    build_json_report, status_from_json, JsonFile, JsonMutant, JsonReport, MutationResult,
+    ReportOptions,
+};
+use crate::cli::TimeoutPolicy;
+use crate::mutation::MutantStatus;
+use crate::runner::MutantTestResult;
+use anyhow::{Context, Result};
+use std::collections::{BTreeSet, HashMap};
+use std::fmt::Write as _;
+use std::path::Path;
+
+/// Generate a markdown diff report comparing this run to a baseline
+/// Stryker-compatible JSON report. File scores are tallied as in the run
+/// summary, under `timeout_policy`
+pub fn generate_diff_report(
+    result: &MutationResult,
+    test_results: &[MutantTestResult],
+    baseline_path: &Path,
+    output_path: &Path,
+    options: &ReportOptions,
+    timeout_policy: TimeoutPolicy,
+) -> Result<()> {
+    let baseline_json = std::fs::read_to_string(baseline_path).with_context(|| {
+        format!(
+            "Failed to read baseline report: {}",
+            baseline_path.display()
+        )
+    })?;
+    let baseline: JsonReport = serde_json::from_str(&baseline_json).with_context(|| {
+        format!(
+            "Failed to parse baseline report: {}",
+            baseline_path.display()
+        )
+    })?;
+
+    let current = build_json_report(result, test_results, options);
+    let markdown = render_diff(&baseline, &current, timeout_policy);
+
+    std::fs::write(output_path, markdown).context("Failed to write diff report")?;
+
+    Ok(())
+}
+
+/// Mutants that changed status in a single file
+struct FileDiff<'a> {
+    file: &'a str,
+    before: Option<f64>,
+    after: Option<f64>,
+    newly_killed: Vec<&'a JsonMutant>,
+    newly_surviving: Vec<&'a JsonMutant>,
+}
+
+impl FileDiff<'_> {
+    fn is_unchanged(&self) -> bool {
+        self.newly_killed.is_empty() && self.newly_surviving.is_empty() && self.before == self.after
+    }
+}
+
+fn is_detected(status: &str, timeout_policy: TimeoutPolicy) -> bool {
+    match status_from_json(status) {
+        Some(MutantStatus::Killed) => true,
+        Some(MutantStatus::Timeout) => timeout_policy == TimeoutPolicy::Killed,
+        _ => false,
+    }
+}
+
+/// The file's score as [`MutationResult::from_results`] computes it, or
+/// `None` when no mutant counts towards it
+fn file_score(file: &JsonFile, timeout_policy: TimeoutPolicy) -> Option<f64> {
+    let statuses = file
+        .mutants
+        .iter()
+        .filter_map(|m| status_from_json(&m.status))
+        .map(|status| (status, false));
+    let (result, valid) = MutationResult::tally_statuses(statuses, timeout_policy);
+    (valid > 0).then_some(result.mutation_score)
+}
+
+/// A mutant is newly killed when the baseline had it undetected, and newly
+/// surviving when it survives now but did not survive (or did not exist) before
+fn diff_file<'a>(
+    file: &'a str,
+    baseline: Option<&'a JsonFile>,
+    current: Option<&'a JsonFile>,
+    timeout_policy: TimeoutPolicy,
+) -> FileDiff<'a> {
+    let baseline_status: HashMap<&str, &str> = baseline
+        .map(|f| {
+            f.mutants
+                .iter()
+                .map(|m| (m.id.as_str(), m.status.as_str()))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let mut newly_killed = Vec::new();
+    let mut newly_surviving = Vec::new();
+    for mutant in current.iter().flat_map(|f| &f.mutants) {
+        let before = baseline_status.get(mutant.id.as_str()).copied();
+        let detected = |status| is_detected(status, timeout_policy);
+        if detected(&mutant.status) && before.is_some_and(|s| !detected(s)) {
+            newly_killed.push(mutant);
+        } else if mutant.status == "Survived" && before != Some("Survived") {
+            newly_surviving.push(mutant);
+        }
+    }
+
+    FileDiff {
+        file,
+        before: baseline.and_then(|f| file_score(f, timeout_policy)),
+        after: current.and_then(|f| file_score(f, timeout_policy)),
+        newly_killed,
+        newly_surviving,
+    }
+}
+
+fn format_score(score: Option<f64>) -> String {
+    score.map_or_else(|| "—".to_string(), |s| format!("{s:.0}%"))
+}
+
+fn format_delta(before: Option<f64>, after: Option<f64>) -> String {
+    match (before, after) {
+        (Some(before), Some(after)) => format!("{:+.0}", after - before),
+        _ => "—".to_string(),
+    }
+}
+
+fn render_diff(
+    baseline: &JsonReport,
+    current: &JsonReport,
+    timeout_policy: TimeoutPolicy,
+) -> String {
+    let files: BTreeSet<&str> = baseline
+        .files
+        .keys()
+        .chain(current.files.keys())
+        .map(String::as_str)
+        .collect();
+
+    let diffs: Vec<FileDiff<'_>> = files
+        .into_iter()
+        .map(|file| {
+            diff_file(
+                file,
+                baseline.files.get(file),
+                current.files.get(file),
+                timeout_policy,
+            )
+        })
+        .filter(|d| !d.is_unchanged())
+        .collect();
+
+    let mut report = String::new();
+    report.push_str("# Mutation Score Diff\n\n");
+    let _ = writeln!(
+        report,
+        "**Overall**: {:.1}% → {:.1}% ({:+.1})\n",
+        baseline.mutation_score,
+        current.mutation_score,
+        current.mutation_score - baseline.mutation_score
+    );
+
+    if diffs.is_empty() {
+        report.push_str("No per-file changes relative to the baseline.\n");
+        return report;
+    }
+
+    report.push_str("| File | Before | After | Δ | Newly killed | Newly surviving |\n");
+    report.push_str("| ---- | ------ | ----- | - | ------------ | --------------- |\n");
+    for d in &diffs {
+        let _ = writeln!(
+            report,
+            "| {} | {} | {} | {} | {} | {} |",
+            d.file,
+            format_score(d.before),
+            format_score(d.after),
+            format_delta(d.before, d.after),
+            d.newly_killed.len(),
+            d.newly_surviving.len()
+        );
+    }
+
+    for d in &diffs {
+        let _ = writeln!(
+            report,
+            "\n## {}: {} → {}, {} mutants newly killed, {} newly surviving\n",
+            d.file,
+            format_score(d.before),
+            format_score(d.after),
+            d.newly_killed.len(),
+            d.newly_surviving.len()
+        );
+        for m in &d.newly_surviving {
+            let _ = writeln!(
+                report,
+                "- 🔴 Line {}: {} (→ `{}`) newly surviving",
+                m.location.start.line, m.mutator_name, m.replacement
+            );
+        }
+        for m in &d.newly_killed {
+            let _ = writeln!(
+                report,
+                "- 🟢 Line {}: {} (→ `{}`) newly killed",
+                m.location.start.line, m.mutator_name, m.replacement
+            );
+        }
+    }
+
+    report
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    fn report(score: f64, statuses: &[(&str, &str)]) -> JsonReport {
+        let mutants = statuses
+            .iter()
+            .map(|(id, status)| {
+                serde_json::from_value(serde_json::json!({
+                    "id": id,
+                    "mutatorName": "Arithmetic: + → -",
+                    "replacement": "-",
+                    "status": status,
+                    "location": {
+                        "start": { "line": 1, "column": 1 },
+                        "end": { "line": 1, "column": 2 }
+                    }
+                }))
+                .unwrap()
+            })
+            .collect();
+        JsonReport {
+            schema_version: "1".to_string(),
+            thresholds: super::super::Thresholds::default(),
+            files: HashMap::from([(
+                "lib/payments.dart".to_string(),
+                JsonFile {
+                    language: "dart".to_string(),
+                    source: String::new(),
+                    mutants,
+                },
+            )]),
+            project_root: None,
+            mutation_score: score,
+            mutants_per_second: None,
+        }
+    }
+
+    #[test]
+    fn test_reports_newly_killed_and_surviving_mutants() {
+        let baseline = report(
+            50.0,
+            &[
+                ("a", "Survived"),
+                ("b", "Survived"),
+                ("c", "Killed"),
+                ("d", "Killed"),
+            ],
+        );
+        let current = report(
+            75.0,
+            &[
+                ("a", "Killed"),
+                ("b", "Killed"),
+                ("c", "Survived"),
+                ("d", "Killed"),
+            ],
+        );
+
+        let markdown = render_diff(&baseline, &current, TimeoutPolicy::Killed);
+
+        assert!(markdown.contains("**Overall**: 50.0% → 75.0% (+25.0)"));
+        assert!(markdown
+            .contains("lib/payments.dart: 50% → 75%, 2 mutants newly killed, 1 newly surviving"));
+        assert_eq!(markdown.matches("newly killed\n").count(), 2);
+    }
+
+    #[test]
+    fn test_skips_unchanged_files() {
+        let baseline = report(50.0, &[("a", "Survived"), ("b", "Killed")]);
+        let markdown = render_diff(
+            &baseline,
+            &report(50.0, &[("a", "Survived"), ("b", "Killed")]),
+            TimeoutPolicy::Killed,
+        );
+
+        assert!(markdown.contains("No per-file changes"));
+    }
+
+    #[test]
+    fn test_file_scores_are_tallied_like_the_run_summary() {
+        let file = |statuses| report(0.0, statuses).files.into_values().next().unwrap();
+        let scored = file(&[
+            ("a", "Killed"),
+            ("b", "Survived"),
+            ("c", "Timeout"),
+            ("d", "NoCoverage"),
+            ("e", "CompileError"),
+        ]);
+
+        let score = |policy| file_score(&scored, policy).unwrap();
+        assert!((score(TimeoutPolicy::Killed) - 200.0 / 3.0).abs() < 1e-9);
+        assert!((score(TimeoutPolicy::Ignored) - 50.0).abs() < 1e-9);
+        assert!((score(TimeoutPolicy::Error) - 50.0).abs() < 1e-9);
+
+        let unscored = file(&[("a", "NoCoverage"), ("b", "CompileError")]);
+        assert_eq!(file_score(&unscored, TimeoutPolicy::Killed), None);
+    }
+}
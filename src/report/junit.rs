@@ -0,0 +1,189 @@
+//! JUnit XML report
+//!
+//! Each mutant is a `<testcase>` and each source file a `<testsuite>`, so CI
+//! systems (GitLab, Jenkins, Azure DevOps) list surviving mutants as failed
+//! tests.
+
+use super::{html_escape as xml_escape, MutationResult, ReportOptions};
+use crate::mutation::MutantStatus;
+use crate::runner::MutantTestResult;
+use anyhow::{Context, Result};
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::path::Path;
+
+/// Generate a JUnit XML report
+pub fn generate_junit_report(
+    result: &MutationResult,
+    test_results: &[MutantTestResult],
+    output_path: &Path,
+    options: &ReportOptions,
+) -> Result<()> {
+    let xml = render_junit(result, test_results, options);
+    std::fs::write(output_path, xml).context("Failed to write JUnit report")?;
+    Ok(())
+}
+
+fn render_junit(
+    result: &MutationResult,
+    test_results: &[MutantTestResult],
+    options: &ReportOptions,
+) -> String {
+    let mut by_file: BTreeMap<String, Vec<&MutantTestResult>> = BTreeMap::new();
+    for r in test_results {
+        by_file
+            .entry(options.display_path(&r.mutation.location.file))
+            .or_default()
+            .push(r);
+    }
+
+    let total_time: f64 = test_results.iter().map(|r| r.duration.as_secs_f64()).sum();
+
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    let _ = writeln!(
+        xml,
+        r#"<testsuites name="dart_mutant" tests="{}" failures="{}" errors="{}" time="{:.3}">"#,
+        result.total,
+        result.survived + result.no_coverage,
+        result.errors,
+        total_time
+    );
+
+    for (file, mut cases) in by_file {
+        cases.sort_by_key(|r| {
+            (
+                r.mutation.location.start_line,
+                r.mutation.location.start_col,
+            )
+        });
+        let failures = cases.iter().filter(|r| is_undetected(r.status)).count();
+        let errors = cases
+            .iter()
+            .filter(|r| r.status == MutantStatus::Error)
+            .count();
+        let skipped = cases
+            .iter()
+            .filter(|r| r.status == MutantStatus::Pending)
+            .count();
+        let time: f64 = cases.iter().map(|r| r.duration.as_secs_f64()).sum();
+
+        let _ = writeln!(
+            xml,
+            r#"  <testsuite name="{}" tests="{}" failures="{failures}" errors="{errors}" skipped="{skipped}" time="{time:.3}">"#,
+            xml_escape(&file),
+            cases.len()
+        );
+        for r in cases {
+            write_testcase(&mut xml, &file, r);
+        }
+        xml.push_str("  </testsuite>\n");
+    }
+
+    xml.push_str("</testsuites>\n");
+    xml
+}
+
+fn is_undetected(status: MutantStatus) -> bool {
+    matches!(status, MutantStatus::Survived | MutantStatus::NoCoverage)
+}
+
+fn write_testcase(xml: &mut String, file: &str, r: &MutantTestResult) {
+    let m = &r.mutation;
+    let name = format!("{file}::{}::{}", m.location.start_line, m.operator.name());
+    let _ = write!(
+        xml,
+        r#"    <testcase name="{}" classname="{}" time="{:.3}""#,
+        xml_escape(&name),
+        xml_escape(file),
+        r.duration.as_secs_f64()
+    );
+
+    match r.status {
+        MutantStatus::Killed | MutantStatus::Timeout => xml.push_str("/>\n"),
+        MutantStatus::Survived | MutantStatus::NoCoverage => {
+            let message = if r.status == MutantStatus::Survived {
+                "Mutation survived"
+            } else {
+                "Mutation not covered by any test"
+            };
+            let change = format!("{} → {}", m.original, m.mutated);
+            let _ = write!(
+                xml,
+                ">\n      <failure message=\"{message}: {}\">",
+                xml_escape(&change)
+            );
+            let _ = write!(
+                xml,
+                "{}:{}:{}\n- {}\n+ {}",
+                xml_escape(file),
+                m.location.start_line,
+                m.location.start_col,
+                xml_escape(&m.original),
+                xml_escape(&m.mutated)
+            );
+            xml.push_str("</failure>\n    </testcase>\n");
+        }
+        MutantStatus::Error => {
+            let _ = writeln!(
+                xml,
+                ">\n      <error message=\"{}\"/>\n    </testcase>",
+                xml_escape(r.error.as_deref().unwrap_or("Mutant could not be tested"))
+            );
+        }
+        MutantStatus::Pending => xml.push_str(">\n      <skipped/>\n    </testcase>\n"),
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::cli::TimeoutPolicy;
+    use crate::mutation::testing::mutant;
+    use crate::mutation::MutationOperator;
+    use std::path::PathBuf;
+    use std::time::Duration;
+
+    fn result(
+        line: usize,
+        original: &str,
+        mutated: &str,
+        status: MutantStatus,
+    ) -> MutantTestResult {
+        mutant()
+            .file("/project/lib/calc.dart")
+            .line(line)
+            .column(5)
+            .change(original, mutated)
+            .operator(MutationOperator::ComparisonGtToGte)
+            .status(status)
+            .duration(Duration::from_millis(1500))
+            .result()
+    }
+
+    #[test]
+    fn test_surviving_mutants_become_failures() {
+        let results = vec![
+            result(12, ">", ">=", MutantStatus::Survived),
+            result(5, "<", "<=", MutantStatus::Killed),
+        ];
+        let result = MutationResult::from_results(&results, TimeoutPolicy::Killed);
+        let options = ReportOptions {
+            path_root: Some(PathBuf::from("/project")),
+            ..ReportOptions::default()
+        };
+
+        let xml = render_junit(&result, &results, &options);
+
+        assert!(xml.contains(
+            r#"<testsuite name="lib/calc.dart" tests="2" failures="1" errors="0" skipped="0" time="3.000">"#
+        ));
+        assert!(xml.contains("<failure message=\"Mutation survived: &gt; → &gt;=\">"));
+        assert_eq!(xml.matches("<failure").count(), 1);
+        // Cases are ordered by line, and killed mutants are passing cases
+        let killed = xml.find("lib/calc.dart::5::").unwrap();
+        let survived = xml.find("lib/calc.dart::12::").unwrap();
+        assert!(killed < survived);
+        assert!(xml[killed..survived].contains("/>\n"));
+    }
+}
@@ -4,14 +4,32 @@
 //! Uses the Toxic Lab theme from the dart_mutant website.
 
 mod css;
-
-use crate::mutation::MutantStatus;
+mod diff;
+mod junit;
+mod ndjson;
+mod sarif;
+mod survivors;
+mod thresholds;
+mod verification;
+
+pub use diff::generate_diff_report;
+pub use junit::generate_junit_report;
+pub use ndjson::NdjsonStream;
+pub use sarif::generate_sarif_report;
+pub use survivors::Survivors;
+pub use thresholds::{FileThresholdViolation, FileThresholds};
+pub use verification::{generate_verification_report, verification_by_operator};
+
+use crate::cli::TimeoutPolicy;
+use crate::mutation::{MutantStatus, Mutation};
+use crate::mutators::MutatorCategory;
 use crate::runner::MutantTestResult;
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fmt::Write as _;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 
 /// Helper trait for MutantStatus display
 pub trait MutantStatusDisplay {
@@ -46,13 +64,67 @@ impl MutantStatusDisplay for MutantStatus {
 /// Overall mutation testing results
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MutationResult {
+    /// Mutants tested, or reported without testing
     pub total: usize,
+    /// Mutants a test failed on
     pub killed: usize,
+    /// Mutants every test passed on
     pub survived: usize,
+    /// Mutants whose tests ran past the timeout
     pub timeout: usize,
+    /// Mutants no test reaches
     pub no_coverage: usize,
+    /// Mutants that could not be tested
     pub errors: usize,
+    /// Mutants left untested when `--max-duration` ran out
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub pending: usize,
+    /// Percentage of detected mutants, see `--timeout-policy`
     pub mutation_score: f64,
+    /// Number of generated mutations before `--max-mutations` was applied
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub capped_from: Option<usize>,
+    /// Files cut down by `--max-mutations-per-file`, whose scores are based
+    /// on a subset of their mutants
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub capped_files: Vec<CappedFile>,
+    /// Seed used to pick the `--sample`, to reproduce the run with `--seed`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sample_seed: Option<u64>,
+    /// Files scoring below their `--file-thresholds` entry
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub file_threshold_violations: Vec<FileThresholdViolation>,
+    /// Tested mutants per second of test execution (absent when no mutant
+    /// was tested, as in dry runs)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mutants_per_second: Option<f64>,
+    /// Duration of the unmutated test run in seconds (absent for dry runs)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub baseline_secs: Option<f64>,
+    /// Mutants whose result came from the `--incremental` cache
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub cached: usize,
+    /// Survivors flagged as likely equivalent to the original code
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub likely_equivalent: usize,
+    /// Mutation score per operator category, for categories with scored mutants
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub category_scores: HashMap<MutatorCategory, f64>,
+}
+
+/// A file that generated more mutations than `--max-mutations-per-file`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CappedFile {
+    /// File path as shown in reports
+    pub file: String,
+    /// Mutations generated for the file
+    pub generated: usize,
+    /// Mutations kept
+    pub kept: usize,
+}
+
+const fn is_zero(n: &usize) -> bool {
+    *n == 0
 }
 
 impl Default for MutationResult {
@@ -64,35 +136,125 @@ impl Default for MutationResult {
             timeout: 0,
             no_coverage: 0,
             errors: 0,
+            pending: 0,
             mutation_score: 0.0,
+            capped_from: None,
+            capped_files: Vec::new(),
+            sample_seed: None,
+            file_threshold_violations: Vec::new(),
+            mutants_per_second: None,
+            baseline_secs: None,
+            cached: 0,
+            likely_equivalent: 0,
+            category_scores: HashMap::new(),
         }
     }
 }
 
 impl MutationResult {
-    pub fn from_results(results: &[MutantTestResult]) -> Self {
-        let mut r = Self::default();
-        r.total = results.len();
+    /// Tally the statuses of `results` and compute the score
+    pub fn from_results(results: &[MutantTestResult], timeout_policy: TimeoutPolicy) -> Self {
+        let (mut r, _) = Self::tally(results, timeout_policy);
 
+        let mut by_category: HashMap<MutatorCategory, Vec<MutantTestResult>> = HashMap::new();
         for result in results {
-            match result.status {
+            by_category
+                .entry(result.mutation.operator.category())
+                .or_default()
+                .push(result.clone());
+        }
+        r.category_scores = by_category
+            .into_iter()
+            .filter_map(|(category, category_results)| {
+                let (scored, valid) = Self::tally(&category_results, timeout_policy);
+                (valid > 0).then_some((category, scored.mutation_score))
+            })
+            .collect();
+
+        r
+    }
+
+    /// Status counts and overall score, plus the number of mutants the
+    /// score is computed over
+    fn tally(results: &[MutantTestResult], timeout_policy: TimeoutPolicy) -> (Self, usize) {
+        Self::tally_statuses(
+            results
+                .iter()
+                .map(|r| (r.status, r.mutation.likely_equivalent)),
+            timeout_policy,
+        )
+    }
+
+    /// [`Self::tally`] over `(status, likely_equivalent)` pairs, for
+    /// mutants read back from a JSON report
+    fn tally_statuses(
+        statuses: impl IntoIterator<Item = (MutantStatus, bool)>,
+        timeout_policy: TimeoutPolicy,
+    ) -> (Self, usize) {
+        let mut r = Self::default();
+
+        for (status, likely_equivalent) in statuses {
+            r.total += 1;
+            match status {
                 MutantStatus::Killed => r.killed += 1,
-                MutantStatus::Survived => r.survived += 1,
+                MutantStatus::Survived => {
+                    r.survived += 1;
+                    if likely_equivalent {
+                        r.likely_equivalent += 1;
+                    }
+                }
+                MutantStatus::Timeout if timeout_policy == TimeoutPolicy::Error => r.errors += 1,
                 MutantStatus::Timeout => r.timeout += 1,
                 MutantStatus::NoCoverage => r.no_coverage += 1,
-                MutantStatus::Error | MutantStatus::Pending => r.errors += 1,
+                MutantStatus::Error => r.errors += 1,
+                MutantStatus::Pending => r.pending += 1,
             }
         }
 
-        let detected = r.killed + r.timeout;
-        let valid = r.total - r.errors - r.no_coverage;
+        let (detected, ignored) = match timeout_policy {
+            TimeoutPolicy::Killed => (r.killed + r.timeout, 0),
+            TimeoutPolicy::Ignored => (r.killed, r.timeout),
+            TimeoutPolicy::Error => (r.killed, 0),
+        };
+        let valid = r.total - r.errors - r.pending - r.no_coverage - ignored;
         r.mutation_score = if valid > 0 {
             (detected as f64 / valid as f64) * 100.0
         } else {
             0.0
         };
 
-        r
+        (r, valid)
+    }
+}
+
+/// Settings shared by the report generators
+#[derive(Debug, Clone, Default)]
+pub struct ReportOptions {
+    /// Show file paths relative to this directory instead of as discovered
+    pub path_root: Option<PathBuf>,
+    /// Embed the source of each mutated file in the HTML report, unless it
+    /// has more lines than this cap
+    pub source_line_cap: Option<usize>,
+    /// Add a unified diff of each mutant to the JSON report
+    pub json_diffs: bool,
+    /// Keep the HTML report free of external requests: no web fonts
+    pub html_offline: bool,
+    /// Score bands for the badge, the HTML and the JSON report
+    pub thresholds: Thresholds,
+    /// How timed-out mutants count toward per-file scores
+    pub timeout_policy: TimeoutPolicy,
+}
+
+impl ReportOptions {
+    /// Path of `file` as it should appear in reports
+    fn display_path(&self, file: &Path) -> String {
+        self.path_root
+            .as_deref()
+            .and_then(|root| file.strip_prefix(root).ok())
+            .filter(|relative| !relative.as_os_str().is_empty())
+            .unwrap_or(file)
+            .display()
+            .to_string()
     }
 }
 
@@ -100,37 +262,70 @@ impl MutationResult {
 pub fn generate_html_report(
     result: &MutationResult,
     test_results: &[MutantTestResult],
-    dart_files: &[std::path::PathBuf],
+    dart_files: &[PathBuf],
     output_path: &Path,
+    options: &ReportOptions,
 ) -> Result<()> {
-    // Group results by file
-    let mut by_file: HashMap<String, Vec<&MutantTestResult>> = HashMap::new();
+    let mut file_stats = compute_file_stats(test_results, options);
+    for stats in &mut file_stats {
+        let mutants: Vec<&MutantTestResult> = stats.mutants.iter().collect();
+        stats.source_html = options
+            .source_line_cap
+            .map(|max_lines| generate_source_section(&stats.path, &mutants, max_lines));
+        stats.source = std::fs::read_to_string(&stats.path).ok();
+    }
+
+    let html = generate_html_content(
+        result,
+        &file_stats,
+        dart_files.len(),
+        options.html_offline,
+        options.thresholds,
+    );
+
+    std::fs::write(output_path, html).context("Failed to write HTML report")?;
+
+    Ok(())
+}
+
+/// Per-file counts and scores, lowest score first. Shared by the HTML
+/// report and the console breakdown so both agree, and scored like the
+/// run summary.
+fn compute_file_stats(
+    test_results: &[MutantTestResult],
+    options: &ReportOptions,
+) -> Vec<FileStats> {
+    let mut by_file: HashMap<&Path, Vec<&MutantTestResult>> = HashMap::new();
     for r in test_results {
-        let file = r.mutation.location.file.display().to_string();
-        by_file.entry(file).or_default().push(r);
+        by_file
+            .entry(r.mutation.location.file.as_path())
+            .or_default()
+            .push(r);
     }
 
-    // Calculate per-file stats
     let mut file_stats: Vec<FileStats> = by_file
         .iter()
         .map(|(file, results)| {
-            let total = results.len();
-            let killed = results
-                .iter()
-                .filter(|r| matches!(r.status, MutantStatus::Killed | MutantStatus::Timeout))
-                .count();
-            let score = if total > 0 {
-                (killed as f64 / total as f64) * 100.0
-            } else {
-                0.0
+            let (tally, _) = MutationResult::tally_statuses(
+                results
+                    .iter()
+                    .map(|r| (r.status, r.mutation.likely_equivalent)),
+                options.timeout_policy,
+            );
+            let killed = match options.timeout_policy {
+                TimeoutPolicy::Killed => tally.killed + tally.timeout,
+                TimeoutPolicy::Ignored | TimeoutPolicy::Error => tally.killed,
             };
 
             FileStats {
-                file: file.clone(),
-                total,
+                file: options.display_path(file),
+                path: file.to_path_buf(),
+                total: tally.total,
                 killed,
-                score,
+                score: tally.mutation_score,
                 mutants: results.iter().map(|r| (*r).clone()).collect(),
+                source: None,
+                source_html: None,
             }
         })
         .collect();
@@ -139,33 +334,210 @@ pub fn generate_html_report(
         a.score
             .partial_cmp(&b.score)
             .unwrap_or(std::cmp::Ordering::Equal)
+            .then_with(|| a.file.cmp(&b.file))
     });
+    file_stats
+}
 
-    let html = generate_html_content(result, &file_stats, dart_files.len());
+/// Plain-text table of per-file results for the console, lowest score
+/// first, naming the operator with the most survivors in each file
+pub fn format_file_breakdown(test_results: &[MutantTestResult], options: &ReportOptions) -> String {
+    let file_stats = compute_file_stats(test_results, options);
+    let file_width = file_stats
+        .iter()
+        .map(|f| f.file.chars().count())
+        .max()
+        .unwrap_or(0)
+        .max("File".len());
+
+    let mut table = format!(
+        "  {:<file_width$}  {:>12}  {:>6}  Most survivors\n",
+        "File", "Killed", "Score"
+    );
+    for stats in &file_stats {
+        let mut survivors: HashMap<&str, usize> = HashMap::new();
+        for m in &stats.mutants {
+            if m.status == MutantStatus::Survived {
+                *survivors.entry(m.mutation.operator.name()).or_default() += 1;
+            }
+        }
+        // Most survivors, ties broken by name so the output is stable
+        let worst = survivors
+            .into_iter()
+            .max_by(|a, b| a.1.cmp(&b.1).then_with(|| b.0.cmp(a.0)))
+            .map_or_else(
+                || "-".to_string(),
+                |(name, count)| format!("{name} ({count})"),
+            );
+        let _ = writeln!(
+            table,
+            "  {:<file_width$}  {:>12}  {:>5.1}%  {worst}",
+            stats.file,
+            format!("{}/{}", stats.killed, stats.total),
+            stats.score,
+        );
+    }
+    table
+}
 
-    std::fs::create_dir_all(output_path.parent().unwrap_or(Path::new(".")))?;
-    std::fs::write(output_path, html).context("Failed to write HTML report")?;
+/// How many mutants the timing breakdown lists
+const SLOWEST_MUTANTS: usize = 10;
 
-    Ok(())
+/// Where the test time went, from the mutants that ran tests
+struct Timing<'a> {
+    /// The slowest mutants with their display paths, slowest first
+    slowest: Vec<(&'a str, &'a MutantTestResult)>,
+    /// Display path, mutants tested and total time per file, most time first
+    per_file: Vec<(&'a str, usize, Duration)>,
+}
+
+fn compute_timing(file_stats: &[FileStats]) -> Timing<'_> {
+    let mut slowest = Vec::new();
+    let mut per_file = Vec::new();
+    for stats in file_stats {
+        let timed: Vec<&MutantTestResult> = stats
+            .mutants
+            .iter()
+            .filter(|m| !m.duration.is_zero())
+            .collect();
+        if timed.is_empty() {
+            continue;
+        }
+        per_file.push((
+            stats.file.as_str(),
+            timed.len(),
+            timed.iter().map(|m| m.duration).sum::<Duration>(),
+        ));
+        slowest.extend(timed.into_iter().map(|m| (stats.file.as_str(), m)));
+    }
+    slowest.sort_by(|a, b| b.1.duration.cmp(&a.1.duration).then_with(|| a.0.cmp(b.0)));
+    slowest.truncate(SLOWEST_MUTANTS);
+    per_file.sort_by(|a, b| b.2.cmp(&a.2).then_with(|| a.0.cmp(b.0)));
+    Timing { slowest, per_file }
+}
+
+/// Plain-text list of the slowest mutants and the test time per file, for
+/// the console. `None` when no mutant ran tests
+pub fn format_timing_breakdown(
+    test_results: &[MutantTestResult],
+    options: &ReportOptions,
+) -> Option<String> {
+    let file_stats = compute_file_stats(test_results, options);
+    let timing = compute_timing(&file_stats);
+    if timing.slowest.is_empty() {
+        return None;
+    }
+
+    let location =
+        |file: &str, m: &MutantTestResult| format!("{file}:{}", m.mutation.location.start_line);
+    let width = timing
+        .slowest
+        .iter()
+        .map(|(file, m)| location(file, m).chars().count())
+        .chain(
+            timing
+                .per_file
+                .iter()
+                .map(|(file, _, _)| file.chars().count()),
+        )
+        .max()
+        .unwrap_or(0)
+        .max("Slowest mutants".len());
+
+    let mut text = format!("  {:<width$}  {:>8}  Operator\n", "Slowest mutants", "Time");
+    for (file, m) in &timing.slowest {
+        let _ = writeln!(
+            text,
+            "  {:<width$}  {:>7.1}s  {}",
+            location(file, m),
+            m.duration.as_secs_f64(),
+            m.mutation.operator.name()
+        );
+    }
+    let _ = write!(text, "\n  {:<width$}  {:>8}  Mutants\n", "File", "Time");
+    for (file, count, total) in &timing.per_file {
+        let _ = writeln!(
+            text,
+            "  {:<width$}  {:>7.1}s  {count}",
+            file,
+            total.as_secs_f64()
+        );
+    }
+    Some(text)
+}
+
+/// HTML tables of the slowest mutants and the test time per file; empty
+/// when no mutant ran tests
+fn generate_timing_section(file_stats: &[FileStats]) -> String {
+    let timing = compute_timing(file_stats);
+    if timing.slowest.is_empty() {
+        return String::new();
+    }
+
+    let mut slowest_rows = String::new();
+    for (file, m) in &timing.slowest {
+        let _ = write!(
+            slowest_rows,
+            "<tr><td>{}:{}</td><td>{}</td><td class=\"timing-secs\">{:.1}s</td></tr>",
+            html_escape(file),
+            m.mutation.location.start_line,
+            html_escape(m.mutation.operator.name()),
+            m.duration.as_secs_f64()
+        );
+    }
+    let mut file_rows = String::new();
+    for (file, count, total) in &timing.per_file {
+        let _ = write!(
+            file_rows,
+            "<tr><td>{}</td><td>{count}</td><td class=\"timing-secs\">{:.1}s</td></tr>",
+            html_escape(file),
+            total.as_secs_f64()
+        );
+    }
+
+    format!(
+        r#"<section class="timing">
+            <h2 class="section-title">Timing</h2>
+            <table class="timing-table">
+                <thead><tr><th>Slowest mutants</th><th>Operator</th><th>Time</th></tr></thead>
+                <tbody>{slowest_rows}</tbody>
+            </table>
+            <table class="timing-table">
+                <thead><tr><th>File</th><th>Mutants</th><th>Time</th></tr></thead>
+                <tbody>{file_rows}</tbody>
+            </table>
+        </section>"#
+    )
 }
 
 #[derive(Debug, Clone)]
 struct FileStats {
     file: String,
+    path: PathBuf,
     total: usize,
     killed: usize,
     score: f64,
     mutants: Vec<MutantTestResult>,
+    /// Current contents of the file, for per-mutant snippets
+    source: Option<String>,
+    source_html: Option<String>,
 }
 
+/// Google Fonts used by the report theme
+const WEB_FONT_LINKS: &str = r#"<link rel="preconnect" href="https://fonts.googleapis.com">
+    <link rel="preconnect" href="https://fonts.gstatic.com" crossorigin>
+    <link href="https://fonts.googleapis.com/css2?family=Inter:wght@400;500;600;700&family=JetBrains+Mono:wght@400;500&family=Orbitron:wght@700&display=swap" rel="stylesheet">"#;
+
 fn generate_html_content(
     result: &MutationResult,
     file_stats: &[FileStats],
     total_files: usize,
+    offline: bool,
+    thresholds: Thresholds,
 ) -> String {
-    let score_class = if result.mutation_score >= 80.0 {
+    let score_class = if result.mutation_score >= f64::from(thresholds.high) {
         "high"
-    } else if result.mutation_score >= 60.0 {
+    } else if result.mutation_score >= f64::from(thresholds.low) {
         "medium"
     } else {
         "low"
@@ -173,10 +545,42 @@ fn generate_html_content(
 
     let files_html: String = file_stats
         .iter()
-        .map(|f| generate_file_section(f))
+        .map(|f| generate_file_section(f, thresholds))
         .collect();
 
+    let mut cap_note = result.capped_from.map_or_else(String::new, |generated| {
+        format!(
+            r#"<p class="report-note">Capped at {} of {generated} generated mutations (--max-mutations)</p>"#,
+            result.total
+        )
+    });
+    if !result.capped_files.is_empty() {
+        let files: Vec<String> = result
+            .capped_files
+            .iter()
+            .map(|c| format!("{} ({} of {})", html_escape(&c.file), c.kept, c.generated))
+            .collect();
+        let _ = write!(
+            cap_note,
+            r#"<p class="report-note">Scores based on a subset of mutations (--max-mutations-per-file): {}</p>"#,
+            files.join(", ")
+        );
+    }
+    if result.pending > 0 {
+        let _ = write!(
+            cap_note,
+            r#"<p class="report-note">Time budget exceeded: tested {} of {} mutations (--max-duration)</p>"#,
+            result.total - result.pending,
+            result.total
+        );
+    }
+
     let report_css = css::get_report_css();
+    let (font_links, font_css) = if offline {
+        ("", css::SYSTEM_FONTS_CSS)
+    } else {
+        (WEB_FONT_LINKS, "")
+    };
 
     format!(
         r#"<!DOCTYPE html>
@@ -185,11 +589,9 @@ fn generate_html_content(
     <meta charset="UTF-8">
     <meta name="viewport" content="width=device-width, initial-scale=1.0">
     <title>🧬 Dart Mutant - Mutation Testing Report</title>
-    <link rel="preconnect" href="https://fonts.googleapis.com">
-    <link rel="preconnect" href="https://fonts.gstatic.com" crossorigin>
-    <link href="https://fonts.googleapis.com/css2?family=Inter:wght@400;500;600;700&family=JetBrains+Mono:wght@400;500&family=Orbitron:wght@700&display=swap" rel="stylesheet">
+    {font_links}
     <style>
-{report_css}
+{report_css}{font_css}
     </style>
 </head>
 <body>
@@ -237,6 +639,7 @@ fn generate_html_content(
                     <div class="stat-label">Errors ⚠️</div>
                 </div>
             </div>
+            {cap_note}
         </div>
 
         <section>
@@ -251,6 +654,8 @@ fn generate_html_content(
             {files_html}
         </section>
 
+        {timing_html}
+
         <footer class="footer">
             Generated by <a href="https://github.com/user/dart_mutant">dart_mutant</a> •
             Mutation testing helps you write better tests by finding gaps in your test coverage
@@ -293,6 +698,8 @@ fn generate_html_content(
 </body>
 </html>"#,
         report_css = report_css,
+        font_links = font_links,
+        font_css = font_css,
         score = result.mutation_score,
         score_class = score_class,
         total = result.total,
@@ -304,13 +711,15 @@ fn generate_html_content(
         total_files = total_files,
         file_count = file_stats.len(),
         files_html = files_html,
+        timing_html = generate_timing_section(file_stats),
+        cap_note = cap_note,
     )
 }
 
-fn generate_file_section(file_stats: &FileStats) -> String {
-    let score_class = if file_stats.score >= 80.0 {
+fn generate_file_section(file_stats: &FileStats, thresholds: Thresholds) -> String {
+    let score_class = if file_stats.score >= f64::from(thresholds.high) {
         "high"
-    } else if file_stats.score >= 60.0 {
+    } else if file_stats.score >= f64::from(thresholds.low) {
         "medium"
     } else {
         "low"
@@ -322,17 +731,31 @@ fn generate_file_section(file_stats: &FileStats) -> String {
         .map(|m| {
             let status_class = MutantStatusDisplay::css_class(&m.status);
             let status_emoji = MutantStatusDisplay::emoji(&m.status);
+            let equivalent_tag =
+                if m.status == MutantStatus::Survived && m.mutation.likely_equivalent {
+                    r#" <span class="mutant-tag">likely equivalent</span>"#
+                } else {
+                    ""
+                };
+            let killer = m.killed_by.as_deref().map_or_else(String::new, |test| {
+                format!(
+                    r#"<div class="mutant-killer">Killed by: {}</div>"#,
+                    html_escape(test)
+                )
+            });
             format!(
                 r#"<div class="mutant-item {status_class}">
                     <div class="mutant-status">{status_emoji}</div>
                     <div class="mutant-details">
-                        <div class="mutant-location">Line {line}:{col}</div>
+                        <div class="mutant-location">Line {line}:{col}{equivalent_tag}</div>
                         <div class="mutant-description">{description}</div>
+                        {killer}
                         <div class="mutant-code">
                             <span class="code-original">{original}</span>
                             →
                             <span class="code-replacement">{replacement}</span>
                         </div>
+                        {snippet}
                     </div>
                 </div>"#,
                 status_class = status_class,
@@ -342,6 +765,7 @@ fn generate_file_section(file_stats: &FileStats) -> String {
                 description = html_escape(&m.mutation.description),
                 original = html_escape(&m.mutation.original),
                 replacement = html_escape(&m.mutation.mutated),
+                snippet = generate_mutant_snippet(file_stats.source.as_deref(), &m.mutation),
             )
         })
         .collect();
@@ -357,6 +781,7 @@ fn generate_file_section(file_stats: &FileStats) -> String {
             </div>
             <div class="file-content">
                 {mutants_html}
+                {source_html}
             </div>
         </div>"#,
         file = html_escape(&file_stats.file),
@@ -365,6 +790,165 @@ fn generate_file_section(file_stats: &FileStats) -> String {
         score = file_stats.score,
         score_class = score_class,
         mutants_html = mutants_html,
+        source_html = file_stats.source_html.as_deref().unwrap_or_default(),
+    )
+}
+
+/// Lines of source shown above and below a mutant in the HTML report
+const SNIPPET_CONTEXT_LINES: usize = 3;
+
+/// The whole lines a mutant touches, with some unchanged lines either side
+struct LineWindow<'a> {
+    /// Byte offset of the first mutated line
+    start: usize,
+    /// Byte offset of the end of the last mutated line, before its newline
+    end: usize,
+    /// Line number of the first mutated line, 1-based
+    first_line: usize,
+    /// Lines just above the mutated ones
+    before: Vec<&'a str>,
+    /// Lines just below the mutated ones
+    after: Vec<&'a str>,
+}
+
+impl<'a> LineWindow<'a> {
+    /// The window around `mutation` with up to `context` lines either side.
+    /// `None` unless the source still has the mutated code at its offsets
+    fn new(source: &'a str, mutation: &Mutation, context: usize) -> Option<Self> {
+        if !mutation.matches(source) {
+            return None;
+        }
+        let loc = &mutation.location;
+        let start = source[..loc.byte_start].rfind('\n').map_or(0, |i| i + 1);
+        let end = source[loc.byte_end..]
+            .find('\n')
+            .map_or(source.len(), |i| loc.byte_end + i);
+
+        let mut before: Vec<&str> = source[..start].lines().collect();
+        let first_line = before.len() + 1;
+        before.drain(..before.len().saturating_sub(context));
+        let after = source[end..]
+            .strip_prefix('\n')
+            .unwrap_or_default()
+            .lines()
+            .take(context)
+            .collect();
+        Some(Self {
+            start,
+            end,
+            first_line,
+            before,
+            after,
+        })
+    }
+}
+
+/// Collapsible source around one mutant with the mutated code highlighted.
+/// Falls back to a note when the file is gone or no longer matches the
+/// mutation's byte offsets.
+fn generate_mutant_snippet(source: Option<&str>, mutation: &Mutation) -> String {
+    let loc = &mutation.location;
+    let Some((source, window)) = source.and_then(|source| {
+        LineWindow::new(source, mutation, SNIPPET_CONTEXT_LINES).map(|window| (source, window))
+    }) else {
+        return r#"<div class="source-omitted">Source unavailable</div>"#.to_string();
+    };
+    let LineWindow {
+        start: line_start,
+        end: line_end,
+        first_line,
+        before,
+        after,
+    } = window;
+
+    let line_html = |number: usize, class: &str, code: &str| {
+        format!(
+            r#"<div class="source-line{class}"><span class="line-number">{number}</span><span class="line-code">{code}</span></div>"#
+        )
+    };
+    let mut lines_html = String::new();
+    for (offset, line) in before.iter().enumerate() {
+        let number = first_line - before.len() + offset;
+        lines_html.push_str(&line_html(number, "", &html_escape(line)));
+    }
+    let mutated_code = format!(
+        r#"{}<mark class="mutant-token">{}</mark>{}"#,
+        html_escape(&source[line_start..loc.byte_start]),
+        html_escape(&mutation.original),
+        html_escape(&source[loc.byte_end..line_end])
+    );
+    lines_html.push_str(&line_html(first_line, " has-mutant", &mutated_code));
+    let last_mutated_line = first_line + source[line_start..line_end].matches('\n').count();
+    for (offset, line) in after.into_iter().enumerate() {
+        lines_html.push_str(&line_html(
+            last_mutated_line + 1 + offset,
+            "",
+            &html_escape(line),
+        ));
+    }
+
+    format!(
+        r#"<details class="mutant-snippet">
+                            <summary>Code</summary>
+                            <pre class="source-code">{lines_html}</pre>
+                        </details>"#
+    )
+}
+
+/// Render a file's source with line numbers, marking lines that carry mutants.
+/// Files longer than `max_lines` are replaced by a short note.
+fn generate_source_section(file: &Path, mutants: &[&MutantTestResult], max_lines: usize) -> String {
+    let Ok(source) = std::fs::read_to_string(file) else {
+        return r#"<div class="source-omitted">Source unavailable</div>"#.to_string();
+    };
+
+    let line_count = source.lines().count();
+    if line_count > max_lines {
+        return format!(
+            r#"<div class="source-omitted">Source omitted: {line_count} lines exceeds the {max_lines}-line cap</div>"#
+        );
+    }
+
+    let mut by_line: HashMap<usize, Vec<&MutantTestResult>> = HashMap::new();
+    for m in mutants {
+        by_line
+            .entry(m.mutation.location.start_line)
+            .or_default()
+            .push(m);
+    }
+
+    let lines_html: String = source
+        .lines()
+        .enumerate()
+        .map(|(idx, line)| {
+            let line_number = idx + 1;
+            let (line_class, marker) = by_line.get(&line_number).map_or_else(
+                || (String::new(), String::new()),
+                |line_mutants| {
+                    // A surviving mutant is the most interesting thing on a line
+                    let status = line_mutants
+                        .iter()
+                        .find(|m| m.status == MutantStatus::Survived)
+                        .unwrap_or(&line_mutants[0])
+                        .status;
+                    (
+                        format!(" has-mutant {}", status.css_class()),
+                        format!("{} {}", status.emoji(), line_mutants.len()),
+                    )
+                },
+            );
+            format!(
+                r#"<div class="source-line{line_class}"><span class="line-number">{line_number}</span><span class="line-marker">{marker}</span><span class="line-code">{code}</span></div>"#,
+                code = html_escape(line),
+            )
+        })
+        .collect();
+
+    format!(
+        r#"<details class="file-source">
+                    <summary>Source ({line_count} lines)</summary>
+                    <pre class="source-code">{lines_html}</pre>
+                </details>"#
     )
 }
 
@@ -381,49 +965,160 @@ pub fn generate_json_report(
     result: &MutationResult,
     test_results: &[MutantTestResult],
     output_path: &Path,
+    options: &ReportOptions,
 ) -> Result<()> {
-    let report = JsonReport {
-        schema_version: "1".to_string(),
-        thresholds: Thresholds { high: 80, low: 60 },
-        files: generate_json_files(test_results),
-        project_root: std::env::current_dir()
-            .map(|p| p.display().to_string())
-            .unwrap_or_default(),
-        mutation_score: result.mutation_score,
-    };
+    let report = build_json_report(result, test_results, options);
 
     let json = serde_json::to_string_pretty(&report)?;
-    std::fs::create_dir_all(output_path.parent().unwrap_or(Path::new(".")))?;
     std::fs::write(output_path, json).context("Failed to write JSON report")?;
 
     Ok(())
 }
 
+/// Generate the standard Stryker HTML report: a page that hands the
+/// Stryker-schema JSON to the `mutation-testing-elements` web component
+pub fn generate_stryker_html_report(
+    result: &MutationResult,
+    test_results: &[MutantTestResult],
+    output_path: &Path,
+    options: &ReportOptions,
+) -> Result<()> {
+    let report = build_json_report(result, test_results, options);
+
+    // Keep `</script>` inside string values from closing the script tag
+    let json = serde_json::to_string(&report)?.replace("</", "<\\/");
+    let html = format!(
+        r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+    <meta charset="UTF-8">
+    <meta name="viewport" content="width=device-width, initial-scale=1.0">
+    <title>Mutation Report - dart_mutant</title>
+    <script src="https://unpkg.com/mutation-testing-elements"></script>
+</head>
+<body>
+    <mutation-test-report-app title-postfix="dart_mutant"></mutation-test-report-app>
+    <script>
+        document.querySelector('mutation-test-report-app').report = {json};
+    </script>
+</body>
+</html>
+"#
+    );
+
+    std::fs::write(output_path, html).context("Failed to write Stryker HTML report")?;
+
+    Ok(())
+}
+
+/// Write a shields.io endpoint badge with the mutation score, colored using
+/// the report thresholds
+pub fn generate_badge(
+    result: &MutationResult,
+    output_path: &Path,
+    options: &ReportOptions,
+) -> Result<()> {
+    let thresholds = &options.thresholds;
+    let color = if result.mutation_score >= f64::from(thresholds.high) {
+        "green"
+    } else if result.mutation_score >= f64::from(thresholds.low) {
+        "yellow"
+    } else {
+        "red"
+    };
+
+    let badge = Badge {
+        schema_version: 1,
+        label: "mutation".to_string(),
+        message: format!("{:.0}%", result.mutation_score),
+        color: color.to_string(),
+    };
+
+    let json = serde_json::to_string(&badge)?;
+    std::fs::write(output_path, json).context("Failed to write badge")?;
+
+    Ok(())
+}
+
 #[derive(Serialize)]
+struct Badge {
+    #[serde(rename = "schemaVersion")]
+    schema_version: u32,
+    label: String,
+    message: String,
+    color: String,
+}
+
+fn build_json_report(
+    result: &MutationResult,
+    test_results: &[MutantTestResult],
+    options: &ReportOptions,
+) -> JsonReport {
+    // Relative paths keep reports portable, so don't leak the absolute root
+    let project_root = if options.path_root.is_some() {
+        None
+    } else {
+        std::env::current_dir()
+            .ok()
+            .map(|p| p.display().to_string())
+    };
+
+    JsonReport {
+        schema_version: "1".to_string(),
+        thresholds: options.thresholds,
+        files: generate_json_files(test_results, options),
+        project_root,
+        mutation_score: result.mutation_score,
+        mutants_per_second: result.mutants_per_second,
+    }
+}
+
+#[derive(Serialize, Deserialize)]
 struct JsonReport {
     #[serde(rename = "schemaVersion")]
     schema_version: String,
     thresholds: Thresholds,
     files: HashMap<String, JsonFile>,
-    #[serde(rename = "projectRoot")]
-    project_root: String,
-    #[serde(rename = "mutationScore")]
+    #[serde(
+        rename = "projectRoot",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    project_root: Option<String>,
+    #[serde(rename = "mutationScore", default)]
     mutation_score: f64,
+    #[serde(
+        rename = "mutantsPerSecond",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    mutants_per_second: Option<f64>,
 }
 
-#[derive(Serialize)]
-struct Thresholds {
-    high: u32,
-    low: u32,
+/// Score bands, as in Stryker's `thresholds` setting
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct Thresholds {
+    /// Scores at or above this are good
+    pub high: u32,
+    /// Scores below this are poor
+    pub low: u32,
 }
 
-#[derive(Serialize)]
+impl Default for Thresholds {
+    fn default() -> Self {
+        Self { high: 80, low: 60 }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
 struct JsonFile {
     language: String,
+    #[serde(default)]
+    source: String,
     mutants: Vec<JsonMutant>,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, Deserialize)]
 struct JsonMutant {
     id: String,
     #[serde(rename = "mutatorName")]
@@ -431,39 +1126,92 @@ struct JsonMutant {
     replacement: String,
     status: String,
     location: JsonLocation,
+    #[serde(default)]
     description: String,
+    /// Stryker's free-form explanation of the status
+    #[serde(
+        rename = "statusReason",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    status_reason: Option<String>,
+    /// Unified diff of the mutated lines, with `--json-with-diff`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    diff: Option<String>,
+    /// Extra test runs from `--rerun-killed` or `--confirm-survivors`
+    #[serde(default, skip_serializing_if = "is_zero")]
+    retries: usize,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, Deserialize)]
 struct JsonLocation {
     start: JsonPosition,
     end: JsonPosition,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, Deserialize)]
 struct JsonPosition {
     line: usize,
     column: usize,
 }
 
-fn generate_json_files(results: &[MutantTestResult]) -> HashMap<String, JsonFile> {
+fn generate_json_files(
+    results: &[MutantTestResult],
+    options: &ReportOptions,
+) -> HashMap<String, JsonFile> {
     let mut files: HashMap<String, JsonFile> = HashMap::new();
 
     for result in results {
-        let file = result.mutation.location.file.display().to_string();
+        let file = options.display_path(&result.mutation.location.file);
+        let json_file = files.entry(file.clone()).or_insert_with(|| JsonFile {
+            language: "dart".to_string(),
+            source: std::fs::read_to_string(&result.mutation.location.file).unwrap_or_default(),
+            mutants: vec![],
+        });
+        let diff = options
+            .json_diffs
+            .then(|| unified_diff(&file, &json_file.source, &result.mutation))
+            .flatten();
+
+        json_file.mutants.push(JsonMutant::new(result, diff));
+    }
+
+    files
+}
+
+/// The Stryker name of a status
+const fn json_status(status: MutantStatus) -> &'static str {
+    match status {
+        MutantStatus::Killed => "Killed",
+        MutantStatus::Survived => "Survived",
+        MutantStatus::Timeout => "Timeout",
+        MutantStatus::NoCoverage => "NoCoverage",
+        MutantStatus::Error => "CompileError",
+        MutantStatus::Pending => "Pending",
+    }
+}
 
-        let mutant = JsonMutant {
+/// The status a JSON report names, if it's one this tool writes
+fn status_from_json(status: &str) -> Option<MutantStatus> {
+    [
+        MutantStatus::Killed,
+        MutantStatus::Survived,
+        MutantStatus::Timeout,
+        MutantStatus::NoCoverage,
+        MutantStatus::Error,
+        MutantStatus::Pending,
+    ]
+    .into_iter()
+    .find(|&s| json_status(s) == status)
+}
+
+impl JsonMutant {
+    fn new(result: &MutantTestResult, diff: Option<String>) -> Self {
+        Self {
             id: result.mutation.id.clone(),
             mutator_name: result.mutation.operator.name().to_string(),
             replacement: result.mutation.mutated.clone(),
-            status: match result.status {
-                MutantStatus::Killed => "Killed",
-                MutantStatus::Survived => "Survived",
-                MutantStatus::Timeout => "Timeout",
-                MutantStatus::NoCoverage => "NoCoverage",
-                MutantStatus::Error | MutantStatus::Pending => "CompileError",
-            }
-            .to_string(),
+            status: json_status(result.status).to_string(),
             location: JsonLocation {
                 start: JsonPosition {
                     line: result.mutation.location.start_line,
@@ -475,31 +1223,206 @@ fn generate_json_files(results: &[MutantTestResult]) -> HashMap<String, JsonFile
                 },
             },
             description: result.mutation.description.clone(),
-        };
+            status_reason: (result.status == MutantStatus::Survived
+                && result.mutation.likely_equivalent)
+                .then(|| "Likely equivalent mutant".to_string()),
+            diff,
+            retries: result.retries as usize,
+        }
+    }
+}
+
+/// Lines of unchanged context around each diff
+const DIFF_CONTEXT_LINES: usize = 3;
+
+/// Unified diff of the whole lines a mutation touches, with a few lines of
+/// context. `None` if the mutation doesn't fit the source.
+fn unified_diff(file: &str, source: &str, mutation: &Mutation) -> Option<String> {
+    let loc = &mutation.location;
+    let LineWindow {
+        start: line_start,
+        end: line_end,
+        first_line,
+        before,
+        after,
+    } = LineWindow::new(source, mutation, DIFF_CONTEXT_LINES)?;
+    let mutated_lines = format!(
+        "{}{}{}",
+        &source[line_start..loc.byte_start],
+        mutation.mutated,
+        &source[loc.byte_end..line_end]
+    );
+
+    let removed: Vec<&str> = source[line_start..line_end].split('\n').collect();
+    let added: Vec<&str> = mutated_lines.split('\n').collect();
+
+    let mut diff = format!("--- a/{file}\n+++ b/{file}\n");
+    let _ = writeln!(
+        diff,
+        "@@ -{first},{old} +{first},{new} @@",
+        first = first_line - before.len(),
+        old = before.len() + removed.len() + after.len(),
+        new = before.len() + added.len() + after.len(),
+    );
+    for line in before {
+        let _ = writeln!(diff, " {line}");
+    }
+    for line in removed {
+        let _ = writeln!(diff, "-{line}");
+    }
+    for line in added {
+        let _ = writeln!(diff, "+{line}");
+    }
+    for line in after {
+        let _ = writeln!(diff, " {line}");
+    }
+    Some(diff)
+}
+
+/// Generate a Cobertura-style XML report for tools that ingest coverage.
+///
+/// This maps mutation results onto the coverage format; it is not line
+/// coverage. Each file is a `<class>` whose `line-rate` is the fraction of
+/// its mutants detected, and each line with a mutant is a `<line>` with
+/// `hits="1"` when every mutant on it was detected and `hits="0"`
+/// otherwise. Compile errors and untested mutants are left out.
+pub fn generate_cobertura_report(
+    result: &MutationResult,
+    test_results: &[MutantTestResult],
+    output_path: &Path,
+    options: &ReportOptions,
+) -> Result<()> {
+    let xml = render_cobertura(result, test_results, options);
+    std::fs::write(output_path, xml).context("Failed to write Cobertura report")?;
+    Ok(())
+}
+
+/// Mutants and per-line outcomes of one file in the Cobertura report
+#[derive(Default)]
+struct CoberturaClass {
+    detected: usize,
+    scored: usize,
+    /// Line number -> every mutant on it was detected
+    lines: BTreeMap<usize, bool>,
+}
+
+impl CoberturaClass {
+    fn line_rate(&self) -> f64 {
+        if self.scored == 0 {
+            0.0
+        } else {
+            self.detected as f64 / self.scored as f64
+        }
+    }
+}
 
-        files
+fn render_cobertura(
+    result: &MutationResult,
+    test_results: &[MutantTestResult],
+    options: &ReportOptions,
+) -> String {
+    // package (directory) -> file -> results
+    let mut packages: BTreeMap<String, BTreeMap<String, CoberturaClass>> = BTreeMap::new();
+    for r in test_results {
+        let detected = match r.status {
+            MutantStatus::Killed | MutantStatus::Timeout => true,
+            MutantStatus::Survived | MutantStatus::NoCoverage => false,
+            MutantStatus::Error | MutantStatus::Pending => continue,
+        };
+        let file = options
+            .display_path(&r.mutation.location.file)
+            .replace('\\', "/");
+        let package = file
+            .rsplit_once('/')
+            .map_or_else(|| ".".to_string(), |(dir, _)| dir.replace('/', "."));
+        let class = packages
+            .entry(package)
+            .or_default()
             .entry(file)
-            .or_insert_with(|| JsonFile {
-                language: "dart".to_string(),
-                mutants: vec![],
-            })
-            .mutants
-            .push(mutant);
+            .or_default();
+        class.scored += 1;
+        class.detected += usize::from(detected);
+        *class
+            .lines
+            .entry(r.mutation.location.start_line)
+            .or_insert(true) &= detected;
     }
 
-    files
+    let lines: Vec<bool> = packages
+        .values()
+        .flat_map(BTreeMap::values)
+        .flat_map(|class| class.lines.values().copied())
+        .collect();
+
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    xml.push_str(
+        "<!DOCTYPE coverage SYSTEM \"http://cobertura.sourceforge.net/xml/coverage-04.dtd\">\n",
+    );
+    let _ = writeln!(
+        xml,
+        r#"<coverage line-rate="{:.4}" branch-rate="0" lines-covered="{}" lines-valid="{}" branches-covered="0" branches-valid="0" complexity="0" version="dart_mutant {}" timestamp="{}">"#,
+        result.mutation_score / 100.0,
+        lines.iter().filter(|&&hit| hit).count(),
+        lines.len(),
+        env!("CARGO_PKG_VERSION"),
+        chrono::Utc::now().timestamp_millis()
+    );
+    xml.push_str("  <sources>\n    <source>.</source>\n  </sources>\n  <packages>\n");
+
+    for (package, classes) in &packages {
+        let package_total = CoberturaClass {
+            detected: classes.values().map(|c| c.detected).sum(),
+            scored: classes.values().map(|c| c.scored).sum(),
+            lines: BTreeMap::new(),
+        };
+        let _ = writeln!(
+            xml,
+            r#"    <package name="{}" line-rate="{:.4}" branch-rate="0" complexity="0">"#,
+            html_escape(package),
+            package_total.line_rate()
+        );
+        xml.push_str("      <classes>\n");
+        for (file, class) in classes {
+            let name = file.rsplit('/').next().unwrap_or(file);
+            let _ = writeln!(
+                xml,
+                r#"        <class name="{}" filename="{}" line-rate="{:.4}" branch-rate="0" complexity="0">"#,
+                html_escape(name),
+                html_escape(file),
+                class.line_rate()
+            );
+            xml.push_str("          <methods/>\n          <lines>\n");
+            for (number, &hit) in &class.lines {
+                let _ = writeln!(
+                    xml,
+                    r#"            <line number="{number}" hits="{}"/>"#,
+                    u8::from(hit)
+                );
+            }
+            xml.push_str("          </lines>\n        </class>\n");
+        }
+        xml.push_str("      </classes>\n    </package>\n");
+    }
+
+    xml.push_str("  </packages>\n</coverage>\n");
+    xml
 }
 
+/// Tests listed under "Most Effective Tests" in the AI report
+const AI_REPORT_TOP_TESTS: usize = 20;
+
 /// Generate an AI-friendly markdown report optimized for LLM consumption
 ///
 /// This report is structured to help AI assistants quickly understand:
 /// - What code has surviving mutants (test gaps)
 /// - What changes were made that tests didn't catch
 /// - What kind of tests would catch each mutant
+/// - Which tests kill the most mutants
 pub fn generate_ai_report(
     result: &MutationResult,
     test_results: &[MutantTestResult],
     output_path: &Path,
+    options: &ReportOptions,
 ) -> Result<()> {
     let mut report = String::new();
 
@@ -522,6 +1445,13 @@ pub fn generate_ai_report(
         "- **Survived**: {} (tests missed the bug)",
         result.survived
     );
+    if result.likely_equivalent > 0 {
+        let _ = writeln!(
+            report,
+            "- **Likely Equivalent**: {} of the survivors cannot change behaviour",
+            result.likely_equivalent
+        );
+    }
     let _ = writeln!(report, "- **Timeout**: {}", result.timeout);
     let _ = writeln!(report, "- **Errors**: {}\n", result.errors);
 
@@ -529,7 +1459,7 @@ pub fn generate_ai_report(
     let mut survived_by_file: HashMap<String, Vec<&MutantTestResult>> = HashMap::new();
     for r in test_results {
         if matches!(r.status, MutantStatus::Survived) {
-            let file = r.mutation.location.file.display().to_string();
+            let file = options.display_path(&r.mutation.location.file);
             survived_by_file.entry(file).or_default().push(r);
         }
     }
@@ -543,7 +1473,7 @@ pub fn generate_ai_report(
 
         // Sort files by number of survivors (worst first)
         let mut files: Vec<_> = survived_by_file.iter().collect();
-        files.sort_by(|a, b| b.1.len().cmp(&a.1.len()));
+        files.sort_by_key(|(_, mutants)| std::cmp::Reverse(mutants.len()));
 
         for (file, mutants) in files {
             let _ = writeln!(report, "### {}\n", file);
@@ -559,15 +1489,42 @@ pub fn generate_ai_report(
                 let _ = writeln!(report, "**Mutation**: `{}` → `{}`\n", m.original, m.mutated);
                 let _ = writeln!(report, "**Operator**: {}\n", m.operator.name());
 
-                // Generate test hint based on operator
-                let test_hint = generate_test_hint(&m.operator, &m.original, &m.mutated);
-                let _ = writeln!(report, "**Suggested Test**: {}\n", test_hint);
+                if m.likely_equivalent {
+                    report.push_str(
+                        "**Likely Equivalent**: the change can't alter the result (an identity \
+                        operand like `0` or `1`, or a boundary where both branches give the same \
+                        value), so no test can tell the mutant apart. Simplify the expression \
+                        instead.\n\n",
+                    );
+                } else {
+                    // Generate test hint based on operator
+                    let test_hint = generate_test_hint(&m.operator, &m.original, &m.mutated);
+                    let _ = writeln!(report, "**Suggested Test**: {}\n", test_hint);
+                }
 
                 report.push_str("---\n\n");
             }
         }
     }
 
+    // Which tests do the killing, to show where the suite is strong
+    let mut kills_by_test: HashMap<&str, usize> = HashMap::new();
+    for r in test_results {
+        if let Some(test) = &r.killed_by {
+            *kills_by_test.entry(test).or_default() += 1;
+        }
+    }
+    if !kills_by_test.is_empty() {
+        let mut tests: Vec<_> = kills_by_test.into_iter().collect();
+        tests.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+        report.push_str("## Most Effective Tests\n\n");
+        report.push_str("Tests that were the first to fail on the most mutants.\n\n");
+        for (test, kills) in tests.iter().take(AI_REPORT_TOP_TESTS) {
+            let _ = writeln!(report, "- `{test}`: killed {kills}");
+        }
+        report.push('\n');
+    }
+
     // Add section for easy copy-paste file:line references
     if !survived_by_file.is_empty() {
         report.push_str("## Quick Reference (file:line)\n\n");
@@ -587,14 +1544,14 @@ pub fn generate_ai_report(
         report.push_str("```\n");
     }
 
-    std::fs::create_dir_all(output_path.parent().unwrap_or(Path::new(".")))?;
     std::fs::write(output_path, report).context("Failed to write AI report")?;
 
     Ok(())
 }
 
 /// Generate a test hint based on the mutation operator
-fn generate_test_hint(
+/// Suggest the kind of test that would kill a mutant
+pub fn generate_test_hint(
     operator: &crate::mutation::MutationOperator,
     original: &str,
     mutated: &str,
@@ -619,6 +1576,16 @@ fn generate_test_hint(
                 original, mutated
             )
         }
+        MutationOperator::AssignmentAddToSub | MutationOperator::AssignmentSubToAdd => format!(
+            "Assert on the accumulated value after `{}` runs, starting from a non-zero \
+            amount so adding and subtracting give different results.",
+            original
+        ),
+        MutationOperator::AssignmentMulToDiv | MutationOperator::AssignmentDivToMul => format!(
+            "Assert on the value after `{}` runs, using a factor other than 1 so \
+            multiplying and dividing give different results.",
+            original
+        ),
         MutationOperator::ArithmeticModToMul => {
             "Test modulo operation with values that produce a remainder (not evenly divisible)."
                 .to_string()
@@ -660,7 +1627,7 @@ fn generate_test_hint(
             one condition is true and the other is false."
                 .to_string()
         }
-        MutationOperator::LogicalNotRemoval => {
+        MutationOperator::LogicalNotRemoval | MutationOperator::LogicalNotInsertion => {
             "Add tests for both true and false outcomes of the negated expression. \
             Ensure the test fails when negation is removed."
                 .to_string()
@@ -706,6 +1673,11 @@ fn generate_test_hint(
             Verify behavior when the object is null vs non-null."
                 .to_string()
         }
+        MutationOperator::NullableTypeRemoval => {
+            "Test the null path explicitly. If this type can be non-nullable, \
+            tighten it; otherwise cover the code that handles null."
+                .to_string()
+        }
         MutationOperator::NullAssertionRemoval => {
             "Test with non-null values to ensure assertion (`!`) behavior is correct.".to_string()
         }
@@ -734,6 +1706,17 @@ fn generate_test_hint(
             "Test the else branch explicitly. Verify behavior when the if condition is false."
                 .to_string()
         }
+        MutationOperator::ControlFlowLoopConditionFalse => {
+            "Assert on the result of the loop's work. A test that passes when the loop never \
+            runs isn't checking what it does."
+                .to_string()
+        }
+        MutationOperator::ControlFlowLoopConditionTrue => {
+            "Test that the loop terminates. A mutant that loops forever times out and counts \
+            as killed; one that survives means the loop exits some other way and no test \
+            checks where it stops."
+                .to_string()
+        }
         MutationOperator::ControlFlowBreakRemoval
         | MutationOperator::ControlFlowContinueRemoval => {
             "Test loop termination/continuation. Verify the loop stops or continues at the right time."
@@ -743,10 +1726,38 @@ fn generate_test_hint(
             "Test early return conditions. Verify function returns expected value at the return point."
                 .to_string()
         }
+        MutationOperator::AsyncAwaitRemoval => {
+            "Assert on state after the awaited call completes. Without `await`, \
+            the code carries on before the Future finishes."
+                .to_string()
+        }
+        MutationOperator::MethodCallRemoval => format!(
+            "Assert on the side effect of `{}`. A test that passes without the call \
+            isn't checking what it does.",
+            original.trim_end_matches(';')
+        ),
+        MutationOperator::ControlFlowReturnToDefault => format!(
+            "Assert on the exact return value. A test that ignores it cannot tell `{}` from `{}`.",
+            original, mutated
+        ),
 
         // Collection
-        MutationOperator::Collection
-        | MutationOperator::CollectionEmptyCheck
+        MutationOperator::Collection if mutated.is_empty() => format!(
+            "Assert on the collection's exact contents. A test that only checks some elements \
+            cannot tell whether `{}` is there.",
+            original.trim_matches(|c: char| c == ',' || c.is_whitespace())
+        ),
+        MutationOperator::Collection if original == "...?" => {
+            "Test with the spread collection set to null. A test that always passes a \
+            collection cannot tell `...?` from `...`, which throws on null."
+                .to_string()
+        }
+        MutationOperator::Collection => format!(
+            "Test the collection both with and without the optional elements. A test that \
+            ignores them cannot tell `{}` from `{}`.",
+            original, mutated
+        ),
+        MutationOperator::CollectionEmptyCheck
         | MutationOperator::CollectionNotEmptyCheck => {
             "Test with empty collection AND non-empty collection. \
             Verify isEmpty/isNotEmpty checks affect behavior."
@@ -769,3 +1780,270 @@ fn generate_test_hint(
         ),
     }
 }
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::mutation::testing::mutant;
+    use crate::mutation::MutationOperator;
+
+    #[test]
+    fn test_file_breakdown_sorted_by_score_with_worst_operator() {
+        let result = |file: &str, operator, status| {
+            mutant()
+                .file(file)
+                .operator(operator)
+                .status(status)
+                .result()
+        };
+        let results = [
+            result(
+                "lib/a.dart",
+                MutationOperator::ArithmeticAddToSub,
+                MutantStatus::Killed,
+            ),
+            // A compile error counts against neither the file nor the run
+            result(
+                "lib/a.dart",
+                MutationOperator::ArithmeticAddToSub,
+                MutantStatus::Error,
+            ),
+            result(
+                "lib/b.dart",
+                MutationOperator::ArithmeticAddToSub,
+                MutantStatus::Survived,
+            ),
+            result(
+                "lib/b.dart",
+                MutationOperator::ComparisonLtToGt,
+                MutantStatus::Survived,
+            ),
+            result(
+                "lib/b.dart",
+                MutationOperator::ComparisonLtToGt,
+                MutantStatus::Survived,
+            ),
+            result(
+                "lib/b.dart",
+                MutationOperator::ComparisonLtToGt,
+                MutantStatus::Killed,
+            ),
+        ];
+
+        let table = format_file_breakdown(&results, &ReportOptions::default());
+        let lines: Vec<&str> = table.lines().collect();
+
+        assert_eq!(lines.len(), 3);
+        assert!(lines[1].starts_with("  lib/b.dart"));
+        assert!(lines[1].contains("1/4"));
+        assert!(lines[1].contains("25.0%"));
+        assert!(lines[1].ends_with(&format!(
+            "{} (2)",
+            MutationOperator::ComparisonLtToGt.name()
+        )));
+        assert!(lines[2].contains("1/2"));
+        assert!(lines[2].contains("100.0%"));
+        assert!(lines[2].ends_with('-'));
+    }
+
+    #[test]
+    fn test_timing_breakdown_lists_slowest_mutants_and_time_per_file() {
+        let result = |file: &str, line, millis| {
+            mutant()
+                .file(file)
+                .line(line)
+                .duration(Duration::from_millis(millis))
+                .result()
+        };
+        let mut results: Vec<MutantTestResult> = (1..=12)
+            .map(|line| result("lib/a.dart", line, 100))
+            .collect();
+        results.push(result("lib/b.dart", 7, 2500));
+        // Cached or untested mutants took no test time
+        results.push(result("lib/c.dart", 1, 0));
+
+        let text = format_timing_breakdown(&results, &ReportOptions::default()).unwrap();
+        let lines: Vec<&str> = text.lines().collect();
+        assert!(lines[0].starts_with("  Slowest mutants"));
+        assert!(lines[1].starts_with("  lib/b.dart:7") && lines[1].contains("2.5s"));
+        // The header and the ten slowest, then a blank line and the files
+        assert_eq!(lines[11], "");
+        assert!(lines[13].starts_with("  lib/b.dart") && lines[13].ends_with("  1"));
+        assert!(lines[14].starts_with("  lib/a.dart") && lines[14].contains("1.2s"));
+        assert_eq!(lines.len(), 15);
+
+        let html = generate_html_content(
+            &MutationResult::default(),
+            &compute_file_stats(&results, &ReportOptions::default()),
+            3,
+            true,
+            Thresholds::default(),
+        );
+        assert!(html.contains("<td>lib/b.dart:7</td>"));
+        assert!(!html.contains("lib/c.dart:1"));
+
+        assert!(format_timing_breakdown(&results[13..], &ReportOptions::default()).is_none());
+    }
+
+    #[test]
+    fn test_badge_color_follows_the_thresholds() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("badge.json");
+        let result = MutationResult {
+            mutation_score: 85.0,
+            ..MutationResult::default()
+        };
+        let badge = |high, low| {
+            let options = ReportOptions {
+                thresholds: Thresholds { high, low },
+                ..ReportOptions::default()
+            };
+            generate_badge(&result, &path, &options).unwrap();
+            let badge: serde_json::Value =
+                serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+            badge["color"].as_str().unwrap().to_owned()
+        };
+
+        assert_eq!(badge(80, 60), "green");
+        assert_eq!(badge(90, 80), "yellow");
+        assert_eq!(badge(95, 90), "red");
+    }
+
+    #[test]
+    fn test_cobertura_maps_files_to_classes_and_mutant_lines() {
+        let result =
+            |file: &str, line, status| mutant().file(file).line(line).status(status).result();
+        let results = [
+            result("lib/src/calc.dart", 3, MutantStatus::Killed),
+            result("lib/src/calc.dart", 3, MutantStatus::Survived),
+            result("lib/src/calc.dart", 7, MutantStatus::Timeout),
+            result("lib/src/calc.dart", 9, MutantStatus::Killed),
+            result("lib/src/calc.dart", 12, MutantStatus::Error),
+            result("lib/app.dart", 1, MutantStatus::Survived),
+        ];
+        let result = MutationResult::from_results(&results, TimeoutPolicy::Killed);
+
+        let xml = render_cobertura(&result, &results, &ReportOptions::default());
+
+        assert!(xml.contains(
+            r#"<coverage line-rate="0.6000" branch-rate="0" lines-covered="2" lines-valid="4""#
+        ));
+        assert!(xml.contains(r#"<package name="lib.src" line-rate="0.7500""#));
+        assert!(xml.contains(
+            r#"<class name="calc.dart" filename="lib/src/calc.dart" line-rate="0.7500""#
+        ));
+        assert!(xml.contains(r#"<line number="3" hits="0"/>"#));
+        assert!(xml.contains(r#"<line number="7" hits="1"/>"#));
+        assert!(xml.contains(r#"<line number="9" hits="1"/>"#));
+        assert!(!xml.contains(r#"<line number="12""#));
+        assert!(
+            xml.contains(r#"<class name="app.dart" filename="lib/app.dart" line-rate="0.0000""#)
+        );
+    }
+
+    #[test]
+    fn test_offline_html_makes_no_external_font_requests() {
+        let result = MutationResult::default();
+
+        let online = generate_html_content(&result, &[], 0, false, Thresholds::default());
+        assert!(online.contains("fonts.googleapis.com"));
+
+        let offline = generate_html_content(&result, &[], 0, true, Thresholds::default());
+        assert!(!offline.contains("fonts.googleapis.com"));
+        assert!(!offline.contains("fonts.gstatic.com"));
+        assert!(offline.contains("--font-display: var(--font-sans);"));
+    }
+
+    #[test]
+    fn test_mutant_snippet_highlights_token_and_checks_offsets() {
+        let source = "int f(int a, int b) {\n  return a < b;\n}\n";
+        let mutation = mutant()
+            .change("<", "<=")
+            .operator(MutationOperator::ComparisonLtToLte)
+            .found_in(source)
+            .mutation();
+
+        let html = generate_mutant_snippet(Some(source), &mutation);
+        assert!(html.contains(
+            r#"<span class="line-number">2</span><span class="line-code">  return a <mark class="mutant-token">&lt;</mark> b;</span>"#
+        ));
+        assert!(html.contains(r#"<span class="line-number">1</span>"#));
+        assert!(html.contains(r#"<span class="line-number">3</span>"#));
+
+        // The file changed since the run, so the offsets no longer match
+        let edited = source.replace("a < b", "a<b");
+        for stale in [Some(edited.as_str()), Some(""), None] {
+            assert!(generate_mutant_snippet(stale, &mutation).contains("Source unavailable"));
+        }
+    }
+
+    #[test]
+    fn test_unified_diff_shows_mutated_lines_with_context() {
+        let source =
+            "int f(int a, int b) {\n  var c = 1;\n  var d = 2;\n  var e = 3;\n  return a + b;\n}\n";
+        let mutation = mutant().found_in(source).mutation();
+
+        let diff = unified_diff("lib/calc.dart", source, &mutation).unwrap();
+
+        let expected = [
+            "--- a/lib/calc.dart",
+            "+++ b/lib/calc.dart",
+            "@@ -2,5 +2,5 @@",
+            "   var c = 1;",
+            "   var d = 2;",
+            "   var e = 3;",
+            "-  return a + b;",
+            "+  return a - b;",
+            " }",
+        ];
+        assert_eq!(diff.lines().collect::<Vec<_>>(), expected);
+
+        // Line numbers come from the source, not the recorded location
+        let mut stale = mutation.clone();
+        stale.location.start_line = 1;
+        let diff = unified_diff("lib/calc.dart", source, &stale).unwrap();
+        assert!(diff.contains("@@ -2,5 +2,5 @@"), "{diff}");
+
+        let edited = source.replace("a + b", "a+b");
+        assert_eq!(unified_diff("lib/calc.dart", &edited, &mutation), None);
+    }
+
+    #[test]
+    fn test_ai_report_ranks_tests_by_mutants_killed() {
+        let result = |line, status, killed_by: Option<&str>| {
+            let mut mutant = mutant().line(line).status(status);
+            if let Some(test) = killed_by {
+                mutant = mutant.killed_by(test);
+            }
+            mutant.result()
+        };
+        let results = [
+            result(1, MutantStatus::Killed, Some("Calculator subtract")),
+            result(2, MutantStatus::Killed, Some("Calculator add")),
+            result(3, MutantStatus::Killed, Some("Calculator add")),
+            result(4, MutantStatus::Killed, None),
+            result(5, MutantStatus::Survived, None),
+        ];
+        let result = MutationResult::from_results(&results, TimeoutPolicy::Killed);
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("report.md");
+
+        generate_ai_report(&result, &results, &path, &ReportOptions::default()).unwrap();
+
+        let report = std::fs::read_to_string(path).unwrap();
+        let section = report.split("## Most Effective Tests\n\n").nth(1).unwrap();
+        let ranked: Vec<&str> = section
+            .lines()
+            .filter(|line| line.starts_with("- "))
+            .take_while(|line| line.contains("killed"))
+            .collect();
+        assert_eq!(
+            ranked,
+            [
+                "- `Calculator add`: killed 2",
+                "- `Calculator subtract`: killed 1"
+            ]
+        );
+    }
+}
@@ -0,0 +1,136 @@
+//! Newline-delimited JSON results, written while the run is in progress
+//!
+//! Each line is a Stryker-style mutant object with its `file` and
+//! `durationMs` added. Lines are flushed as soon as they are written, so the
+//! file can be tailed, piped into another tool, or read back after a killed
+//! run; at worst it loses the mutant that was being written.
+
+use super::{JsonMutant, ReportOptions};
+use crate::progress::{Phase, ProgressSink};
+use crate::runner::{MutantTestResult, Mutation};
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::fs::File;
+use std::io::{LineWriter, Write};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+#[derive(Serialize)]
+struct NdjsonRecord {
+    file: String,
+    #[serde(flatten)]
+    mutant: JsonMutant,
+    #[serde(rename = "durationMs")]
+    duration_ms: u128,
+}
+
+/// Writes each mutant's result as it finishes, then passes every event on
+/// to the wrapped sink
+pub struct NdjsonStream {
+    writer: Mutex<LineWriter<File>>,
+    options: ReportOptions,
+    inner: Arc<dyn ProgressSink>,
+}
+
+impl NdjsonStream {
+    /// Create (or truncate) `path`
+    pub fn create(
+        path: &Path,
+        options: ReportOptions,
+        inner: Arc<dyn ProgressSink>,
+    ) -> Result<Self> {
+        let file = File::create(path)
+            .with_context(|| format!("Failed to create NDJSON file {}", path.display()))?;
+        Ok(Self {
+            writer: Mutex::new(LineWriter::new(file)),
+            options,
+            inner,
+        })
+    }
+
+    fn write(&self, result: &MutantTestResult) -> Result<()> {
+        let record = NdjsonRecord {
+            file: self.options.display_path(&result.mutation.location.file),
+            mutant: JsonMutant::new(result, None),
+            duration_ms: result.duration.as_millis(),
+        };
+        let mut line = serde_json::to_string(&record)?;
+        line.push('\n');
+
+        let mut writer = self
+            .writer
+            .lock()
+            .map_err(|_| anyhow::anyhow!("NDJSON writer poisoned"))?;
+        writer.write_all(line.as_bytes())?;
+        writer.flush()?;
+        Ok(())
+    }
+}
+
+impl ProgressSink for NdjsonStream {
+    fn on_phase_change(&self, phase: Phase) {
+        self.inner.on_phase_change(phase);
+    }
+
+    fn on_mutation_started(&self, mutation: &Mutation) {
+        self.inner.on_mutation_started(mutation);
+    }
+
+    fn on_mutation_finished(&self, result: &MutantTestResult) {
+        if let Err(e) = self.write(result) {
+            tracing::warn!("Failed to write NDJSON result: {e}");
+        }
+        self.inner.on_mutation_finished(result);
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::mutation::testing::mutant;
+    use crate::mutation::MutantStatus;
+    use crate::progress::NoProgress;
+    use std::path::PathBuf;
+    use std::time::Duration;
+
+    fn result(line: usize, status: MutantStatus) -> MutantTestResult {
+        mutant()
+            .file("/project/lib/calc.dart")
+            .line(line)
+            .column(5)
+            .status(status)
+            .duration(Duration::from_millis(1500))
+            .result()
+    }
+
+    #[test]
+    fn test_writes_one_line_per_finished_mutant() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("results.ndjson");
+        let options = ReportOptions {
+            path_root: Some(PathBuf::from("/project")),
+            ..ReportOptions::default()
+        };
+        let stream = NdjsonStream::create(&path, options, Arc::new(NoProgress)).unwrap();
+
+        stream.on_mutation_finished(&result(3, MutantStatus::Killed));
+        // Readable before the run ends, as after a crash
+        let partial = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(partial.lines().count(), 1);
+
+        stream.on_mutation_finished(&result(8, MutantStatus::Survived));
+        let lines: Vec<serde_json::Value> = std::fs::read_to_string(&path)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0]["file"], "lib/calc.dart");
+        assert_eq!(lines[0]["status"], "Killed");
+        assert_eq!(lines[0]["durationMs"], 1500);
+        assert_eq!(lines[1]["status"], "Survived");
+        assert_eq!(lines[1]["location"]["start"]["line"], 8);
+    }
+}
@@ -0,0 +1,149 @@
+//! SARIF 2.1.0 report
+//!
+//! Each surviving mutant is a `warning` result whose rule is the mutation
+//! operator, so GitHub code scanning shows survivors as annotations on the
+//! lines they mutate.
+
+use super::ReportOptions;
+use crate::mutation::MutantStatus;
+use crate::runner::MutantTestResult;
+use anyhow::{Context, Result};
+use serde_json::{json, Value};
+use std::collections::BTreeSet;
+use std::path::Path;
+
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+
+/// Generate a SARIF report of the surviving mutants
+pub fn generate_sarif_report(
+    test_results: &[MutantTestResult],
+    output_path: &Path,
+    options: &ReportOptions,
+) -> Result<()> {
+    let sarif = render_sarif(test_results, options);
+    let json = serde_json::to_string_pretty(&sarif)?;
+    std::fs::write(output_path, json).context("Failed to write SARIF report")?;
+    Ok(())
+}
+
+fn render_sarif(test_results: &[MutantTestResult], options: &ReportOptions) -> Value {
+    let survivors: Vec<&MutantTestResult> = test_results
+        .iter()
+        .filter(|r| r.status == MutantStatus::Survived)
+        .collect();
+
+    let rule_ids: BTreeSet<&str> = survivors
+        .iter()
+        .map(|r| r.mutation.operator.name())
+        .collect();
+    let rules: Vec<Value> = rule_ids
+        .iter()
+        .map(|id| {
+            json!({
+                "id": id,
+                "shortDescription": { "text": format!("Surviving mutant: {id}") },
+            })
+        })
+        .collect();
+
+    let results: Vec<Value> = survivors
+        .iter()
+        .map(|r| {
+            let m = &r.mutation;
+            // SARIF URIs always use forward slashes
+            let uri = options.display_path(&m.location.file).replace('\\', "/");
+            json!({
+                "ruleId": m.operator.name(),
+                "level": "warning",
+                "message": {
+                    "text": format!(
+                        "Mutant survived: `{}` → `{}` was not detected by any test",
+                        m.original, m.mutated
+                    ),
+                },
+                "locations": [{
+                    "physicalLocation": {
+                        "artifactLocation": { "uri": uri },
+                        "region": {
+                            "startLine": m.location.start_line,
+                            "startColumn": m.location.start_col,
+                            "endLine": m.location.end_line,
+                            "endColumn": m.location.end_col,
+                        },
+                    },
+                }],
+                "partialFingerprints": { "mutantId": m.id },
+            })
+        })
+        .collect();
+
+    json!({
+        "$schema": SARIF_SCHEMA,
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "dart_mutant",
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "informationUri": env!("CARGO_PKG_REPOSITORY"),
+                    "rules": rules,
+                },
+            },
+            "results": results,
+        }],
+    })
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::mutation::testing::mutant;
+    use crate::mutation::MutationOperator;
+    use std::path::PathBuf;
+
+    fn result(line: usize, status: MutantStatus) -> MutantTestResult {
+        mutant()
+            .file("/project/lib/calc.dart")
+            .line(line)
+            .column(5)
+            .change(">", ">=")
+            .operator(MutationOperator::ComparisonGtToGte)
+            .status(status)
+            .result()
+    }
+
+    #[test]
+    fn test_only_survivors_become_warnings() {
+        let results = vec![
+            result(12, MutantStatus::Survived),
+            result(5, MutantStatus::Killed),
+            result(7, MutantStatus::Error),
+        ];
+        let options = ReportOptions {
+            path_root: Some(PathBuf::from("/project")),
+            ..ReportOptions::default()
+        };
+
+        let sarif = render_sarif(&results, &options);
+
+        assert_eq!(sarif["version"], "2.1.0");
+        let run = &sarif["runs"][0];
+        assert_eq!(run["tool"]["driver"]["rules"].as_array().unwrap().len(), 1);
+        let results = run["results"].as_array().unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(
+            results[0]["ruleId"],
+            MutationOperator::ComparisonGtToGte.name()
+        );
+        assert_eq!(results[0]["level"], "warning");
+        let location = &results[0]["locations"][0]["physicalLocation"];
+        assert_eq!(location["artifactLocation"]["uri"], "lib/calc.dart");
+        assert_eq!(location["region"]["startLine"], 12);
+        assert_eq!(location["region"]["startColumn"], 5);
+        assert!(results[0]["message"]["text"]
+            .as_str()
+            .unwrap()
+            .contains("`>` → `>=`"));
+    }
+}
@@ -0,0 +1,212 @@
+//! Survivors of a previous run, for `--only-survivors`
+//!
+//! Reads a Stryker-compatible JSON report and keeps the mutants it marks
+//! `Survived`. Mutants of this run are matched to them by id, which holds
+//! while a file is unchanged. A survivor whose id no longer exists, because
+//! its file was edited since, is matched by file, mutator and start line.
+
+use super::JsonReport;
+use crate::mutation::Mutation;
+use anyhow::{Context, Result};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone)]
+struct Survivor {
+    id: String,
+    /// Relative to the project when the report was written with
+    /// `--relative-paths` or from the same `--path`
+    file: PathBuf,
+    mutator: String,
+    line: usize,
+}
+
+/// The surviving mutants of a previous report
+#[derive(Debug, Clone)]
+pub struct Survivors {
+    survivors: Vec<Survivor>,
+    root: PathBuf,
+}
+
+impl Survivors {
+    /// Read the survivors from the JSON report at `path`, for a run against
+    /// `project`
+    pub fn load(path: &Path, project: &Path) -> Result<Self> {
+        let json = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read report: {}", path.display()))?;
+        let report: JsonReport = serde_json::from_str(&json)
+            .with_context(|| format!("Failed to parse report: {}", path.display()))?;
+
+        let mut survivors: Vec<Survivor> = report
+            .files
+            .into_iter()
+            .flat_map(|(file, json_file)| {
+                let file = relative_to(project, Path::new(&file)).to_path_buf();
+                json_file
+                    .mutants
+                    .into_iter()
+                    .filter(|m| m.status == "Survived")
+                    .map(move |m| Survivor {
+                        id: m.id,
+                        file: file.clone(),
+                        mutator: m.mutator_name,
+                        line: m.location.start.line,
+                    })
+            })
+            .collect();
+        survivors.sort_by(|a, b| (&a.file, a.line).cmp(&(&b.file, b.line)));
+
+        Ok(Self {
+            survivors,
+            root: project.to_path_buf(),
+        })
+    }
+
+    /// Number of surviving mutants in the report
+    pub fn len(&self) -> usize {
+        self.survivors.len()
+    }
+
+    /// Whether the report has no surviving mutants
+    pub fn is_empty(&self) -> bool {
+        self.survivors.is_empty()
+    }
+
+    /// Whether any survivor is in `file`
+    pub fn touches_file(&self, file: &Path) -> bool {
+        let file = relative_to(&self.root, file);
+        self.survivors.iter().any(|s| s.file == file)
+    }
+
+    /// Keep only the mutations that match a survivor. Returns the number of
+    /// survivors nothing matched, whose code was removed or moved
+    pub fn retain(&self, mutations: &mut Vec<Mutation>) -> usize {
+        let ids: HashSet<&str> = mutations.iter().map(|m| m.id.as_str()).collect();
+        let (by_id, moved): (Vec<&Survivor>, Vec<&Survivor>) = self
+            .survivors
+            .iter()
+            .partition(|s| ids.contains(s.id.as_str()));
+        let by_id: HashSet<String> = by_id.iter().map(|s| s.id.clone()).collect();
+        let moved: HashSet<(&Path, &str, usize)> = moved
+            .iter()
+            .map(|s| (s.file.as_path(), s.mutator.as_str(), s.line))
+            .collect();
+
+        let mut found = HashSet::new();
+        mutations.retain(|m| {
+            if by_id.contains(&m.id) {
+                return true;
+            }
+            let key = (
+                relative_to(&self.root, &m.location.file),
+                m.operator.name(),
+                m.location.start_line,
+            );
+            let matched = moved.contains(&key);
+            if matched {
+                found.insert((key.0.to_path_buf(), key.1, key.2));
+            }
+            matched
+        });
+
+        moved.len() - found.len()
+    }
+}
+
+fn relative_to<'a>(root: &Path, file: &'a Path) -> &'a Path {
+    file.strip_prefix(root).unwrap_or(file)
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::mutation::testing::mutant;
+
+    fn mutation(line: usize, original: &str, mutated: &str) -> Mutation {
+        mutant()
+            .file("/project/lib/calc.dart")
+            .line(line)
+            .column(5)
+            .change(original, mutated)
+            .mutation()
+    }
+
+    #[test]
+    fn test_matches_survivors_by_id_then_by_line() {
+        let unchanged = mutation(3, "+", "-");
+        let edited = mutation(8, "+", "-");
+        let report = serde_json::json!({
+            "schemaVersion": "1",
+            "thresholds": { "high": 80, "low": 60 },
+            "files": {
+                "lib/calc.dart": {
+                    "language": "dart",
+                    "mutants": [
+                        {
+                            "id": unchanged.id,
+                            "mutatorName": "Arithmetic: + → -",
+                            "replacement": "-",
+                            "status": "Survived",
+                            "location": {
+                                "start": { "line": 3, "column": 5 },
+                                "end": { "line": 3, "column": 6 }
+                            }
+                        },
+                        {
+                            "id": "edited-since",
+                            "mutatorName": "Arithmetic: + → -",
+                            "replacement": "-",
+                            "status": "Survived",
+                            "location": {
+                                "start": { "line": 8, "column": 9 },
+                                "end": { "line": 8, "column": 10 }
+                            }
+                        },
+                        {
+                            "id": "removed-since",
+                            "mutatorName": "Arithmetic: + → -",
+                            "replacement": "-",
+                            "status": "Survived",
+                            "location": {
+                                "start": { "line": 20, "column": 5 },
+                                "end": { "line": 20, "column": 6 }
+                            }
+                        },
+                        {
+                            "id": "killed",
+                            "mutatorName": "Arithmetic: + → -",
+                            "replacement": "-",
+                            "status": "Killed",
+                            "location": {
+                                "start": { "line": 12, "column": 5 },
+                                "end": { "line": 12, "column": 6 }
+                            }
+                        }
+                    ]
+                }
+            }
+        });
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("report.json");
+        std::fs::write(&path, report.to_string()).unwrap();
+
+        let survivors = Survivors::load(&path, Path::new("/project")).unwrap();
+        assert_eq!(survivors.len(), 3);
+        assert!(survivors.touches_file(Path::new("/project/lib/calc.dart")));
+        assert!(!survivors.touches_file(Path::new("/project/lib/other.dart")));
+
+        let mut mutations = vec![
+            unchanged,
+            edited.clone(),
+            mutation(12, "+", "-"),
+            mutation(15, "+", "-"),
+        ];
+        let missing = survivors.retain(&mut mutations);
+
+        let lines: Vec<usize> = mutations.iter().map(|m| m.location.start_line).collect();
+        assert_eq!(lines, [3, 8]);
+        assert_eq!(mutations[1].id, edited.id);
+        assert_eq!(missing, 1);
+    }
+}
@@ -0,0 +1,198 @@
+//! Per-file and per-category mutation score thresholds
+//!
+//! File thresholds are loaded from a JSON object mapping globs to minimum
+//! scores, e.g. `{ "lib/payments/**": 90, "lib/ui/**": 50 }`. The first glob
+//! matching a file (in file order) sets its threshold.
+
+use super::MutationResult;
+use crate::cli::TimeoutPolicy;
+use crate::mutators::MutatorCategory;
+use crate::runner::MutantTestResult;
+use anyhow::{Context, Result};
+use serde::de::{Deserializer, MapAccess, Visitor};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::Path;
+
+/// Ordered (glob, minimum score) pairs
+#[derive(Debug, Clone)]
+pub struct FileThresholds {
+    entries: Vec<(glob::Pattern, f64)>,
+}
+
+/// A file whose mutation score is below its configured threshold
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileThresholdViolation {
+    /// File path as shown in reports
+    pub file: String,
+    /// `--file-threshold` glob that matched the file
+    pub pattern: String,
+    /// The file's mutation score
+    pub score: f64,
+    /// Minimum score the pattern requires
+    pub threshold: f64,
+}
+
+impl FileThresholds {
+    /// Load thresholds from a JSON file
+    pub fn load(path: &Path) -> Result<Self> {
+        let json = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read file thresholds: {}", path.display()))?;
+        Self::parse(&json)
+            .with_context(|| format!("Failed to parse file thresholds: {}", path.display()))
+    }
+
+    fn parse(json: &str) -> Result<Self> {
+        let OrderedEntries(raw) = serde_json::from_str(json)?;
+        let entries = raw
+            .into_iter()
+            .map(|(pattern, threshold)| {
+                glob::Pattern::new(&pattern)
+                    .map(|p| (p, threshold))
+                    .with_context(|| format!("Invalid glob pattern: {pattern}"))
+            })
+            .collect::<Result<_>>()?;
+        Ok(Self { entries })
+    }
+
+    /// First configured (pattern, threshold) matching a project-relative path
+    fn threshold_for(&self, relative_path: &str) -> Option<&(glob::Pattern, f64)> {
+        self.entries
+            .iter()
+            .find(|(pattern, _)| pattern.matches(relative_path))
+    }
+
+    /// Check each file's score against its threshold. Paths are matched
+    /// relative to `root`.
+    pub fn violations(
+        &self,
+        results: &[MutantTestResult],
+        root: &Path,
+        timeout_policy: TimeoutPolicy,
+    ) -> Vec<FileThresholdViolation> {
+        let mut by_file: BTreeMap<&Path, Vec<MutantTestResult>> = BTreeMap::new();
+        for r in results {
+            by_file
+                .entry(r.mutation.location.file.as_path())
+                .or_default()
+                .push(r.clone());
+        }
+
+        by_file
+            .into_iter()
+            .filter_map(|(file, file_results)| {
+                let relative = file.strip_prefix(root).unwrap_or(file);
+                let relative = relative.to_string_lossy();
+                let (pattern, threshold) = self.threshold_for(&relative)?;
+                let score =
+                    MutationResult::from_results(&file_results, timeout_policy).mutation_score;
+                (score < *threshold).then(|| FileThresholdViolation {
+                    file: relative.into_owned(),
+                    pattern: pattern.as_str().to_owned(),
+                    score,
+                    threshold: *threshold,
+                })
+            })
+            .collect()
+    }
+}
+
+impl MutationResult {
+    /// Categories scoring below their `--category-threshold`, as
+    /// (category, score, threshold). Categories without scored mutants pass.
+    pub fn categories_below(
+        &self,
+        thresholds: &[(MutatorCategory, f64)],
+    ) -> Vec<(MutatorCategory, f64, f64)> {
+        thresholds
+            .iter()
+            .filter_map(|&(category, threshold)| {
+                let score = *self.category_scores.get(&category)?;
+                (score < threshold).then_some((category, score, threshold))
+            })
+            .collect()
+    }
+}
+
+/// JSON object entries in document order
+struct OrderedEntries(Vec<(String, f64)>);
+
+impl<'de> Deserialize<'de> for OrderedEntries {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct EntriesVisitor;
+
+        impl<'de> Visitor<'de> for EntriesVisitor {
+            type Value = OrderedEntries;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("an object mapping glob patterns to scores")
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+                let mut entries = Vec::new();
+                while let Some(entry) = map.next_entry::<String, f64>()? {
+                    entries.push(entry);
+                }
+                Ok(OrderedEntries(entries))
+            }
+        }
+
+        deserializer.deserialize_map(EntriesVisitor)
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::mutation::testing::mutant;
+    use crate::mutation::{MutantStatus, MutationOperator};
+
+    #[test]
+    fn test_first_matching_glob_wins() {
+        let thresholds = FileThresholds::parse(
+            r#"{ "lib/payments/legacy.dart": 40, "lib/payments/**": 90, "lib/ui/**": 50 }"#,
+        )
+        .unwrap();
+
+        let threshold = |path| thresholds.threshold_for(path).map(|(_, t)| *t);
+        assert_eq!(threshold("lib/payments/legacy.dart"), Some(40.0));
+        assert_eq!(threshold("lib/payments/card/charge.dart"), Some(90.0));
+        assert_eq!(threshold("lib/ui/button.dart"), Some(50.0));
+        assert_eq!(threshold("lib/core.dart"), None);
+    }
+
+    #[test]
+    fn test_category_thresholds_use_per_category_scores() {
+        let result = |operator, status| mutant().operator(operator).status(status).result();
+        let results = [
+            result(MutationOperator::ArithmeticAddToSub, MutantStatus::Killed),
+            result(MutationOperator::ArithmeticAddToSub, MutantStatus::Killed),
+            result(MutationOperator::NullAssertionRemoval, MutantStatus::Killed),
+            result(
+                MutationOperator::NullAssertionRemoval,
+                MutantStatus::Survived,
+            ),
+            result(MutationOperator::BooleanTrueToFalse, MutantStatus::Error),
+        ];
+        let result = MutationResult::from_results(&results, TimeoutPolicy::Killed);
+
+        assert_eq!(result.category_scores.len(), 2);
+        assert!((result.category_scores[&MutatorCategory::NullSafety] - 50.0).abs() < 1e-9);
+
+        let below = result.categories_below(&[
+            (MutatorCategory::Arithmetic, 80.0),
+            (MutatorCategory::NullSafety, 90.0),
+            (MutatorCategory::Boolean, 90.0),
+        ]);
+        assert_eq!(below.len(), 1);
+        assert_eq!(below[0].0, MutatorCategory::NullSafety);
+    }
+
+    #[test]
+    fn test_rejects_invalid_config() {
+        assert!(FileThresholds::parse(r#"{ "lib/[": 90 }"#).is_err());
+        assert!(FileThresholds::parse(r#"["lib/**"]"#).is_err());
+    }
+}
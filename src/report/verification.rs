@@ -0,0 +1,151 @@
+//! Markdown report of `--verify-mutations`
+//!
+//! Groups the mutants that don't compile by operator, worst first, with the
+//! share of each operator's mutants that broke, so an operator bug can be
+//! filed with its examples.
+
+use super::ReportOptions;
+use crate::mutation::Mutation;
+use crate::runner::InvalidMutation;
+use anyhow::{Context, Result};
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::path::Path;
+
+/// Invalid mutants of one operator
+#[derive(Debug, Clone)]
+pub struct OperatorVerification<'a> {
+    /// Display name of the operator
+    pub operator: &'static str,
+    /// Mutants of the operator that were checked
+    pub checked: usize,
+    /// Those that don't compile
+    pub invalid: Vec<&'a InvalidMutation>,
+}
+
+/// Operators with invalid mutants, most invalid first
+pub fn verification_by_operator<'a>(
+    checked: &[Mutation],
+    invalid: &'a [InvalidMutation],
+) -> Vec<OperatorVerification<'a>> {
+    let mut counts: HashMap<&'static str, usize> = HashMap::new();
+    for mutation in checked {
+        *counts.entry(mutation.operator.name()).or_default() += 1;
+    }
+    let mut by_operator: HashMap<&'static str, Vec<&InvalidMutation>> = HashMap::new();
+    for entry in invalid {
+        by_operator
+            .entry(entry.mutation.operator.name())
+            .or_default()
+            .push(entry);
+    }
+
+    let mut rows: Vec<OperatorVerification<'a>> = by_operator
+        .into_iter()
+        .map(|(operator, invalid)| OperatorVerification {
+            operator,
+            checked: counts.get(operator).copied().unwrap_or(invalid.len()),
+            invalid,
+        })
+        .collect();
+    rows.sort_by(|a, b| {
+        b.invalid
+            .len()
+            .cmp(&a.invalid.len())
+            .then(a.operator.cmp(b.operator))
+    });
+    rows
+}
+
+/// Generate the Markdown verification report
+pub fn generate_verification_report(
+    checked: &[Mutation],
+    invalid: &[InvalidMutation],
+    output_path: &Path,
+    options: &ReportOptions,
+) -> Result<()> {
+    let mut report = String::from("# Mutation Verification Report\n\n");
+    let _ = writeln!(
+        report,
+        "{} of {} mutants don't compile.\n",
+        invalid.len(),
+        checked.len()
+    );
+
+    for row in verification_by_operator(checked, invalid) {
+        let _ = writeln!(
+            report,
+            "## {} ({} of {})\n",
+            row.operator,
+            row.invalid.len(),
+            row.checked
+        );
+        for entry in row.invalid {
+            let mutation = &entry.mutation;
+            let _ = writeln!(
+                report,
+                "- `{}:{}` `{}` → `{}`: {}",
+                options.display_path(&mutation.location.file),
+                mutation.location.start_line,
+                mutation.original.replace('`', "'"),
+                mutation.mutated.replace('`', "'"),
+                entry.error
+            );
+        }
+        report.push('\n');
+    }
+
+    std::fs::write(output_path, report).context("Failed to write verification report")?;
+    Ok(())
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::mutation::testing::mutant;
+    use crate::mutation::MutationOperator;
+    use std::path::PathBuf;
+
+    fn mutation(line: usize, operator: MutationOperator) -> Mutation {
+        mutant()
+            .file("/project/lib/calc.dart")
+            .at(line)
+            .line(line)
+            .operator(operator)
+            .mutation()
+    }
+
+    #[test]
+    fn test_groups_invalid_mutants_by_operator() {
+        let checked = vec![
+            mutation(1, MutationOperator::ArithmeticAddToSub),
+            mutation(2, MutationOperator::ArithmeticAddToSub),
+            mutation(3, MutationOperator::ArithmeticMulToDiv),
+        ];
+        let invalid = vec![InvalidMutation {
+            mutation: checked[1].clone(),
+            error: "2:5: syntax error".to_owned(),
+        }];
+
+        let rows = verification_by_operator(&checked, &invalid);
+        assert_eq!(rows.len(), 1);
+        assert_eq!(
+            rows[0].operator,
+            MutationOperator::ArithmeticAddToSub.name()
+        );
+        assert_eq!((rows[0].invalid.len(), rows[0].checked), (1, 2));
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("mutation-verification.md");
+        let options = ReportOptions {
+            path_root: Some(PathBuf::from("/project")),
+            ..ReportOptions::default()
+        };
+        generate_verification_report(&checked, &invalid, &path, &options).unwrap();
+        let report = std::fs::read_to_string(path).unwrap();
+        assert!(report.contains("1 of 3 mutants don't compile."));
+        assert!(report.contains("(1 of 2)"));
+        assert!(report.contains("- `lib/calc.dart:2` `+` → `-`: 2:5: syntax error"));
+    }
+}
@@ -2,9 +2,16 @@
 //!
 //! This module handles running tests against mutated code and collecting results.
 
+mod reuse_vm;
+mod sandbox;
+mod verify;
+
 pub use crate::mutation::{MutantStatus, Mutation};
+use crate::parser::library;
+use crate::progress::ProgressSink;
 use anyhow::{Context, Result};
-use indicatif::ProgressBar;
+use reuse_vm::{VmLease, VmPool};
+use sandbox::{SandboxLease, SandboxPool};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
@@ -15,6 +22,7 @@ use std::time::{Duration, Instant};
 use tokio::process::Command;
 use tokio::sync::{Mutex, Semaphore};
 use tokio::time::timeout;
+pub use verify::{verify_mutations, InvalidMutation};
 
 /// RAII guard that restores a file to its original content on drop
 struct FileRestoreGuard {
@@ -22,26 +30,160 @@ struct FileRestoreGuard {
     original_content: String,
 }
 
+/// Attempts to restore a file before giving up and writing a sidecar copy
+const RESTORE_ATTEMPTS: u32 = 4;
+
 impl Drop for FileRestoreGuard {
     fn drop(&mut self) {
-        if let Err(e) = std::fs::write(&self.path, &self.original_content) {
+        // Retry with backoff to ride out transient locks (antivirus, NFS)
+        let mut delay = Duration::from_millis(25);
+        let mut last_error = None;
+        for attempt in 1..=RESTORE_ATTEMPTS {
+            match std::fs::write(&self.path, &self.original_content) {
+                Ok(()) => return,
+                Err(e) => last_error = Some(e),
+            }
+            if attempt < RESTORE_ATTEMPTS {
+                std::thread::sleep(delay);
+                delay *= 2;
+            }
+        }
+
+        // Last resort: keep the original next to the file so it can be recovered
+        let mut sidecar = self.path.clone().into_os_string();
+        sidecar.push(".dart_mutant.orig");
+        let sidecar = PathBuf::from(sidecar);
+        let saved = std::fs::write(&sidecar, &self.original_content).is_ok();
+
+        eprintln!(
+            "\nERROR: Failed to restore {} after {} attempts: {}",
+            self.path.display(),
+            RESTORE_ATTEMPTS,
+            last_error.map_or_else(String::new, |e| e.to_string())
+        );
+        if saved {
             eprintln!(
-                "Warning: Failed to restore file {}: {}",
-                self.path.display(),
-                e
+                "ERROR: The file may still be MUTATED. The original was saved to {}; \
+                 copy it back over {} to recover.\n",
+                sidecar.display(),
+                self.path.display()
             );
+        } else {
+            eprintln!(
+                "ERROR: The file may still be MUTATED and the original could not be saved \
+                 to {}. Restore it from version control.\n",
+                sidecar.display()
+            );
+        }
+    }
+}
+
+/// Which failing tests count as killing a mutant. With no patterns every
+/// failure counts, matching plain `dart test` exit-code semantics.
+#[derive(Debug, Clone, Default)]
+pub struct KillFilter {
+    killing: Vec<glob::Pattern>,
+    non_killing: Vec<glob::Pattern>,
+}
+
+impl KillFilter {
+    pub fn new(killing: &[String], non_killing: &[String]) -> Result<Self> {
+        let compile = |patterns: &[String]| -> Result<Vec<glob::Pattern>> {
+            patterns
+                .iter()
+                .map(|p| {
+                    glob::Pattern::new(p).with_context(|| format!("Invalid test name pattern: {p}"))
+                })
+                .collect()
+        };
+        Ok(Self {
+            killing: compile(killing)?,
+            non_killing: compile(non_killing)?,
+        })
+    }
+
+    /// Whether test names need to be inspected at all
+    pub fn is_active(&self) -> bool {
+        !self.killing.is_empty() || !self.non_killing.is_empty()
+    }
+
+    fn counts(&self, test_name: &str) -> bool {
+        (self.killing.is_empty() || self.killing.iter().any(|p| p.matches(test_name)))
+            && !self.non_killing.iter().any(|p| p.matches(test_name))
+    }
+
+    /// Decide the status of a failing run from `dart test --reporter=json`
+    /// output. Failures that can't be attributed to a test (e.g. a load
+    /// error) still kill.
+    fn status_for_failure(&self, json_output: &str) -> MutantStatus {
+        let failed = failed_test_names(json_output);
+        if failed.is_empty() || failed.iter().any(|name| self.counts(name)) {
+            MutantStatus::Killed
+        } else {
+            MutantStatus::Survived
+        }
+    }
+
+    /// The first failing test that counts, reported as the mutant's killer
+    fn killing_test(&self, json_output: &str) -> Option<String> {
+        failed_test_names(json_output)
+            .into_iter()
+            .find(|name| self.counts(name))
+    }
+}
+
+/// Names of visible tests that failed, from `dart test --reporter=json` events
+fn failed_test_names(json_output: &str) -> Vec<String> {
+    let mut names: HashMap<u64, String> = HashMap::new();
+    let mut failed = Vec::new();
+
+    for event in json_output
+        .lines()
+        .filter_map(|line| serde_json::from_str::<serde_json::Value>(line).ok())
+    {
+        match event["type"].as_str() {
+            Some("testStart") => {
+                if let (Some(id), Some(name)) =
+                    (event["test"]["id"].as_u64(), event["test"]["name"].as_str())
+                {
+                    names.insert(id, name.to_owned());
+                }
+            }
+            Some("testDone") => {
+                let hidden = event["hidden"].as_bool().unwrap_or(false);
+                let passed = event["result"].as_str() == Some("success");
+                if let Some(name) = event["testID"].as_u64().and_then(|id| names.get(&id)) {
+                    if !hidden && !passed {
+                        failed.push(name.clone());
+                    }
+                }
+            }
+            _ => {}
         }
     }
+
+    failed
 }
 
 /// Result of testing a single mutation
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MutantTestResult {
+    /// The mutation that was applied
     pub mutation: Mutation,
+    /// Outcome of the test run
     pub status: MutantStatus,
+    /// Time spent testing the mutant
     pub duration: Duration,
+    /// Test output, kept for reports
     pub output: Option<String>,
+    /// Why the mutant could not be tested, for `Error`
     pub error: Option<String>,
+    /// Extra test runs from `--rerun-killed` or `--confirm-survivors`
+    #[serde(default)]
+    pub retries: u32,
+    /// First failing test of a killed mutant, when the test output names it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub killed_by: Option<String>,
 }
 
 /// Type alias for per-file locks to prevent concurrent mutations on same file
@@ -56,6 +198,212 @@ async fn get_file_lock(file_locks: &FileLocks, file_path: &Path) -> Arc<Mutex<()
         .clone()
 }
 
+/// Per-file limits on sandboxed mutants tested at once
+type FileSlots = Arc<Mutex<HashMap<PathBuf, Arc<Semaphore>>>>;
+
+/// Get or create the slots for a specific file
+async fn get_file_slots(file_slots: &FileSlots, file_path: &Path, slots: usize) -> Arc<Semaphore> {
+    let mut all = file_slots.lock().await;
+    all.entry(file_path.to_path_buf())
+        .or_insert_with(|| Arc::new(Semaphore::new(slots)))
+        .clone()
+}
+
+/// The test command to run for each mutant, split into program and arguments
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TestCommand {
+    program: String,
+    args: Vec<String>,
+    env: Vec<(String, String)>,
+    test_map: Option<String>,
+}
+
+impl TestCommand {
+    /// Split a command string like `flutter test --tags unit` using shell
+    /// quoting rules
+    pub fn parse(command: &str) -> Result<Self> {
+        let mut words = shlex::split(command)
+            .with_context(|| format!("Invalid test command (unbalanced quotes): {command}"))?
+            .into_iter();
+        let program = words
+            .next()
+            .with_context(|| format!("Test command is empty: {command:?}"))?;
+        Ok(Self {
+            program,
+            args: words.collect(),
+            env: Vec::new(),
+            test_map: None,
+        })
+    }
+
+    /// Set environment variables for the test process
+    pub fn with_env(mut self, env: &[(String, String)]) -> Self {
+        self.env.extend_from_slice(env);
+        self
+    }
+
+    /// Append `--dart-define=KEY=VALUE` for each pair
+    pub fn with_dart_defines(mut self, defines: &[(String, String)]) -> Self {
+        self.args.extend(
+            defines
+                .iter()
+                .map(|(key, value)| format!("--dart-define={key}={value}")),
+        );
+        self
+    }
+
+    /// Map each source file to its test files with a glob pattern, where
+    /// `{name}` is the file stem and `{dir}` its directory under `lib/`,
+    /// e.g. `test/{dir}/{name}_test.dart`
+    pub fn with_test_map(mut self, pattern: Option<String>) -> Self {
+        self.test_map = pattern;
+        self
+    }
+
+    /// The command for mutants in `source_file`, which belongs to `library`:
+    /// the mapped test files are appended as arguments, or the whole suite
+    /// runs when none exist. A part file without tests of its own uses its
+    /// library's
+    pub fn for_source(&self, project_path: &Path, source_file: &Path, library: &Path) -> Self {
+        let test_files = self
+            .test_map
+            .as_deref()
+            .map(|pattern| {
+                let files = mapped_test_files(pattern, project_path, source_file);
+                if files.is_empty() && library != source_file {
+                    mapped_test_files(pattern, project_path, library)
+                } else {
+                    files
+                }
+            })
+            .unwrap_or_default();
+        let mut command = self.clone();
+        command.args.extend(test_files);
+        command
+    }
+
+    /// Whether this is plain `dart test`, whose reporter dart_mutant controls
+    fn is_dart_test(&self) -> bool {
+        self.program == "dart" && self.args.first().is_some_and(|a| a == "test")
+    }
+
+    /// Whether the user already chose a reporter
+    fn has_reporter(&self) -> bool {
+        self.args
+            .iter()
+            .any(|a| a == "-r" || a == "--reporter" || a.starts_with("--reporter="))
+    }
+
+    /// Whether runs print the JSON events that name failing tests: plain
+    /// `dart test`, with no reporter of its own or `--reporter json`
+    pub fn reports_test_names(&self) -> bool {
+        if !self.is_dart_test() {
+            return false;
+        }
+        let reporter = self.args.iter().enumerate().find_map(|(i, arg)| {
+            if arg == "-r" || arg == "--reporter" {
+                Some(self.args.get(i + 1).map_or("", String::as_str))
+            } else {
+                arg.strip_prefix("--reporter=")
+            }
+        });
+        reporter.map_or(true, |reporter| reporter == "json")
+    }
+
+    /// Build the process, appending `--reporter` only for `dart test`
+    /// without a reporter of its own
+    fn to_command(&self, reporter: &str) -> Command {
+        let mut command = Command::new(&self.program);
+        command.args(&self.args).envs(self.env.iter().cloned());
+        if self.is_dart_test() && !self.has_reporter() {
+            command.arg(format!("--reporter={reporter}"));
+        }
+        command
+    }
+}
+
+impl std::fmt::Display for TestCommand {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.program)?;
+        for arg in &self.args {
+            write!(f, " {arg}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Existing test files matching `pattern` for a source file, relative to
+/// the project
+#[allow(clippy::literal_string_with_formatting_args)] // `{name}` is our own placeholder
+fn mapped_test_files(pattern: &str, project_path: &Path, source_file: &Path) -> Vec<String> {
+    let relative = source_file
+        .strip_prefix(project_path)
+        .unwrap_or(source_file);
+    let within_lib = relative.strip_prefix("lib").unwrap_or(relative);
+    let name = within_lib
+        .file_stem()
+        .map(|stem| stem.to_string_lossy())
+        .unwrap_or_default();
+    let dir = within_lib
+        .parent()
+        .map(|dir| dir.to_string_lossy())
+        .unwrap_or_default();
+    let resolved = if dir.is_empty() {
+        pattern.replace("{dir}/", "").replace("{dir}", "")
+    } else {
+        pattern.replace("{dir}", &dir)
+    }
+    .replace("{name}", &name);
+
+    let Ok(paths) = glob::glob(&project_path.join(&resolved).to_string_lossy()) else {
+        tracing::warn!("Invalid --test-map pattern: {pattern}");
+        return Vec::new();
+    };
+    let mut files: Vec<String> = paths
+        .filter_map(Result::ok)
+        .filter(|path| path.is_file())
+        .map(|path| {
+            path.strip_prefix(project_path)
+                .unwrap_or(&path)
+                .to_string_lossy()
+                .into_owned()
+        })
+        .collect();
+    files.sort();
+    files
+}
+
+/// How many mutants run at once and how long they may take
+#[derive(Debug, Clone, Copy)]
+pub struct RunLimits {
+    pub parallel_jobs: usize,
+    /// Per-mutant test timeout
+    pub timeout: Duration,
+    /// Wall-clock budget for the whole run; mutants not started by then are
+    /// left `Pending`
+    pub max_duration: Option<Duration>,
+    /// Give each worker its own copy of the project, so mutants of the same
+    /// file run in parallel
+    pub sandbox: bool,
+    /// With `sandbox`, how many mutants of one file may run at once; `None`
+    /// for no limit below `parallel_jobs`. Without it, always one
+    pub concurrency_per_file: Option<usize>,
+    /// Keep a Dart VM running per worker instead of starting `dart test`
+    /// for every mutant
+    pub reuse_vm: bool,
+}
+
+/// Reruns that guard against flaky tests deciding a mutant's status
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Retries {
+    /// Rerun a killed mutant up to this many times; if any rerun passes it
+    /// survived, and the first failure was flaky
+    pub rerun_killed: u32,
+    /// Rerun a surviving mutant once; if that fails, the pass was flaky and
+    /// the mutant is killed
+    pub confirm_survivors: bool,
+}
+
 /// Run mutation tests in parallel
 ///
 /// Mutations are run in parallel, but mutations targeting the same file
@@ -64,20 +412,51 @@ async fn get_file_lock(file_locks: &FileLocks, file_path: &Path) -> Arc<Mutex<()
 pub async fn run_mutation_tests(
     project_path: &Path,
     mutations: &[Mutation],
-    parallel_jobs: usize,
-    timeout_secs: u64,
-    progress: ProgressBar,
+    test_command: &TestCommand,
+    limits: RunLimits,
+    retries: Retries,
+    kill_filter: &KillFilter,
+    progress: Arc<dyn ProgressSink>,
 ) -> Result<Vec<MutantTestResult>> {
-    let semaphore = Arc::new(Semaphore::new(parallel_jobs));
+    let semaphore = Arc::new(Semaphore::new(limits.parallel_jobs));
+    let sandboxes = if limits.sandbox {
+        let pool = SandboxPool::create(project_path, limits.parallel_jobs, test_command).await?;
+        Some(Arc::new(pool))
+    } else {
+        None
+    };
+    let vms = if limits.reuse_vm {
+        let pool = VmPool::create(project_path, test_command, kill_filter, limits.timeout).await?;
+        Some(Arc::new(pool))
+    } else {
+        None
+    };
     let project_path = Arc::new(project_path.to_path_buf());
-    let timeout_duration = Duration::from_secs(timeout_secs);
+    let timeout_duration = limits.timeout;
 
-    // Per-file locks to prevent concurrent mutations on the same file
+    // Per-file locks to prevent concurrent mutations on the same file. The
+    // files of a library share one lock, since the analyzer checks them all
     let file_locks: FileLocks = Arc::new(Mutex::new(HashMap::new()));
+    let file_slots: FileSlots = Arc::new(Mutex::new(HashMap::new()));
+    let slots_per_file = limits
+        .concurrency_per_file
+        .filter(|_| limits.sandbox)
+        .map(|slots| slots.max(1));
+    let libraries = owning_libraries(&project_path, mutations);
+    let lock_keys: HashMap<&PathBuf, PathBuf> = libraries
+        .iter()
+        .map(|(file, library)| {
+            (
+                file,
+                library.canonicalize().unwrap_or_else(|_| library.clone()),
+            )
+        })
+        .collect();
 
-    // Counters for progress display
-    let killed = Arc::new(AtomicUsize::new(0));
-    let survived = Arc::new(AtomicUsize::new(0));
+    let completed = Arc::new(AtomicUsize::new(0));
+    let total = mutations.len();
+    let start = Instant::now();
+    let deadline = limits.max_duration.map(|budget| start + budget);
 
     let handles: Vec<_> = mutations
         .iter()
@@ -86,9 +465,16 @@ pub async fn run_mutation_tests(
             let semaphore = semaphore.clone();
             let project_path = project_path.clone();
             let progress = progress.clone();
-            let killed = killed.clone();
-            let survived = survived.clone();
+            let completed = completed.clone();
             let file_locks = file_locks.clone();
+            let file_slots = file_slots.clone();
+            let sandboxes = sandboxes.clone();
+            let vms = vms.clone();
+            let kill_filter = kill_filter.clone();
+            let library = libraries[&mutation.location.file].clone();
+            let test_command =
+                test_command.for_source(&project_path, &mutation.location.file, &library);
+            let lock_key = lock_keys[&mutation.location.file].clone();
 
             tokio::spawn(async move {
                 let Ok(_permit) = semaphore.acquire().await else {
@@ -98,30 +484,87 @@ pub async fn run_mutation_tests(
                         duration: Duration::ZERO,
                         output: None,
                         error: Some("Failed to acquire semaphore".to_owned()),
+                        retries: 0,
+                        killed_by: None,
                     };
                 };
 
-                // Acquire per-file lock to prevent concurrent mutations on same file
-                let file_lock = get_file_lock(&file_locks, &mutation.location.file).await;
-                let _file_guard = file_lock.lock().await;
-
-                let result = test_single_mutation(&project_path, &mutation, timeout_duration).await;
+                // Acquire per-file lock to prevent concurrent mutations on same
+                // file. A sandbox is private to this worker, so it needs none,
+                // only a slot when --concurrency-per-file limits the file
+                let sandbox = sandboxes.as_ref().and_then(SandboxPool::lease);
+                let file_lock = get_file_lock(&file_locks, &lock_key).await;
+                let _file_guard = match sandbox {
+                    Some(_) => None,
+                    None => Some(file_lock.lock().await),
+                };
+                let _file_slot = match slots_per_file.filter(|_| sandbox.is_some()) {
+                    Some(slots) => get_file_slots(&file_slots, &lock_key, slots)
+                        .await
+                        .acquire_owned()
+                        .await
+                        .ok(),
+                    None => None,
+                };
 
-                // Update counters and progress
-                match result.status {
-                    MutantStatus::Killed | MutantStatus::Timeout => {
-                        killed.fetch_add(1, Ordering::SeqCst);
+                // Past the --max-duration budget, leave the mutant untested
+                let result = if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+                    MutantTestResult {
+                        mutation: mutation.clone(),
+                        status: MutantStatus::Pending,
+                        duration: Duration::ZERO,
+                        output: None,
+                        error: None,
+                        retries: 0,
+                        killed_by: None,
                     }
-                    MutantStatus::Survived => {
-                        survived.fetch_add(1, Ordering::SeqCst);
+                } else {
+                    progress.on_mutation_started(&mutation);
+                    let vm = vms.as_ref().map(VmPool::lease);
+                    match &sandbox {
+                        Some(sandbox) => {
+                            test_in_sandbox(
+                                sandbox,
+                                &mutation,
+                                &library,
+                                &test_command,
+                                timeout_duration,
+                                retries,
+                                &kill_filter,
+                                vm.as_ref(),
+                            )
+                            .await
+                        }
+                        None => {
+                            test_single_mutation(
+                                &project_path,
+                                &mutation,
+                                &library,
+                                &test_command,
+                                timeout_duration,
+                                retries,
+                                &kill_filter,
+                                vm.as_ref(),
+                            )
+                            .await
+                        }
                     }
-                    _ => {}
-                }
+                };
 
-                let k = killed.load(Ordering::SeqCst);
-                let s = survived.load(Ordering::SeqCst);
-                progress.set_message(format!("killed: {} survived: {}", k, s));
-                progress.inc(1);
+                progress.on_mutation_finished(&result);
+                tracing::debug!(
+                    "{}:{} {} [{:?}]",
+                    result.mutation.location.file.display(),
+                    result.mutation.location.start_line,
+                    result.mutation.operator.name(),
+                    result.status
+                );
+
+                let done = completed.fetch_add(1, Ordering::SeqCst) + 1;
+                tracing::debug!(
+                    "{done}/{total} mutants tested, {:.2} mutants/s",
+                    done as f64 / start.elapsed().as_secs_f64()
+                );
 
                 result
             })
@@ -136,11 +579,140 @@ pub async fn run_mutation_tests(
     Ok(results)
 }
 
-/// Test a single mutation
+/// The library each mutated file belongs to, resolved once per run: the
+/// file itself unless it's a part
+fn owning_libraries(project_path: &Path, mutations: &[Mutation]) -> HashMap<PathBuf, PathBuf> {
+    let mut libraries = HashMap::new();
+    for mutation in mutations {
+        let file = &mutation.location.file;
+        if !libraries.contains_key(file) {
+            let library =
+                library::owning_library(file, project_path).unwrap_or_else(|| file.clone());
+            libraries.insert(file.clone(), library);
+        }
+    }
+    libraries
+}
+
+/// Test a mutant against a sandbox's copy of its file, reporting it against
+/// the original
+async fn test_in_sandbox(
+    sandbox: &SandboxLease,
+    mutation: &Mutation,
+    library: &Path,
+    test_command: &TestCommand,
+    timeout_duration: Duration,
+    retries: Retries,
+    kill_filter: &KillFilter,
+    vm: Option<&VmLease>,
+) -> MutantTestResult {
+    let Some(file) = sandbox.map(&mutation.location.file) else {
+        return MutantTestResult {
+            mutation: mutation.clone(),
+            status: MutantStatus::Error,
+            duration: Duration::ZERO,
+            output: None,
+            error: Some("File is outside the sandboxed project".to_owned()),
+            retries: 0,
+            killed_by: None,
+        };
+    };
+    let library = sandbox.map(library).unwrap_or_else(|| file.clone());
+    let mut sandboxed = mutation.clone();
+    sandboxed.location.file = file;
+
+    let mut result = test_single_mutation(
+        sandbox.root(),
+        &sandboxed,
+        &library,
+        test_command,
+        timeout_duration,
+        retries,
+        kill_filter,
+        vm,
+    )
+    .await;
+    result.mutation = mutation.clone();
+    result
+}
+
+/// The file holding most of `mutations`, with its count, when it holds
+/// enough of them that testing its mutants one at a time leaves
+/// `parallel_jobs` workers idle: over half, and more than one per worker
+pub fn dominant_file(mutations: &[Mutation], parallel_jobs: usize) -> Option<(&Path, usize)> {
+    if parallel_jobs < 2 {
+        return None;
+    }
+    let mut counts: HashMap<&Path, usize> = HashMap::new();
+    for mutation in mutations {
+        *counts.entry(&mutation.location.file).or_default() += 1;
+    }
+    counts
+        .into_iter()
+        .max_by_key(|&(file, count)| (count, std::cmp::Reverse(file)))
+        .filter(|&(_, count)| count * 2 > mutations.len() && count > parallel_jobs)
+}
+
+/// Per-mutant timeout never derived below this, so a very fast suite still
+/// has room for the slowdown of parallel runs
+const MIN_AUTO_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Per-mutant timeout derived from the baseline test run
+pub fn auto_timeout(baseline: Duration, factor: f64) -> Duration {
+    baseline.mul_f64(factor.max(1.0)).max(MIN_AUTO_TIMEOUT)
+}
+
+/// Outcome of the test command on the unmutated code
+#[derive(Debug, Clone)]
+pub struct BaselineRun {
+    pub duration: Duration,
+    pub exit_code: i32,
+    /// Captured stderr, or the end of stdout when stderr is empty
+    pub output: String,
+}
+
+impl BaselineRun {
+    pub const fn passed(&self) -> bool {
+        self.exit_code == 0
+    }
+}
+
+/// Run the unmutated tests once to check they pass and time them
+pub async fn run_baseline(project_path: &Path, test_command: &TestCommand) -> Result<BaselineRun> {
+    let start = Instant::now();
+    let (exit_code, stdout, stderr) =
+        run_test_command(project_path, test_command, "compact").await?;
+    let output = if stderr.trim().is_empty() {
+        let lines: Vec<&str> = stdout.lines().collect();
+        lines[lines.len().saturating_sub(20)..].join("\n")
+    } else {
+        stderr
+    };
+    Ok(BaselineRun {
+        duration: start.elapsed(),
+        exit_code,
+        output,
+    })
+}
+
+/// Run the unmutated test suite once, discarding results, so compilation and
+/// pub resolution are cached before mutants are timed
+pub async fn run_warmup(project_path: &Path, test_command: &TestCommand) -> Result<Duration> {
+    let start = Instant::now();
+    run_test_command(project_path, test_command, "compact").await?;
+    Ok(start.elapsed())
+}
+
+/// Test a single mutation in a file of `library`
 async fn test_single_mutation(
     project_path: &Path,
     mutation: &Mutation,
+    library: &Path,
+    test_command: &TestCommand,
     timeout_duration: Duration,
+    retries: Retries,
+    kill_filter: &KillFilter,
+    vm: Option<&VmLease>,
 ) -> MutantTestResult {
     let start = Instant::now();
 
@@ -155,10 +727,26 @@ async fn test_single_mutation(
                 duration: start.elapsed(),
                 output: None,
                 error: Some(format!("Failed to read file: {}", e)),
+                retries: 0,
+                killed_by: None,
             };
         }
     };
 
+    // Offsets from an older version of the file would splice the mutant
+    // into the wrong code
+    if !mutation.matches(&original_source) {
+        return MutantTestResult {
+            mutation: mutation.clone(),
+            status: MutantStatus::Error,
+            duration: start.elapsed(),
+            output: None,
+            error: Some("File changed since the mutant was generated".to_owned()),
+            retries: 0,
+            killed_by: None,
+        };
+    }
+
     // Apply the mutation
     let mutated_source = mutation.apply(&original_source);
 
@@ -176,20 +764,109 @@ async fn test_single_mutation(
             duration: start.elapsed(),
             output: None,
             error: Some(format!("Failed to write mutated file: {}", e)),
+            retries: 0,
+            killed_by: None,
+        };
+    }
+
+    // A mutant that doesn't compile fails the tests for the wrong reason
+    if let Ok(Some(compile_error)) = timeout(
+        timeout_duration,
+        analyze_for_errors(project_path, file_path, library),
+    )
+    .await
+    {
+        return MutantTestResult {
+            mutation: mutation.clone(),
+            status: MutantStatus::Error,
+            duration: start.elapsed(),
+            output: None,
+            error: Some(format!("Compile error: {compile_error}")),
+            retries: 0,
+            killed_by: None,
         };
     }
 
-    // Run the test command
-    let test_result = timeout(timeout_duration, run_dart_test(project_path)).await;
+    let run = || {
+        run_mutant_tests(
+            project_path,
+            test_command,
+            timeout_duration,
+            kill_filter,
+            vm,
+        )
+    };
+    let (mut status, mut output, mut error) = run().await;
+
+    // Rerun while the mutant is still written, so flaky tests don't decide
+    let mut reruns = 0;
+    if status == MutantStatus::Killed {
+        while reruns < retries.rerun_killed {
+            reruns += 1;
+            let rerun = run().await;
+            if rerun.0 == MutantStatus::Survived {
+                (status, output, error) = rerun;
+                break;
+            }
+        }
+    } else if status == MutantStatus::Survived && retries.confirm_survivors {
+        reruns += 1;
+        let rerun = run().await;
+        if matches!(rerun.0, MutantStatus::Killed | MutantStatus::Timeout) {
+            (status, output, error) = rerun;
+        }
+    }
+
+    // Only `dart test` output names the failing tests
+    let killed_by = (status == MutantStatus::Killed)
+        .then(|| {
+            output
+                .as_deref()
+                .and_then(|out| kill_filter.killing_test(out))
+        })
+        .flatten();
 
     // File will be restored by _restore_guard when it goes out of scope
 
-    // Interpret the result
-    let (status, output, error) = match test_result {
+    MutantTestResult {
+        mutation: mutation.clone(),
+        status,
+        duration: start.elapsed(),
+        output,
+        error,
+        retries: reruns,
+        killed_by,
+    }
+}
+
+/// Run the tests against the mutant on disk and classify the outcome as
+/// `(status, output, error)`
+async fn run_mutant_tests(
+    project_path: &Path,
+    test_command: &TestCommand,
+    timeout_duration: Duration,
+    kill_filter: &KillFilter,
+    vm: Option<&VmLease>,
+) -> (MutantStatus, Option<String>, Option<String>) {
+    // JSON events name the failing tests, for the kill filter and for
+    // reporting which test killed the mutant
+    let test_result = timeout(timeout_duration, async {
+        match vm {
+            Some(vm) => vm.run_tests(project_path, test_command).await,
+            None => run_test_command(project_path, test_command, "json").await,
+        }
+    })
+    .await;
+
+    match test_result {
         Ok(Ok((exit_code, stdout, stderr))) => {
             if exit_code == 0 {
                 // Tests passed - mutation survived (bad!)
                 (MutantStatus::Survived, Some(stdout), None)
+            } else if kill_filter.is_active() {
+                // Only failures of selected tests kill the mutation
+                let status = kill_filter.status_for_failure(&stdout);
+                (status, Some(stdout), Some(stderr))
             } else {
                 // Tests failed - mutation killed (good!)
                 (MutantStatus::Killed, Some(stdout), Some(stderr))
@@ -204,28 +881,84 @@ async fn test_single_mutation(
                 Some("Test timed out".to_string()),
             )
         }
-    };
-
-    MutantTestResult {
-        mutation: mutation.clone(),
-        status,
-        duration: start.elapsed(),
-        output,
-        error,
     }
 }
 
-/// Run `dart test` and return (exit_code, stdout, stderr)
-async fn run_dart_test(project_path: &Path) -> Result<(i32, String, String)> {
+/// Run `dart analyze` on `library`, which `file` belongs to, and return its
+/// first error, if any. A part file is analyzed with its library and the
+/// other parts, since a mutant in one can break another. Analyzer failures
+/// are ignored so the tests still decide the outcome.
+async fn analyze_for_errors(project_path: &Path, file: &Path, library: &Path) -> Option<String> {
+    analyze_library(project_path, file, library)
+        .await?
+        .into_iter()
+        .next()
+}
+
+/// Every error `dart analyze` reports for `library`, which `file` belongs
+/// to, as `analyze_for_errors` formats them. `None` if the analyzer
+/// couldn't run
+async fn analyze_library(project_path: &Path, file: &Path, library: &Path) -> Option<Vec<String>> {
     let output = Command::new("dart")
-        .arg("test")
-        .arg("--reporter=compact")
+        .arg("analyze")
+        .arg("--format=machine")
+        .args(library::library_files(file, library))
+        .current_dir(project_path)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .output()
+        .await
+        .ok()?;
+
+    // Machine output goes to stderr, but accept either stream
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let mut errors = analyzer_errors(&stderr, file);
+    errors.extend(analyzer_errors(&stdout, file));
+    Some(errors)
+}
+
+/// Error diagnostics from `dart analyze --format=machine`, formatted as
+/// `line:col: message`, with the file name in front for errors outside
+/// `file`. Lines look like
+/// `ERROR|COMPILE_TIME_ERROR|CODE|/path/file.dart|line|col|length|message`.
+fn analyzer_errors(machine_output: &str, file: &Path) -> Vec<String> {
+    machine_output
+        .lines()
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.splitn(8, '|').collect();
+            match fields.as_slice() {
+                ["ERROR", _, _, path, line, col, _, message] => {
+                    let path = Path::new(path);
+                    Some(if path.file_name() == file.file_name() {
+                        format!("{line}:{col}: {message}")
+                    } else {
+                        format!(
+                            "{}:{line}:{col}: {message}",
+                            path.file_name().unwrap_or_default().to_string_lossy()
+                        )
+                    })
+                }
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+/// Run the test command and return (exit_code, stdout, stderr)
+async fn run_test_command(
+    project_path: &Path,
+    test_command: &TestCommand,
+    reporter: &str,
+) -> Result<(i32, String, String)> {
+    let output = test_command
+        .to_command(reporter)
         .current_dir(project_path)
         .stdout(Stdio::piped())
         .stderr(Stdio::piped())
         .output()
         .await
-        .context("Failed to run dart test")?;
+        .with_context(|| format!("Failed to run {test_command}"))?;
 
     let exit_code = output.status.code().unwrap_or(-1);
     let stdout = String::from_utf8_lossy(&output.stdout).to_string();
@@ -238,6 +971,7 @@ async fn run_dart_test(project_path: &Path) -> Result<(i32, String, String)> {
 #[allow(clippy::unwrap_used)]
 mod tests {
     use super::*;
+    use crate::mutation::testing::mutant;
     use crate::mutation::{MutationOperator, SourceLocation};
     use std::path::PathBuf;
     use std::sync::atomic::AtomicU32;
@@ -261,10 +995,10 @@ mod tests {
             replacements: vec!["-".to_string()],
             ai_suggested: false,
             ai_confidence: None,
+            likely_equivalent: false,
         }
     }
 
-    #[allow(dead_code)]
     fn create_mutation_for_file(file: &Path, id: &str) -> Mutation {
         Mutation {
             id: id.to_string(),
@@ -284,7 +1018,108 @@ mod tests {
             replacements: vec!["-".to_string()],
             ai_suggested: false,
             ai_confidence: None,
+            likely_equivalent: false,
+        }
+    }
+
+    const JSON_REPORTER_OUTPUT: &str = r#"{"type":"start","protocolVersion":"0.1.1","time":0}
+{"type":"testStart","test":{"id":1,"name":"loading test/calc_test.dart"},"time":1}
+{"type":"testDone","testID":1,"result":"success","hidden":true,"time":2}
+{"type":"testStart","test":{"id":2,"name":"Calculator add"},"time":3}
+{"type":"testDone","testID":2,"result":"failure","hidden":false,"time":4}
+{"type":"testStart","test":{"id":3,"name":"slow network sync"},"time":5}
+{"type":"testDone","testID":3,"result":"error","hidden":false,"time":6}
+{"type":"testStart","test":{"id":4,"name":"Calculator subtract"},"time":7}
+{"type":"testDone","testID":4,"result":"success","hidden":false,"time":8}
+{"type":"done","success":false,"time":9}"#;
+
+    #[test]
+    fn test_failed_test_names_from_json_reporter() {
+        assert_eq!(
+            failed_test_names(JSON_REPORTER_OUTPUT),
+            vec!["Calculator add", "slow network sync"]
+        );
+    }
+
+    #[test]
+    fn test_kill_filter_decides_which_failures_kill() {
+        let only_calculator = KillFilter::new(&["Calculator*".to_string()], &[]).unwrap();
+        assert_eq!(
+            only_calculator.status_for_failure(JSON_REPORTER_OUTPUT),
+            MutantStatus::Killed
+        );
+
+        let ignore_calculator = KillFilter::new(&[], &["Calculator*".to_string()]).unwrap();
+        assert_eq!(
+            ignore_calculator.status_for_failure(JSON_REPORTER_OUTPUT),
+            MutantStatus::Killed
+        );
+
+        let only_unrelated = KillFilter::new(&["Parser*".to_string()], &[]).unwrap();
+        assert_eq!(
+            only_unrelated.status_for_failure(JSON_REPORTER_OUTPUT),
+            MutantStatus::Survived
+        );
+
+        // A load failure has no visible failing test, so it still kills
+        assert_eq!(
+            only_unrelated.status_for_failure(r#"{"type":"done","success":false}"#),
+            MutantStatus::Killed
+        );
+    }
+
+    #[test]
+    fn test_killing_test_is_first_failure_that_counts() {
+        assert_eq!(
+            KillFilter::default().killing_test(JSON_REPORTER_OUTPUT),
+            Some("Calculator add".to_owned())
+        );
+
+        let ignore_calculator = KillFilter::new(&[], &["Calculator*".to_string()]).unwrap();
+        assert_eq!(
+            ignore_calculator.killing_test(JSON_REPORTER_OUTPUT),
+            Some("slow network sync".to_owned())
+        );
+
+        assert_eq!(
+            KillFilter::default().killing_test("00:01 +1: All tests passed!"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_restore_guard_restores_original_content() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("lib.dart");
+        std::fs::write(&path, "int a = 1 - 2;").unwrap();
+
+        {
+            let _guard = FileRestoreGuard {
+                path: path.clone(),
+                original_content: "int a = 1 + 2;".to_string(),
+            };
         }
+
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "int a = 1 + 2;");
+        assert!(!dir.path().join("lib.dart.dart_mutant.orig").exists());
+    }
+
+    #[test]
+    fn test_restore_guard_writes_sidecar_when_restore_fails() {
+        let dir = tempfile::tempdir().unwrap();
+        // Writing to a directory always fails, simulating a locked file
+        let path = dir.path().join("locked.dart");
+        std::fs::create_dir(&path).unwrap();
+
+        {
+            let _guard = FileRestoreGuard {
+                path: path.clone(),
+                original_content: "int a = 1 + 2;".to_string(),
+            };
+        }
+
+        let sidecar = dir.path().join("locked.dart.dart_mutant.orig");
+        assert_eq!(std::fs::read_to_string(sidecar).unwrap(), "int a = 1 + 2;");
     }
 
     #[test]
@@ -521,4 +1356,308 @@ mod tests {
             "Lock should be available after previous guard dropped"
         );
     }
+
+    #[test]
+    fn test_test_command_parsing() {
+        let default = TestCommand::parse("dart test").unwrap();
+        assert_eq!(default.to_string(), "dart test");
+        assert!(default.is_dart_test() && !default.has_reporter());
+
+        let flutter = TestCommand::parse("flutter test").unwrap();
+        assert!(!flutter.is_dart_test());
+
+        let quoted = TestCommand::parse(r#"dart test --tags unit --name "adds two""#).unwrap();
+        assert_eq!(
+            quoted.args,
+            ["test", "--tags", "unit", "--name", "adds two"]
+        );
+
+        assert!(TestCommand::parse("dart test -r expanded")
+            .unwrap()
+            .has_reporter());
+        assert!(TestCommand::parse("dart test --reporter=json")
+            .unwrap()
+            .has_reporter());
+
+        let names = |command: &str| TestCommand::parse(command).unwrap().reports_test_names();
+        assert!(names("dart test"));
+        assert!(names("dart test -r json test/calc_test.dart"));
+        assert!(names("dart test --reporter=json"));
+        assert!(!names("dart test --reporter expanded"));
+        assert!(!names("dart test -r"));
+        assert!(!names("flutter test"));
+
+        assert!(TestCommand::parse("").is_err());
+        assert!(TestCommand::parse("dart test \"unterminated").is_err());
+    }
+
+    #[test]
+    fn test_dominant_file_needs_most_of_the_mutants() {
+        let mutation = |file: &str| mutant().file(file).mutation();
+        let mutations: Vec<Mutation> = ["big.dart"; 6]
+            .into_iter()
+            .chain(["small.dart"; 3])
+            .map(mutation)
+            .collect();
+
+        assert_eq!(
+            dominant_file(&mutations, 4),
+            Some((Path::new("big.dart"), 6))
+        );
+        // One worker, or at least one worker per mutant, leaves no one idle
+        assert_eq!(dominant_file(&mutations, 1), None);
+        assert_eq!(dominant_file(&mutations, 8), None);
+        assert_eq!(dominant_file(&mutations[3..], 2), None);
+    }
+
+    #[test]
+    fn test_analyzer_errors_from_machine_output() {
+        let output = "\
+INFO|LINT|PREFER_CONST|/p/lib/a.dart|3|1|5|Use const.
+ERROR|COMPILE_TIME_ERROR|UNDEFINED_OPERATOR|/p/lib/a.dart|10|14|1|The operator '-' isn't defined for the type 'String'.
+WARNING|STATIC_WARNING|DEAD_CODE|/p/lib/a.dart|12|5|9|Dead code.
+ERROR|COMPILE_TIME_ERROR|UNDEFINED_METHOD|/p/lib/a.g.dart|4|7|3|The method 'sum' isn't defined.
+";
+        let file = Path::new("lib/a.dart");
+
+        assert_eq!(
+            analyzer_errors(output, file),
+            [
+                "10:14: The operator '-' isn't defined for the type 'String'.",
+                "a.g.dart:4:7: The method 'sum' isn't defined."
+            ]
+        );
+        assert!(analyzer_errors("No issues found!", file).is_empty());
+    }
+
+    #[test]
+    fn test_test_command_env_and_defines() {
+        let env = [("API_URL".to_owned(), "http://localhost".to_owned())];
+        let defines = [("FLAVOR".to_owned(), "dev=1".to_owned())];
+        let command = TestCommand::parse("flutter test")
+            .unwrap()
+            .with_env(&env)
+            .with_dart_defines(&defines)
+            .to_command("compact");
+        let command = command.as_std();
+
+        let args: Vec<_> = command.get_args().collect();
+        assert_eq!(args, ["test", "--dart-define=FLAVOR=dev=1"]);
+        let envs: Vec<_> = command.get_envs().collect();
+        assert_eq!(
+            envs,
+            [(
+                std::ffi::OsStr::new("API_URL"),
+                Some(std::ffi::OsStr::new("http://localhost"))
+            )]
+        );
+    }
+
+    #[test]
+    #[allow(clippy::literal_string_with_formatting_args)]
+    fn test_test_map_selects_tests_for_source_file() {
+        let dir = tempfile::tempdir().unwrap();
+        for (file, source) in [
+            ("lib/calc.dart", ""),
+            ("lib/src/parser.dart", "part 'parser.g.dart';\n"),
+            ("lib/src/parser.g.dart", "part of 'parser.dart';\n"),
+            ("lib/src/untested.dart", ""),
+            ("test/calc_test.dart", ""),
+            ("test/src/parser_test.dart", ""),
+        ] {
+            let path = dir.path().join(file);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, source).unwrap();
+        }
+        let command = TestCommand::parse("dart test")
+            .unwrap()
+            .with_test_map(Some("test/{dir}/{name}_test.dart".to_owned()));
+        let args_for = |file: &str| {
+            let file = dir.path().join(file);
+            let library =
+                library::owning_library(&file, dir.path()).unwrap_or_else(|| file.clone());
+            command.for_source(dir.path(), &file, &library).args
+        };
+
+        assert_eq!(args_for("lib/calc.dart"), ["test", "test/calc_test.dart"]);
+        assert_eq!(
+            args_for("lib/src/parser.dart"),
+            ["test", "test/src/parser_test.dart"]
+        );
+        // A part file is tested through its library
+        assert_eq!(
+            args_for("lib/src/parser.g.dart"),
+            ["test", "test/src/parser_test.dart"]
+        );
+        assert_eq!(args_for("lib/src/untested.dart"), ["test"]);
+    }
+
+    #[derive(Default)]
+    struct RecordedEvents {
+        started: AtomicUsize,
+        finished: AtomicUsize,
+    }
+
+    impl ProgressSink for RecordedEvents {
+        fn on_mutation_started(&self, _mutation: &Mutation) {
+            self.started.fetch_add(1, Ordering::SeqCst);
+        }
+
+        fn on_mutation_finished(&self, _result: &MutantTestResult) {
+            self.finished.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_max_duration_leaves_unstarted_mutants_pending() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("calc.dart");
+        std::fs::write(&file, "+").unwrap();
+        let mutations = vec![
+            create_mutation_for_file(&file, "a"),
+            create_mutation_for_file(&file, "b"),
+        ];
+
+        let events = Arc::new(RecordedEvents::default());
+        let results = run_mutation_tests(
+            dir.path(),
+            &mutations,
+            &TestCommand::parse("dart test").unwrap(),
+            RunLimits {
+                parallel_jobs: 2,
+                timeout: Duration::from_secs(30),
+                max_duration: Some(Duration::ZERO),
+                sandbox: false,
+                concurrency_per_file: None,
+                reuse_vm: false,
+            },
+            Retries::default(),
+            &KillFilter::default(),
+            events.clone(),
+        )
+        .await
+        .unwrap();
+
+        assert!(results.iter().all(|r| r.status == MutantStatus::Pending));
+        // Pending mutants finish without ever starting
+        assert_eq!(events.started.load(Ordering::SeqCst), 0);
+        assert_eq!(events.finished.load(Ordering::SeqCst), 2);
+        assert_eq!(std::fs::read_to_string(&file).unwrap(), "+");
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_sandbox_mutates_copies_not_the_project() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("calc.dart");
+        std::fs::write(&file, "int a = 1 + 2;\n").unwrap();
+        let mutation = |start, original: &str, mutated: &str| {
+            mutant()
+                .file(&file)
+                .at(start)
+                .column(start + 1)
+                .change(original, mutated)
+                .mutation()
+        };
+        let mutations = vec![mutation(10, "+", "-"), mutation(8, "1", "0")];
+        // Passes only while the file in the working directory is unmutated
+        let command = TestCommand::parse("sh -c 'grep -q \"1 + 2\" calc.dart'").unwrap();
+
+        let results = run_mutation_tests(
+            dir.path(),
+            &mutations,
+            &command,
+            RunLimits {
+                parallel_jobs: 2,
+                timeout: Duration::from_secs(30),
+                max_duration: None,
+                sandbox: true,
+                concurrency_per_file: None,
+                reuse_vm: false,
+            },
+            Retries::default(),
+            &KillFilter::default(),
+            Arc::new(crate::progress::NoProgress),
+        )
+        .await
+        .unwrap();
+
+        assert!(results.iter().all(|r| r.status == MutantStatus::Killed));
+        assert!(results.iter().all(|r| r.mutation.location.file == file));
+        assert_eq!(std::fs::read_to_string(&file).unwrap(), "int a = 1 + 2;\n");
+    }
+
+    /// Test one mutant with a shell command standing in for `dart test`.
+    /// `$n` in `script` is the 1-based run number
+    #[cfg(unix)]
+    async fn test_with_script(script: &str, retries: Retries) -> MutantTestResult {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("calc.dart");
+        std::fs::write(&file, "int a = 1 + 2;\n").unwrap();
+        let mutation = mutant().file(&file).at(10).column(11).mutation();
+        let counter = "n=$(($(cat runs 2>/dev/null || echo 0) + 1)); echo $n > runs";
+        let command = TestCommand::parse(&format!("sh -c '{counter}; {script}'")).unwrap();
+
+        let result = test_single_mutation(
+            dir.path(),
+            &mutation,
+            &file,
+            &command,
+            Duration::from_secs(30),
+            retries,
+            &KillFilter::default(),
+            None,
+        )
+        .await;
+        result
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_rerun_killed_downgrades_flaky_kill_to_survived() {
+        let retries = Retries {
+            rerun_killed: 3,
+            confirm_survivors: false,
+        };
+
+        let flaky = test_with_script("[ $n -ge 2 ]", retries).await;
+        assert_eq!(flaky.status, MutantStatus::Survived);
+        assert_eq!(flaky.retries, 1);
+
+        let solid = test_with_script("exit 1", retries).await;
+        assert_eq!(solid.status, MutantStatus::Killed);
+        assert_eq!(solid.retries, 3);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_confirm_survivors_catches_flaky_pass() {
+        let retries = Retries {
+            rerun_killed: 0,
+            confirm_survivors: true,
+        };
+
+        let flaky = test_with_script("[ $n -lt 2 ]", retries).await;
+        assert_eq!(flaky.status, MutantStatus::Killed);
+        assert_eq!(flaky.retries, 1);
+
+        let solid = test_with_script("exit 0", retries).await;
+        assert_eq!(solid.status, MutantStatus::Survived);
+        assert_eq!(solid.retries, 1);
+
+        let unretried = test_with_script("exit 0", Retries::default()).await;
+        assert_eq!(unretried.retries, 0);
+    }
+
+    #[test]
+    fn test_auto_timeout_scales_baseline_with_floor() {
+        assert_eq!(
+            auto_timeout(Duration::from_secs(10), 3.0),
+            Duration::from_secs(30)
+        );
+        assert_eq!(
+            auto_timeout(Duration::from_millis(400), 3.0),
+            MIN_AUTO_TIMEOUT
+        );
+    }
 }
@@ -0,0 +1,320 @@
+//! Long-lived Dart VMs for `--reuse-vm`
+//!
+//! `dart test` starts a VM, checks package resolution and compiles its
+//! runner before the first test runs, and it does all of that again for
+//! every mutant. With `--reuse-vm` each worker keeps a small Dart server
+//! running instead. For each mutant it spawns every test file in a fresh
+//! isolate, which compiles the mutated sources, while the VM and its kernel
+//! compiler stay warm between mutants.
+//!
+//! Test files run standalone, as with `dart run test/foo_test.dart`: the
+//! `dart test` runner and its configuration (`dart_test.yaml`, tags,
+//! platform selectors) are not involved. The pool checks that the
+//! unmutated suite passes this way before any mutant is tested.
+
+use super::{KillFilter, TestCommand};
+use anyhow::{bail, Context, Result};
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tempfile::TempDir;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader, Lines};
+use tokio::process::{Child, ChildStdin, ChildStdout, Command};
+use tokio::time::timeout;
+
+/// Starts reply lines on the server's stdout; everything else there is test
+/// output
+const REPLY_MARKER: &str = "##dart_mutant##";
+
+/// Reads `{"root": ..., "tests": [...]}` requests from stdin, one per line.
+/// A test file fails if its isolate can't be spawned (a compile error) or
+/// ends with an uncaught error, which is how `package:test` reports failed
+/// tests when run without its runner
+const TEST_SERVER_DART: &str = r"
+import 'dart:convert';
+import 'dart:io';
+import 'dart:isolate';
+
+Future<void> main() async {
+  final requests =
+      stdin.transform(utf8.decoder).transform(const LineSplitter());
+  await for (final line in requests) {
+    final request = jsonDecode(line) as Map<String, dynamic>;
+    final root = request['root'] as String;
+    final paths = (request['tests'] as List).cast<String>();
+    final failures = await runTests(root, paths.isEmpty ? ['test'] : paths);
+    final reply = {'passed': failures.isEmpty, 'failures': failures};
+    stdout.writeln('##dart_mutant## ${jsonEncode(reply)}');
+    await stdout.flush();
+  }
+}
+
+Future<List<String>> runTests(String root, List<String> paths) async {
+  Directory.current = root;
+  final packageConfig =
+      Uri.directory(root).resolve('.dart_tool/package_config.json');
+  final files = <String>[];
+  for (final path in paths) {
+    final absolute = File(path).isAbsolute ? path : '$root/$path';
+    if (Directory(absolute).existsSync()) {
+      files.addAll(Directory(absolute)
+          .listSync(recursive: true)
+          .whereType<File>()
+          .map((file) => file.path)
+          .where((file) => file.endsWith('_test.dart')));
+    } else if (File(absolute).existsSync()) {
+      files.add(absolute);
+    }
+  }
+  final results = await Future.wait(
+      files.map((file) => runFile(Uri.file(file), packageConfig)));
+  return results.whereType<String>().toList();
+}
+
+Future<String?> runFile(Uri file, Uri packageConfig) async {
+  final errors = ReceivePort();
+  final exit = ReceivePort();
+  String? failure;
+  errors.listen((error) {
+    failure ??= '${file.toFilePath()}: ${(error as List).first}';
+  });
+  try {
+    await Isolate.spawnUri(file, const [], null,
+        packageConfig: packageConfig,
+        onError: errors.sendPort,
+        onExit: exit.sendPort);
+    await exit.first;
+  } catch (error) {
+    failure ??= '${file.toFilePath()}: $error';
+  }
+  exit.close();
+  errors.close();
+  return failure;
+}
+";
+
+#[derive(Deserialize)]
+struct Reply {
+    passed: bool,
+    failures: Vec<String>,
+}
+
+/// The `dart` process behind one worker
+#[derive(Debug)]
+struct TestServer {
+    // Killed when the server is dropped
+    _child: Child,
+    stdin: ChildStdin,
+    stdout: Lines<BufReader<ChildStdout>>,
+}
+
+impl TestServer {
+    fn spawn(script: &Path, env: &[(String, String)]) -> Result<Self> {
+        let mut child = Command::new("dart")
+            .arg(script)
+            .envs(env.iter().cloned())
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .kill_on_drop(true)
+            .spawn()
+            .context("Failed to start the --reuse-vm test server")?;
+        let stdin = child.stdin.take().context("Test server has no stdin")?;
+        let stdout = child.stdout.take().context("Test server has no stdout")?;
+        Ok(Self {
+            _child: child,
+            stdin,
+            stdout: BufReader::new(stdout).lines(),
+        })
+    }
+
+    /// Run `tests` (all of `test/` when empty) against the project at
+    /// `root` and return (exit_code, stdout, stderr) like a `dart test` run
+    async fn run(&mut self, root: &Path, tests: &[String]) -> Result<(i32, String, String)> {
+        let request = serde_json::json!({ "root": root, "tests": tests });
+        self.stdin
+            .write_all(format!("{request}\n").as_bytes())
+            .await?;
+        self.stdin.flush().await?;
+
+        let mut output = String::new();
+        while let Some(line) = self.stdout.next_line().await? {
+            if let Some(reply) = line.strip_prefix(REPLY_MARKER) {
+                let reply: Reply = serde_json::from_str(reply.trim())
+                    .context("Unreadable reply from the --reuse-vm test server")?;
+                return Ok((i32::from(!reply.passed), output, reply.failures.join("\n")));
+            }
+            output.push_str(&line);
+            output.push('\n');
+        }
+        bail!("The --reuse-vm test server exited")
+    }
+}
+
+/// Test servers not currently leased to a worker
+#[derive(Debug)]
+pub struct VmPool {
+    script: TempDir,
+    env: Vec<(String, String)>,
+    free: Mutex<Vec<TestServer>>,
+}
+
+/// A worker's test server, started on first use and returned to the pool on
+/// drop
+#[derive(Debug)]
+pub struct VmLease {
+    pool: Arc<VmPool>,
+    server: tokio::sync::Mutex<Option<TestServer>>,
+}
+
+impl VmPool {
+    /// Start one server and check that the unmutated suite passes in it
+    pub async fn create(
+        project: &Path,
+        test_command: &TestCommand,
+        kill_filter: &KillFilter,
+        check_timeout: Duration,
+    ) -> Result<Self> {
+        let Some(tests) = test_paths(test_command) else {
+            bail!(
+                "--reuse-vm runs test files without the `dart test` runner, so it needs a \
+                 plain `dart test` command with no options, not `{test_command}`"
+            );
+        };
+        if kill_filter.is_active() {
+            bail!(
+                "--reuse-vm can't tell which tests failed, so it can't be combined with \
+                 --killing-tests or --non-killing-tests"
+            );
+        }
+
+        let script = tempfile::Builder::new()
+            .prefix("dart_mutant_vm_")
+            .tempdir()
+            .context("Failed to create test server directory")?;
+        std::fs::write(script.path().join("test_server.dart"), TEST_SERVER_DART)
+            .context("Failed to write test server")?;
+        let pool = Self {
+            script,
+            env: test_command.env.clone(),
+            free: Mutex::new(Vec::new()),
+        };
+
+        let mut server = TestServer::spawn(&pool.script(), &pool.env)?;
+        let (exit_code, output, failures) =
+            timeout(check_timeout, server.run(&absolute(project), tests))
+                .await
+                .context("The tests timed out in the --reuse-vm test server")??;
+        if exit_code != 0 {
+            bail!(
+                "The tests pass with `dart test` but fail when run in a reused VM, so \
+                 --reuse-vm would count every mutant as killed. Drop --reuse-vm for this \
+                 project.\n\n{}{failures}",
+                output
+            );
+        }
+        pool.free
+            .lock()
+            .map_err(|_| anyhow::anyhow!("VM pool poisoned"))?
+            .push(server);
+        Ok(pool)
+    }
+
+    /// Lease a server. A new one is started on first use if none are free
+    pub fn lease(self: &Arc<Self>) -> VmLease {
+        let server = self.free.lock().ok().and_then(|mut free| free.pop());
+        VmLease {
+            pool: Arc::clone(self),
+            server: tokio::sync::Mutex::new(server),
+        }
+    }
+
+    fn script(&self) -> PathBuf {
+        self.script.path().join("test_server.dart")
+    }
+}
+
+impl VmLease {
+    /// Run the tests of `test_command` against the project at `root`. A
+    /// server whose run was cut short (by a timeout) is killed, and the next
+    /// run starts a fresh one
+    pub async fn run_tests(
+        &self,
+        root: &Path,
+        test_command: &TestCommand,
+    ) -> Result<(i32, String, String)> {
+        let tests = test_paths(test_command).unwrap_or_default();
+        let mut slot = self.server.lock().await;
+        let mut server = match slot.take() {
+            Some(server) => server,
+            None => TestServer::spawn(&self.pool.script(), &self.pool.env)?,
+        };
+        let result = server.run(&absolute(root), tests).await;
+        if result.is_ok() {
+            *slot = Some(server);
+        }
+        result
+    }
+}
+
+impl Drop for VmLease {
+    fn drop(&mut self) {
+        if let (Some(server), Ok(mut free)) = (self.server.get_mut().take(), self.pool.free.lock())
+        {
+            free.push(server);
+        }
+    }
+}
+
+/// The test files and directories of a plain `dart test` command, or `None`
+/// if the command is anything else
+fn test_paths(test_command: &TestCommand) -> Option<&[String]> {
+    if !test_command.is_dart_test() {
+        return None;
+    }
+    let paths = &test_command.args[1..];
+    paths
+        .iter()
+        .all(|arg| !arg.starts_with('-'))
+        .then_some(paths)
+}
+
+/// The server's working directory differs from ours, so it needs absolute
+/// paths
+fn absolute(path: &Path) -> PathBuf {
+    path.canonicalize().unwrap_or_else(|_| path.to_path_buf())
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_only_plain_dart_test_commands_reuse_the_vm() {
+        let paths = |command: &str| {
+            let command = TestCommand::parse(command).unwrap();
+            test_paths(&command).map(<[String]>::to_vec)
+        };
+
+        assert_eq!(paths("dart test"), Some(vec![]));
+        assert_eq!(
+            paths("dart test test/calc_test.dart test/unit"),
+            Some(vec![
+                "test/calc_test.dart".to_owned(),
+                "test/unit".to_owned()
+            ])
+        );
+        assert_eq!(paths("dart test --tags unit"), None);
+        assert_eq!(paths("flutter test"), None);
+        assert_eq!(paths("make test"), None);
+
+        // Defines can't be passed to a spawned isolate
+        let command = TestCommand::parse("dart test")
+            .unwrap()
+            .with_dart_defines(&[("FLAVOR".to_owned(), "ci".to_owned())]);
+        assert!(test_paths(&command).is_none());
+    }
+}
@@ -0,0 +1,226 @@
+//! Private copies of the project for `--sandbox`
+//!
+//! Mutants of the same file can't be tested side by side in one working
+//! tree, so without sandboxes the runner serializes them. With a copy of the
+//! project per worker, each mutant is applied where no other test run sees
+//! it, and the user's files are never touched.
+
+use super::TestCommand;
+use anyhow::{bail, Context, Result};
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::sync::{Arc, Mutex};
+use tempfile::TempDir;
+use tokio::process::Command;
+use walkdir::WalkDir;
+
+/// Directories at the project root not copied: version control, build
+/// output and package resolution, which `pub get` recreates
+const SKIPPED_DIRS: &[&str] = &[".git", ".dart_tool", "build"];
+
+/// Sandboxes not currently leased to a worker. Each is deleted when the
+/// pool and every lease are gone
+#[derive(Debug)]
+pub struct SandboxPool {
+    project: PathBuf,
+    canonical_project: Option<PathBuf>,
+    free: Mutex<Vec<TempDir>>,
+}
+
+/// A sandbox held by one worker, returned to the pool on drop
+#[derive(Debug)]
+pub struct SandboxLease {
+    pool: Arc<SandboxPool>,
+    dir: Option<TempDir>,
+}
+
+impl SandboxPool {
+    /// Copy `project` into `count` temp directories and resolve packages in
+    /// each with `pub get --offline`
+    pub async fn create(project: &Path, count: usize, test_command: &TestCommand) -> Result<Self> {
+        if !project.is_dir() {
+            bail!(
+                "--sandbox needs a project directory, not {}",
+                project.display()
+            );
+        }
+
+        let mut dirs = Vec::with_capacity(count);
+        for _ in 0..count.max(1) {
+            let dir = tempfile::Builder::new()
+                .prefix("dart_mutant_sandbox_")
+                .tempdir()
+                .context("Failed to create sandbox directory")?;
+            copy_project(project, dir.path())?;
+            dirs.push(dir);
+        }
+        pub_get(project, &dirs, test_command).await?;
+
+        Ok(Self {
+            project: project.to_path_buf(),
+            canonical_project: project.canonicalize().ok(),
+            free: Mutex::new(dirs),
+        })
+    }
+
+    /// Take a free sandbox. `None` only if more workers than sandboxes run
+    /// at once
+    pub fn lease(self: &Arc<Self>) -> Option<SandboxLease> {
+        let dir = self.free.lock().ok()?.pop()?;
+        Some(SandboxLease {
+            pool: Arc::clone(self),
+            dir: Some(dir),
+        })
+    }
+}
+
+impl SandboxLease {
+    /// Root of the sandbox's copy of the project
+    pub fn root(&self) -> &Path {
+        self.dir.as_ref().map_or(Path::new(""), TempDir::path)
+    }
+
+    /// Where `file` from the project lives in this sandbox
+    pub fn map(&self, file: &Path) -> Option<PathBuf> {
+        if let Ok(relative) = file.strip_prefix(&self.pool.project) {
+            return Some(self.root().join(relative));
+        }
+        // The file was found through another spelling of the project path
+        let project = self.pool.canonical_project.as_deref()?;
+        let file = file.canonicalize().ok()?;
+        Some(self.root().join(file.strip_prefix(project).ok()?))
+    }
+}
+
+impl Drop for SandboxLease {
+    fn drop(&mut self) {
+        if let (Some(dir), Ok(mut free)) = (self.dir.take(), self.pool.free.lock()) {
+            free.push(dir);
+        }
+    }
+}
+
+/// Copy the project tree, leaving out [`SKIPPED_DIRS`] at its root. The
+/// same names deeper down, like `lib/build/`, are source. The package
+/// config is copied so packages still resolve if `pub get` can't run
+fn copy_project(from: &Path, to: &Path) -> Result<()> {
+    let entries = WalkDir::new(from)
+        .follow_links(true)
+        .into_iter()
+        .filter_entry(|entry| {
+            entry.depth() != 1
+                || !(entry.file_type().is_dir()
+                    && SKIPPED_DIRS.contains(&entry.file_name().to_string_lossy().as_ref()))
+        });
+    for entry in entries {
+        let entry = entry.context("Failed to read project for sandbox")?;
+        let relative = entry.path().strip_prefix(from).unwrap_or(entry.path());
+        let target = to.join(relative);
+        if entry.file_type().is_dir() {
+            std::fs::create_dir_all(&target)?;
+        } else {
+            std::fs::copy(entry.path(), &target)
+                .with_context(|| format!("Failed to copy {} to sandbox", entry.path().display()))?;
+        }
+    }
+
+    let package_config = Path::new(".dart_tool").join("package_config.json");
+    if from.join(&package_config).is_file() {
+        std::fs::create_dir_all(to.join(".dart_tool"))?;
+        std::fs::copy(from.join(&package_config), to.join(&package_config))?;
+    }
+    Ok(())
+}
+
+/// Run `pub get --offline` in every sandbox at once. Packages come from the
+/// pub cache the project already uses, so nothing is downloaded; the point
+/// is to rewrite relative path dependencies for the new location. Skipped
+/// without a pubspec or without the tool
+async fn pub_get(project: &Path, dirs: &[TempDir], test_command: &TestCommand) -> Result<()> {
+    let tool = if test_command.program == "flutter" {
+        "flutter"
+    } else {
+        "dart"
+    };
+    if !project.join("pubspec.yaml").is_file() || which::which(tool).is_err() {
+        return Ok(());
+    }
+
+    let runs: Vec<_> = dirs
+        .iter()
+        .map(|dir| {
+            Command::new(tool)
+                .args(["pub", "get", "--offline"])
+                .current_dir(dir.path())
+                .stdin(Stdio::null())
+                .output()
+        })
+        .map(tokio::spawn)
+        .collect();
+    for run in runs {
+        let output = run
+            .await?
+            .with_context(|| format!("Failed to run {tool} pub get"))?;
+        if !output.status.success() {
+            bail!(
+                "{tool} pub get --offline failed in a sandbox; run `{tool} pub get` in the \
+                 project first.\n\n{}",
+                String::from_utf8_lossy(&output.stderr).trim_end()
+            );
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_sandboxes_copy_the_project_without_build_output() {
+        let project = tempfile::tempdir().unwrap();
+        for (file, content) in [
+            ("lib/calc.dart", "int a = 1;"),
+            ("test/calc_test.dart", "void main() {}"),
+            (".env", "KEY=1"),
+            (".git/HEAD", "ref"),
+            ("build/out.js", ""),
+            ("lib/build/builder.dart", "class Builder {}"),
+            (".dart_tool/package_config.json", "{}"),
+            (".dart_tool/test/cache.dill", ""),
+        ] {
+            let path = project.path().join(file);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, content).unwrap();
+        }
+        let command = TestCommand::parse("sh -c true").unwrap();
+
+        let pool = Arc::new(
+            SandboxPool::create(project.path(), 2, &command)
+                .await
+                .unwrap(),
+        );
+        let first = pool.lease().unwrap();
+        let second = pool.lease().unwrap();
+        assert!(pool.lease().is_none());
+
+        let root = first.root();
+        assert_ne!(root, second.root());
+        assert!(root.join("lib/calc.dart").is_file());
+        assert!(root.join("test/calc_test.dart").is_file());
+        assert!(root.join(".env").is_file());
+        assert!(root.join(".dart_tool/package_config.json").is_file());
+        assert!(!root.join(".git").exists());
+        assert!(!root.join("build").exists());
+        assert!(root.join("lib/build/builder.dart").is_file());
+        assert!(!root.join(".dart_tool/test").exists());
+        assert_eq!(
+            first.map(&project.path().join("lib/calc.dart")),
+            Some(root.join("lib/calc.dart"))
+        );
+
+        drop(first);
+        assert!(pool.lease().is_some());
+    }
+}
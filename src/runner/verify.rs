@@ -0,0 +1,231 @@
+//! Compile-only checks of generated mutants, for `--verify-mutations`
+//!
+//! A mutant that doesn't compile points at a bug in its operator: the tests
+//! would fail on it for the wrong reason. Each mutant is parsed with
+//! tree-sitter first, which catches broken syntax without Dart. One that
+//! parses is written to its file and checked with `dart analyze`, as the
+//! runner does before testing, and the file is restored afterwards.
+//!
+//! Each library is analyzed once unmutated, and only errors a mutant adds
+//! to those are blamed on it. Mutants are written to the project itself, so
+//! libraries are checked one at a time: a concurrent analysis could see
+//! another library's mutant through an import.
+
+use super::{analyze_library, owning_libraries, FileRestoreGuard, Mutation};
+use crate::parser::parse_dart;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use tree_sitter::Node;
+
+/// A mutant that doesn't compile, with the first error it introduces
+#[derive(Debug, Clone)]
+pub struct InvalidMutation {
+    /// The mutant
+    pub mutation: Mutation,
+    /// `line:col: message`, from tree-sitter or `dart analyze`
+    pub error: String,
+}
+
+/// Check every mutant, one library at a time. Without `analyze`, only
+/// syntax is checked and no file is written. Mutants whose file changed
+/// since they were generated are skipped.
+pub async fn verify_mutations(
+    project_path: &Path,
+    mutations: &[Mutation],
+    analyze: bool,
+) -> Vec<InvalidMutation> {
+    // The files of a library are analyzed together
+    let owners = owning_libraries(project_path, mutations);
+    let mut libraries: HashMap<PathBuf, (&Path, Vec<Mutation>)> = HashMap::new();
+    for mutation in mutations {
+        let library = &owners[&mutation.location.file];
+        let key = library.canonicalize().unwrap_or_else(|_| library.clone());
+        libraries
+            .entry(key)
+            .or_insert_with(|| (library, Vec::new()))
+            .1
+            .push(mutation.clone());
+    }
+
+    let mut invalid = Vec::new();
+    for (library, mutations) in libraries.into_values() {
+        invalid.extend(verify_library(project_path, library, &mutations, analyze).await);
+    }
+    invalid.sort_by(|a, b| {
+        (&a.mutation.location.file, a.mutation.location.byte_start)
+            .cmp(&(&b.mutation.location.file, b.mutation.location.byte_start))
+    });
+    invalid
+}
+
+/// Check the mutants of `library`, one at a time
+async fn verify_library(
+    project_path: &Path,
+    library: &Path,
+    mutations: &[Mutation],
+    analyze: bool,
+) -> Vec<InvalidMutation> {
+    let mut sources: HashMap<PathBuf, Option<String>> = HashMap::new();
+    // Errors of the unmutated library, per file they are reported for,
+    // analyzed when the first mutant needs them
+    let mut existing: HashMap<PathBuf, Option<Vec<String>>> = HashMap::new();
+    let mut invalid = Vec::new();
+    for mutation in mutations {
+        let file = &mutation.location.file;
+        let source = sources
+            .entry(file.clone())
+            .or_insert_with(|| std::fs::read_to_string(file).ok());
+        let Some(source) = source.as_deref().filter(|s| mutation.matches(s)) else {
+            continue;
+        };
+
+        let error = match syntax_error(source, mutation) {
+            Some(error) => Some(error),
+            None if analyze => {
+                if !existing.contains_key(file) {
+                    let errors = analyze_library(project_path, file, library).await;
+                    existing.insert(file.clone(), errors);
+                }
+                let Some(Some(before)) = existing.get(file) else {
+                    continue;
+                };
+                let _restore_guard = FileRestoreGuard {
+                    path: file.clone(),
+                    original_content: source.to_owned(),
+                };
+                match std::fs::write(file, mutation.apply(source)) {
+                    Ok(()) => analyze_library(project_path, file, library)
+                        .await
+                        .and_then(|after| new_error(before, after)),
+                    Err(_) => None,
+                }
+            }
+            None => None,
+        };
+        if let Some(error) = error {
+            invalid.push(InvalidMutation {
+                mutation: mutation.clone(),
+                error,
+            });
+        }
+    }
+    invalid
+}
+
+/// The first error in `after` that `before` doesn't account for. Errors are
+/// told apart by file and message only, since a mutant that changes a
+/// line's length moves the errors after it
+fn new_error(before: &[String], after: Vec<String>) -> Option<String> {
+    let kind = |error: &str| {
+        let (file, message) = error_kind(error);
+        (file.to_owned(), message.to_owned())
+    };
+    let mut remaining: HashMap<(String, String), usize> = HashMap::new();
+    for error in before {
+        *remaining.entry(kind(error)).or_default() += 1;
+    }
+    after
+        .into_iter()
+        .find(|error| match remaining.get_mut(&kind(error)) {
+            Some(count) if *count > 0 => {
+                *count -= 1;
+                false
+            }
+            _ => true,
+        })
+}
+
+/// `(file, message)` of an error formatted as `[file:]line:col: message`
+fn error_kind(error: &str) -> (&str, &str) {
+    let (position, message) = error.split_once(": ").unwrap_or(("", error));
+    let file = position.rsplitn(3, ':').nth(2).unwrap_or_default();
+    (file, message)
+}
+
+/// The first syntax error the mutant introduces, as `line:col: message`.
+/// A file the grammar can't parse unmutated gives no signal either way
+pub fn syntax_error(source: &str, mutation: &Mutation) -> Option<String> {
+    let original = parse_dart(source).ok()?;
+    if original.root_node().has_error() {
+        return None;
+    }
+    let mutated = parse_dart(&mutation.apply(source)).ok()?;
+    let node = first_error(mutated.root_node())?;
+    let position = node.start_position();
+    let message = if node.is_missing() {
+        format!("missing `{}`", node.kind())
+    } else {
+        "syntax error".to_owned()
+    };
+    Some(format!(
+        "{}:{}: {message}",
+        position.row + 1,
+        position.column + 1
+    ))
+}
+
+/// The first `ERROR` or missing node under `node`, in source order
+fn first_error(node: Node<'_>) -> Option<Node<'_>> {
+    if node.is_error() || node.is_missing() {
+        return Some(node);
+    }
+    if !node.has_error() {
+        return None;
+    }
+    let mut cursor = node.walk();
+    let first = node.children(&mut cursor).find_map(first_error);
+    first
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::mutation::testing::mutant;
+
+    fn mutation(source: &str, original: &str, mutated: &str) -> Mutation {
+        mutant()
+            .change(original, mutated)
+            .found_in(source)
+            .mutation()
+    }
+
+    #[test]
+    fn test_syntax_error_only_for_mutants_that_break_parsing() {
+        let source = "int add(int a, int b) {\n  return a + b;\n}\n";
+
+        assert_eq!(syntax_error(source, &mutation(source, "+", "-")), None);
+        let error = syntax_error(source, &mutation(source, "+", "+ +")).unwrap();
+        assert!(error.starts_with("2:"), "{error}");
+
+        // Nothing to blame on the operator if the file was broken already
+        let broken = "int add(int a, int b) {\n  return a + b\n";
+        assert_eq!(syntax_error(broken, &mutation(broken, "+", "+ +")), None);
+    }
+
+    #[test]
+    fn test_only_errors_the_mutant_adds_are_blamed_on_it() {
+        let before = vec![
+            "3:5: Undefined name 'x'.".to_owned(),
+            "a.g.dart:4:7: The method 'sum' isn't defined.".to_owned(),
+        ];
+
+        // The same errors on other lines, after the mutant changed a length
+        let moved = vec![
+            "4:5: Undefined name 'x'.".to_owned(),
+            "a.g.dart:4:7: The method 'sum' isn't defined.".to_owned(),
+        ];
+        assert_eq!(new_error(&before, moved), None);
+
+        let mut added = before.clone();
+        added.push("8:1: Undefined name 'x'.".to_owned());
+        assert_eq!(
+            new_error(&before, added),
+            Some("8:1: Undefined name 'x'.".to_owned())
+        );
+        assert_eq!(
+            error_kind("a.g.dart:4:7: The method 'sum' isn't defined."),
+            ("a.g.dart", "The method 'sum' isn't defined.")
+        );
+    }
+}
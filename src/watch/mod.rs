@@ -0,0 +1,387 @@
+//! `--watch`: re-test mutants as files are saved
+//!
+//! After the first run the project is watched for `.dart` changes. A saved
+//! source file is re-parsed and all of its mutants are tested again; a saved
+//! test file re-tests the mutants that survived, since the new test may kill
+//! them. Content hashes decide what changed, so the runner mutating and
+//! restoring a source file doesn't trigger another round.
+
+use crate::cache::{FileHashes, ResultCache};
+use crate::cli::Args;
+use crate::mutation::{self, MutantStatus, Mutation};
+use crate::progress::{Phase, ProgressSink, TerminalProgress};
+use crate::report::MutationResult;
+use crate::runner::{self, MutantTestResult};
+use crate::{generated_suffixes, operator_categories, parse_options, parser};
+use anyhow::{bail, Result};
+use colored::Colorize;
+use notify::{RecursiveMode, Watcher};
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Component, Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+/// Editors save in bursts (temp file, rename, touch); wait for them to
+/// settle before re-testing
+const DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// Latest result of every mutant, per canonical source path
+struct WatchState {
+    files: BTreeMap<PathBuf, Vec<MutantTestResult>>,
+    args: Args,
+    test_command: runner::TestCommand,
+    kill_filter: runner::KillFilter,
+    timeout: Duration,
+}
+
+/// Watch the project until Ctrl+C, re-testing mutants as files change.
+/// `results` is the first run's outcome, which later rounds update
+pub async fn watch(
+    args: &Args,
+    result: &MutationResult,
+    results: Vec<MutantTestResult>,
+) -> Result<()> {
+    if args.dry_run {
+        bail!("--watch needs to run tests, so it can't be combined with --dry-run");
+    }
+
+    let test_command = runner::TestCommand::parse(&args.test_command)?
+        .with_env(&args.test_env)
+        .with_dart_defines(&args.dart_define)
+        .with_test_map(args.test_map.clone());
+    let timeout = match (args.timeout, result.baseline_secs) {
+        (Some(secs), _) => Duration::from_secs(secs),
+        (None, Some(secs)) => {
+            runner::auto_timeout(Duration::from_secs_f64(secs), args.timeout_factor)
+        }
+        (None, None) => {
+            let baseline = runner::run_baseline(&args.path, &test_command).await?;
+            runner::auto_timeout(baseline.duration, args.timeout_factor)
+        }
+    };
+
+    let mut files: BTreeMap<PathBuf, Vec<MutantTestResult>> = BTreeMap::new();
+    for result in results {
+        files
+            .entry(canonical(&result.mutation.location.file))
+            .or_default()
+            .push(result);
+    }
+    let mut state = WatchState {
+        files,
+        args: args.clone(),
+        test_command,
+        kill_filter: runner::KillFilter::new(&args.killing_tests, &args.non_killing_tests)?,
+        timeout,
+    };
+    let mut hashes = FileHashes::compute(&state.sources()?.into_keys().collect::<Vec<_>>());
+
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    let mut watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+        if let Ok(event) = event {
+            // The receiver only goes away when watching stops
+            tx.send(event.paths).ok();
+        }
+    })?;
+    watcher.watch(&args.path, RecursiveMode::Recursive)?;
+
+    let ctrl_c = tokio::signal::ctrl_c();
+    tokio::pin!(ctrl_c);
+    print_watching(&args.path);
+    loop {
+        let mut paths = tokio::select! {
+            paths = rx.recv() => match paths {
+                Some(paths) => paths,
+                None => return Ok(()),
+            },
+            _ = &mut ctrl_c => return Ok(()),
+        };
+        tokio::time::sleep(DEBOUNCE).await;
+        while let Ok(more) = rx.try_recv() {
+            paths.extend(more);
+        }
+
+        let mut candidates: Vec<PathBuf> = paths
+            .iter()
+            .filter(|path| is_watched(path))
+            .map(|path| canonical(path))
+            .collect();
+        candidates.sort();
+        candidates.dedup();
+        let changed = hashes.update(&candidates);
+        if changed.is_empty() {
+            continue;
+        }
+        if state.retest(&changed).await? {
+            print_watching(&args.path);
+        }
+    }
+}
+
+fn print_watching(path: &Path) {
+    println!(
+        "\n{} Watching {} for changes (Ctrl+C to stop)",
+        "ℹ".cyan(),
+        path.display()
+    );
+}
+
+impl WatchState {
+    /// The files `--glob` and `--exclude` select now, keyed by canonical
+    /// path. Values are the paths as a full run discovers them, which
+    /// mutation ids are derived from
+    fn sources(&self) -> Result<HashMap<PathBuf, PathBuf>> {
+        let args = &self.args;
+        let files = parser::discover_dart_files(
+            &args.path,
+            &args.glob,
+            &args.exclude,
+            generated_suffixes(args),
+        )?;
+        Ok(files
+            .into_iter()
+            .map(|file| (canonical(&file), file))
+            .collect())
+    }
+
+    /// Re-test the mutants affected by `changed` files and print what moved.
+    /// Returns whether anything was printed
+    async fn retest(&mut self, changed: &[PathBuf]) -> Result<bool> {
+        let sources = self.sources()?;
+        let (changed_sources, changed_other): (Vec<&PathBuf>, Vec<&PathBuf>) =
+            changed.iter().partition(|file| sources.contains_key(*file));
+
+        let mut to_test: Vec<Mutation> = Vec::new();
+        let mut before: BTreeMap<PathBuf, Option<f64>> = BTreeMap::new();
+        for file in &changed_sources {
+            before.insert((*file).clone(), self.score(file));
+            to_test.extend(self.mutations_in(&sources[*file])?);
+        }
+        // Deleted sources, or files that no longer match the globs
+        let (removed, tests): (Vec<&PathBuf>, Vec<&PathBuf>) = changed_other
+            .into_iter()
+            .partition(|file| self.files.contains_key(*file));
+        let removed_any = !removed.is_empty();
+        for file in removed {
+            println!("{} {} removed", "−".dimmed(), self.display(file));
+            self.files.remove(file);
+        }
+
+        // A changed test file may now kill any survivor
+        if tests.iter().any(|file| file.exists()) {
+            for (file, results) in &self.files {
+                if before.contains_key(file) {
+                    continue;
+                }
+                let survivors: Vec<Mutation> = results
+                    .iter()
+                    .filter(|r| r.status == MutantStatus::Survived)
+                    .map(|r| r.mutation.clone())
+                    .collect();
+                if !survivors.is_empty() {
+                    before.insert(file.clone(), self.score(file));
+                    to_test.extend(survivors);
+                }
+            }
+        }
+        if to_test.is_empty() {
+            return Ok(removed_any);
+        }
+
+        let overall_before = self.overall().mutation_score;
+        let progress: Arc<dyn ProgressSink> = Arc::new(TerminalProgress::default());
+        progress.on_phase_change(Phase::Testing {
+            mutants: to_test.len(),
+        });
+        let results = runner::run_mutation_tests(
+            &self.args.path,
+            &to_test,
+            &self.test_command,
+            runner::RunLimits {
+                parallel_jobs: self.args.parallel,
+                timeout: self.timeout,
+                max_duration: None,
+                sandbox: self.args.sandbox,
+                concurrency_per_file: self.args.concurrency_per_file,
+                reuse_vm: self.args.reuse_vm,
+            },
+            runner::Retries {
+                rerun_killed: self.args.rerun_killed,
+                confirm_survivors: self.args.confirm_survivors,
+            },
+            &self.kill_filter,
+            progress.clone(),
+        )
+        .await?;
+        progress.on_phase_change(Phase::Done);
+        if self.args.incremental {
+            self.record_in_cache(&results)?;
+        }
+
+        for file in &changed_sources {
+            self.files.insert((*file).clone(), Vec::new());
+        }
+        for result in results {
+            let file = self
+                .files
+                .entry(canonical(&result.mutation.location.file))
+                .or_default();
+            match file
+                .iter_mut()
+                .find(|r| r.mutation.id == result.mutation.id)
+            {
+                Some(existing) => *existing = result,
+                None => file.push(result),
+            }
+        }
+
+        for (file, score_before) in &before {
+            self.print_file_delta(file, *score_before);
+        }
+        let overall = self.overall();
+        println!(
+            "{} Mutation score: {:.1}% ({:+.1})",
+            "→".cyan(),
+            overall.mutation_score,
+            overall.mutation_score - overall_before
+        );
+        Ok(true)
+    }
+
+    /// Save `results` to the `--incremental` cache so the next full run
+    /// reuses them
+    fn record_in_cache(&self, results: &[MutantTestResult]) -> Result<()> {
+        let mut files: Vec<PathBuf> = results
+            .iter()
+            .map(|r| r.mutation.location.file.clone())
+            .collect();
+        files.sort();
+        files.dedup();
+        let cache_path = self.args.path.join(&self.args.cache_file);
+        let mut cache = ResultCache::load(&cache_path);
+        cache.record(
+            results,
+            &FileHashes::compute(&files).with_tests(&self.args.path),
+        );
+        cache.save(&cache_path)
+    }
+
+    /// Mutants for a source file, honouring the parse, `--operators` and
+    /// `--max-mutations-per-file` options of the first run
+    fn mutations_in(&self, file: &Path) -> Result<Vec<Mutation>> {
+        let mut mutations = parser::parse_and_find_mutations(file, &parse_options(&self.args))?;
+        if let Some(operators) = &self.args.operators {
+            let categories = operator_categories(operators);
+            mutations.retain(|m| categories.contains(&m.operator.category()));
+        }
+        if let Some(max) = self.args.max_mutations_per_file {
+            mutations = mutation::spread_mutations(mutations, max);
+        }
+        Ok(mutations)
+    }
+
+    fn score(&self, file: &Path) -> Option<f64> {
+        self.files
+            .get(file)
+            .filter(|results| !results.is_empty())
+            .map(|results| {
+                MutationResult::from_results(results, self.args.timeout_policy).mutation_score
+            })
+    }
+
+    fn overall(&self) -> MutationResult {
+        let all: Vec<MutantTestResult> = self.files.values().flatten().cloned().collect();
+        MutationResult::from_results(&all, self.args.timeout_policy)
+    }
+
+    /// Path as reports show it: relative to the project with
+    /// `--relative-paths`
+    fn display(&self, file: &Path) -> String {
+        let root = canonical(&self.args.path);
+        let shown = if self.args.relative_paths {
+            file.strip_prefix(&root).unwrap_or(file)
+        } else {
+            file
+        };
+        shown.display().to_string()
+    }
+
+    /// One line with the file's score change, then its surviving mutants
+    fn print_file_delta(&self, file: &Path, before: Option<f64>) {
+        let Some(results) = self.files.get(file) else {
+            return;
+        };
+        let summary = MutationResult::from_results(results, self.args.timeout_policy);
+        let before = before.map_or_else(|| "new".to_owned(), |score| format!("{score:.1}%"));
+        println!(
+            "{} {}: {} → {:.1}% ({}/{} killed)",
+            "↻".cyan(),
+            self.display(file),
+            before,
+            summary.mutation_score,
+            summary.killed + summary.timeout,
+            summary.total
+        );
+        for survivor in results
+            .iter()
+            .filter(|r| r.status == MutantStatus::Survived)
+        {
+            let m = &survivor.mutation;
+            println!(
+                "    {} line {}: {} → {}",
+                "✗".red(),
+                m.location.start_line,
+                m.original,
+                m.mutated
+            );
+        }
+    }
+}
+
+/// `.dart` files outside hidden directories such as `.dart_tool`, which the
+/// test runner writes to on every run
+fn is_watched(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext == "dart")
+        && !path.components().any(|component| {
+            matches!(component, Component::Normal(name) if name.to_string_lossy().starts_with('.'))
+        })
+}
+
+/// Canonical form of `path`, so watcher events, discovered files and
+/// mutation locations compare equal. A deleted file keeps its canonical
+/// directory
+fn canonical(path: &Path) -> PathBuf {
+    path.canonicalize()
+        .or_else(|_| match (path.parent(), path.file_name()) {
+            (Some(parent), Some(name)) => parent.canonicalize().map(|parent| parent.join(name)),
+            _ => Ok(path.to_path_buf()),
+        })
+        .unwrap_or_else(|_| path.to_path_buf())
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_only_dart_files_outside_hidden_dirs_are_watched() {
+        assert!(is_watched(Path::new("/app/lib/calc.dart")));
+        assert!(is_watched(Path::new("./test/calc_test.dart")));
+        assert!(!is_watched(Path::new("/app/.dart_tool/build/gen.dart")));
+        assert!(!is_watched(Path::new("/app/lib/calc.dart.swp")));
+        assert!(!is_watched(Path::new("/app/pubspec.yaml")));
+    }
+
+    #[test]
+    fn test_deleted_file_keeps_canonical_directory() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("gone.dart");
+        std::fs::write(&file, "").unwrap();
+        let existing = canonical(&file);
+        std::fs::remove_file(&file).unwrap();
+
+        assert_eq!(canonical(&file), existing);
+    }
+}
@@ -0,0 +1,25 @@
+/// Receipt formatting, built with cascades
+class Receipt {
+  final List<String> lines = [];
+
+  /// Format a receipt for [items] with a header and a total line
+  String format(Map<String, int> items) {
+    final buffer = StringBuffer()
+      ..writeln('RECEIPT')
+      ..writeAll(items.keys, ', ')
+      ..writeln()
+      ..write('Total: ${items.values.fold(0, (a, b) => a + b)}');
+    return buffer.toString();
+  }
+
+  /// Record a line and return this receipt
+  Receipt add(String line) {
+    return this
+      ..lines.add(line)
+      ..lines.sort();
+  }
+
+  /// [extra] with [lines] added and sorted, or null without [extra]
+  static List<String>? merge(List<String>? extra, List<String> lines) =>
+      extra?..addAll(lines)..sort();
+}
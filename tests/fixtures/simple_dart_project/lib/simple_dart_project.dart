@@ -4,3 +4,4 @@ library simple_dart_project;
 export 'calculator.dart';
 export 'string_utils.dart';
 export 'null_safe.dart';
+export 'receipt.dart';
@@ -46,4 +46,16 @@ class StringUtils {
   bool validateInput(String s, int minLength, int maxLength) {
     return s.isNotEmpty && s.length >= minLength && s.length <= maxLength;
   }
+
+  /// Check if a string is all digits
+  bool isDigits(String s) {
+    return RegExp(r'^\d+$').hasMatch(s);
+  }
+
+  /// Get usage text
+  String usage() {
+    return '''
+Usage: greet <name>
+''';
+  }
 }
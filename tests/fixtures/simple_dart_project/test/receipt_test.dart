@@ -0,0 +1,25 @@
+import 'package:test/test.dart';
+import 'package:simple_dart_project/receipt.dart';
+
+void main() {
+  group('Receipt', () {
+    test('format lists the items and the total', () {
+      expect(
+        Receipt().format({'tea': 3, 'cake': 4}),
+        'RECEIPT\ntea, cake\nTotal: 7',
+      );
+    });
+
+    test('add keeps the lines sorted', () {
+      final receipt = Receipt()
+        ..add('b')
+        ..add('a');
+      expect(receipt.lines, ['a', 'b']);
+    });
+
+    test('merge adds and sorts, or keeps null', () {
+      expect(Receipt.merge(['b'], ['a']), ['a', 'b']);
+      expect(Receipt.merge(null, ['a']), isNull);
+    });
+  });
+}
@@ -94,5 +94,21 @@ void main() {
         expect(utils.validateInput('hello world!', 3, 10), isFalse);
       });
     });
+
+    group('isDigits', () {
+      test('returns true for digits', () {
+        expect(utils.isDigits('123'), isTrue);
+      });
+
+      test('returns false for letters', () {
+        expect(utils.isDigits('12a'), isFalse);
+      });
+    });
+
+    group('usage', () {
+      test('describes the command', () {
+        expect(utils.usage(), startsWith('Usage: greet'));
+      });
+    });
   });
 }
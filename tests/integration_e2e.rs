@@ -190,6 +190,321 @@ mod cli_arguments {
         );
     }
 
+    #[test]
+    fn accepts_timeout_policy_argument() {
+        if !binary_exists() {
+            println!("Skipping: binary not built");
+            return;
+        }
+
+        for policy in ["killed", "ignored", "error"] {
+            let output = Command::new(binary_path())
+                .args([
+                    "--timeout-policy",
+                    policy,
+                    "--dry-run",
+                    "--path",
+                    "/nonexistent",
+                ])
+                .output()
+                .expect("Failed to execute command");
+
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            assert!(
+                !stderr.contains("error: unexpected argument")
+                    && !stderr.contains("error: invalid value"),
+                "--timeout-policy {} should be a valid argument",
+                policy
+            );
+        }
+    }
+
+    #[test]
+    fn accepts_fail_on_argument() {
+        if !binary_exists() {
+            println!("Skipping: binary not built");
+            return;
+        }
+
+        for fail_on in ["score", "survived,error", "timeout"] {
+            let output = Command::new(binary_path())
+                .args(["--fail-on", fail_on, "--dry-run", "--path", "/nonexistent"])
+                .output()
+                .expect("Failed to execute command");
+
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            assert!(
+                !stderr.contains("error: unexpected argument")
+                    && !stderr.contains("error: invalid value"),
+                "--fail-on {} should be a valid argument",
+                fail_on
+            );
+        }
+
+        let output = Command::new(binary_path())
+            .args(["--fail-on", "warnings", "--dry-run"])
+            .output()
+            .expect("Failed to execute command");
+        assert!(String::from_utf8_lossy(&output.stderr).contains("error: invalid value"));
+    }
+
+    #[test]
+    fn accepts_output_format_argument() {
+        if !binary_exists() {
+            println!("Skipping: binary not built");
+            return;
+        }
+
+        for format in ["html", "stryker-html"] {
+            let output = Command::new(binary_path())
+                .args([
+                    "--output-format",
+                    format,
+                    "--dry-run",
+                    "--path",
+                    "/nonexistent",
+                ])
+                .output()
+                .expect("Failed to execute command");
+
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            assert!(
+                !stderr.contains("error: unexpected argument")
+                    && !stderr.contains("error: invalid value"),
+                "--output-format {} should be a valid argument",
+                format
+            );
+        }
+    }
+
+    #[test]
+    fn accepts_diff_report_argument() {
+        if !binary_exists() {
+            println!("Skipping: binary not built");
+            return;
+        }
+
+        let output = Command::new(binary_path())
+            .args([
+                "--diff-report",
+                "baseline.json",
+                "--dry-run",
+                "--path",
+                "/nonexistent",
+            ])
+            .output()
+            .expect("Failed to execute command");
+
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(
+            !stderr.contains("error: unexpected argument"),
+            "--diff-report should be a valid argument"
+        );
+    }
+
+    #[test]
+    fn accepts_badge_output_argument() {
+        if !binary_exists() {
+            println!("Skipping: binary not built");
+            return;
+        }
+
+        let output = Command::new(binary_path())
+            .args([
+                "--badge-output",
+                "badge.json",
+                "--dry-run",
+                "--path",
+                "/nonexistent",
+            ])
+            .output()
+            .expect("Failed to execute command");
+
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(
+            !stderr.contains("error: unexpected argument"),
+            "--badge-output should be a valid argument"
+        );
+    }
+
+    #[test]
+    fn accepts_relative_paths_argument() {
+        if !binary_exists() {
+            println!("Skipping: binary not built");
+            return;
+        }
+
+        for value in ["true", "false"] {
+            let output = Command::new(binary_path())
+                .args([
+                    &format!("--relative-paths={}", value),
+                    "--dry-run",
+                    "--path",
+                    "/nonexistent",
+                ])
+                .output()
+                .expect("Failed to execute command");
+
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            assert!(
+                !stderr.contains("error: unexpected argument")
+                    && !stderr.contains("error: invalid value"),
+                "--relative-paths={} should be a valid argument",
+                value
+            );
+        }
+    }
+
+    #[test]
+    fn accepts_killing_tests_arguments() {
+        if !binary_exists() {
+            println!("Skipping: binary not built");
+            return;
+        }
+
+        let output = Command::new(binary_path())
+            .args([
+                "--killing-tests",
+                "Calculator*",
+                "--non-killing-tests",
+                "*flaky*",
+                "--dry-run",
+                "--path",
+                "/nonexistent",
+            ])
+            .output()
+            .expect("Failed to execute command");
+
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(
+            !stderr.contains("error: unexpected argument"),
+            "--killing-tests and --non-killing-tests should be valid arguments"
+        );
+    }
+
+    #[test]
+    fn accepts_warmup_argument() {
+        if !binary_exists() {
+            println!("Skipping: binary not built");
+            return;
+        }
+
+        let output = Command::new(binary_path())
+            .args(["--warmup", "--dry-run", "--path", "/nonexistent"])
+            .output()
+            .expect("Failed to execute command");
+
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(
+            !stderr.contains("error: unexpected argument"),
+            "--warmup should be a valid argument"
+        );
+    }
+
+    #[test]
+    fn accepts_skip_asserts_argument() {
+        if !binary_exists() {
+            println!("Skipping: binary not built");
+            return;
+        }
+
+        let output = Command::new(binary_path())
+            .args(["--skip-asserts", "--dry-run", "--path", "/nonexistent"])
+            .output()
+            .expect("Failed to execute command");
+
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(
+            !stderr.contains("error: unexpected argument"),
+            "--skip-asserts should be a valid argument"
+        );
+    }
+
+    #[test]
+    fn accepts_report_source_arguments() {
+        if !binary_exists() {
+            println!("Skipping: binary not built");
+            return;
+        }
+
+        let output = Command::new(binary_path())
+            .args([
+                "--report-include-source",
+                "--report-source-max-lines",
+                "500",
+                "--dry-run",
+                "--path",
+                "/nonexistent",
+            ])
+            .output()
+            .expect("Failed to execute command");
+
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(
+            !stderr.contains("error: unexpected argument")
+                && !stderr.contains("error: invalid value"),
+            "--report-include-source and --report-source-max-lines should be valid arguments"
+        );
+    }
+
+    #[test]
+    fn accepts_test_env_and_dart_define_arguments() {
+        if !binary_exists() {
+            println!("Skipping: binary not built");
+            return;
+        }
+
+        let run = |args: &[&str]| {
+            let output = Command::new(binary_path())
+                .args(args)
+                .args(["--dry-run", "--path", "/nonexistent"])
+                .output()
+                .expect("Failed to execute command");
+            String::from_utf8_lossy(&output.stderr).to_string()
+        };
+
+        let stderr = run(&[
+            "--test-env",
+            "API_URL=http://localhost",
+            "--dart-define",
+            "FLAVOR=dev",
+            "--dart-define",
+            "DEBUG=true",
+        ]);
+        assert!(
+            !stderr.contains("error: unexpected argument")
+                && !stderr.contains("error: invalid value"),
+            "--test-env and --dart-define should be valid arguments: {}",
+            stderr
+        );
+
+        let stderr = run(&["--test-env", "NO_EQUALS"]);
+        assert!(
+            stderr.contains("error: invalid value"),
+            "--test-env without = should be rejected: {}",
+            stderr
+        );
+    }
+
+    #[test]
+    fn accepts_no_html_with_open_argument() {
+        if !binary_exists() {
+            println!("Skipping: binary not built");
+            return;
+        }
+
+        let output = Command::new(binary_path())
+            .args(["--no-html", "--open", "--dry-run", "--path", "/nonexistent"])
+            .output()
+            .expect("Failed to execute command");
+
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(
+            !stderr.contains("error: unexpected argument"),
+            "--no-html and --open should be valid arguments"
+        );
+    }
+
     #[test]
     fn accepts_output_directory_argument() {
         if !binary_exists() {
@@ -258,8 +573,8 @@ mod file_discovery_e2e {
             .count();
 
         assert_eq!(
-            dart_file_count, 4,
-            "Should find exactly 4 Dart files in fixtures/lib"
+            dart_file_count, 5,
+            "Should find exactly 5 Dart files in fixtures/lib"
         );
     }
 
@@ -271,6 +586,7 @@ mod file_discovery_e2e {
             lib.join("calculator.dart"),
             lib.join("string_utils.dart"),
             lib.join("null_safe.dart"),
+            lib.join("receipt.dart"),
         ];
 
         let mut parser = tree_sitter::Parser::new();
@@ -411,7 +727,11 @@ mod full_pipeline_e2e {
             return;
         }
 
+        let reports = tempfile::tempdir().expect("Failed to create temp dir");
+
         let output = Command::new(binary_path())
+            .arg("--output")
+            .arg(reports.path())
             .args(["--path", fixtures_path().to_str().unwrap(), "--dry-run"])
             .output()
             .expect("Failed to execute command");
@@ -430,71 +750,748 @@ mod full_pipeline_e2e {
     }
 
     #[test]
-    fn full_run_on_fixtures_produces_report() {
-        if !binary_exists() || !dart_available() {
-            println!("Skipping: binary not built or Dart not available");
+    fn exit_codes_distinguish_failures() {
+        if !binary_exists() {
+            println!("Skipping: binary not built");
             return;
         }
 
-        // IMPORTANT: Copy fixtures to temp dir to prevent corruption during mutation testing
-        let Some(temp_fixtures) = copy_fixtures_to_temp() else {
-            println!("Skipping: failed to copy fixtures");
-            return;
+        let reports = tempfile::tempdir().expect("Failed to create temp dir");
+
+        let empty = tempfile::tempdir().expect("Failed to create temp dir");
+        let exit_code = |args: &[&str]| {
+            Command::new(binary_path())
+                .arg("--output")
+                .arg(reports.path())
+                .args(args)
+                .output()
+                .expect("Failed to execute command")
+                .status
+                .code()
         };
-        let project_path = temp_fixtures.path().join("simple_dart_project");
 
-        // Run dart pub get on the COPY
-        let pub_get = Command::new("dart")
-            .args(["pub", "get"])
-            .current_dir(&project_path)
-            .output();
+        assert_eq!(
+            exit_code(&["--path", empty.path().to_str().unwrap()]),
+            Some(2),
+            "No Dart files"
+        );
+        assert_eq!(exit_code(&["--no-such-flag"]), Some(4), "Invalid arguments");
+        assert_eq!(exit_code(&["--version"]), Some(0));
+    }
 
-        if pub_get.is_err() || !pub_get.unwrap().status.success() {
-            println!("Skipping: dart pub get failed");
+    #[test]
+    fn aborts_when_baseline_tests_fail() {
+        if !binary_exists() {
+            println!("Skipping: binary not built");
             return;
         }
 
-        let temp_output = std::env::temp_dir().join("dart_mutant_e2e_test");
-        std::fs::create_dir_all(&temp_output).ok();
+        let reports = tempfile::tempdir().expect("Failed to create temp dir");
 
+        // `false` stands in for a test suite that is already failing
         let output = Command::new(binary_path())
+            .arg("--output")
+            .arg(reports.path())
             .args([
                 "--path",
-                project_path.to_str().unwrap(),
-                "--output",
-                temp_output.to_str().unwrap(),
-                "--html",
-                "--json",
+                fixtures_path().to_str().unwrap(),
+                "--test-command",
+                "false",
                 "--sample",
-                "5", // Only test 5 mutations for speed
-                "--timeout",
-                "10",
+                "1",
             ])
             .output()
             .expect("Failed to execute command");
 
-        let stdout = String::from_utf8_lossy(&output.stdout);
         let stderr = String::from_utf8_lossy(&output.stderr);
+        assert_eq!(output.status.code(), Some(3), "run should fail: {}", stderr);
+        assert!(
+            stderr.contains("requires a green baseline")
+                && stderr.contains("--skip-baseline-check"),
+            "error should explain the failing baseline: {}",
+            stderr
+        );
+    }
 
-        println!("stdout: {}", stdout);
-        println!("stderr: {}", stderr);
-
-        // Check for report files
-        let html_report = temp_output.join("mutation-report.html");
-        let json_report = temp_output.join("mutation-report.json");
-
-        if html_report.exists() {
-            let html = std::fs::read_to_string(&html_report).unwrap();
-            assert!(
-                html.contains("<!DOCTYPE html>"),
-                "HTML report should be valid HTML"
-            );
+    #[test]
+    fn kill_filters_need_test_names() {
+        if !binary_exists() {
+            println!("Skipping: binary not built");
+            return;
         }
 
-        if json_report.exists() {
-            let json = std::fs::read_to_string(&json_report).unwrap();
-            assert!(
-                json.contains("mutationScore") || json.contains("mutation_score"),
+        let reports = tempfile::tempdir().expect("Failed to create temp dir");
+
+        // `true` prints no JSON events, so no failure could be attributed
+        let output = Command::new(binary_path())
+            .arg("--output")
+            .arg(reports.path())
+            .args([
+                "--path",
+                fixtures_path().to_str().unwrap(),
+                "--test-command",
+                "true",
+                "--killing-tests",
+                "Calculator*",
+                "--sample",
+                "1",
+            ])
+            .output()
+            .expect("Failed to execute command");
+
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(!output.status.success(), "run should fail: {}", stderr);
+        assert!(
+            stderr.contains("need the failing test names") && stderr.contains("`true`"),
+            "error should explain why the filter can't work: {}",
+            stderr
+        );
+    }
+
+    #[test]
+    fn history_file_tracks_the_score_and_fails_on_regression() {
+        if !binary_exists() {
+            println!("Skipping: binary not built");
+            return;
+        }
+
+        let dir = tempfile::tempdir().expect("Failed to create temp dir");
+        let history = dir.path().join("history.jsonl");
+        // The full run last is skipped: only runs of the same scope compare
+        std::fs::write(
+            &history,
+            [
+                r#"{"timestamp":"2024-05-01T12:00:00Z","total":10,"killed":9,"survived":1,"score":90.0,"scope":"--max-mutations 3"}"#,
+                r#"{"timestamp":"2024-05-02T12:00:00Z","total":10,"killed":4,"survived":6,"score":40.0}"#,
+                "",
+            ]
+            .join("\n"),
+        )
+        .expect("Failed to write history");
+
+        // `true` passes on every mutant, so the score drops to 0%
+        let output = Command::new(binary_path())
+            .args([
+                "--path",
+                fixtures_path().to_str().unwrap(),
+                "--test-command",
+                "true",
+                "--max-mutations",
+                "3",
+                "--output",
+                dir.path().to_str().unwrap(),
+                "--history-file",
+                history.to_str().unwrap(),
+                "--trend",
+                "--fail-on",
+                "regression",
+            ])
+            .output()
+            .expect("Failed to execute command");
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert_eq!(output.status.code(), Some(1), "Output: {}", stdout);
+        assert!(
+            stdout.contains(
+                "Mutation score down 90.0 points since the last run (90.0% on 2024-05-01)"
+            ) && stdout.contains("Mutation score fell from 90.0% to 0.0%"),
+            "Should report the regression. Output: {}",
+            stdout
+        );
+
+        let records: Vec<serde_json::Value> = std::fs::read_to_string(&history)
+            .expect("Failed to read history")
+            .lines()
+            .map(|line| serde_json::from_str(line).expect("Invalid history line"))
+            .collect();
+        assert_eq!(records.len(), 3);
+        assert_eq!(records[2]["total"], 3);
+        assert_eq!(records[2]["score"], 0.0);
+        assert_eq!(records[2]["scope"], "--max-mutations 3");
+    }
+
+    #[test]
+    fn reports_requested_operator_categories_with_zero_mutants() {
+        if !binary_exists() {
+            println!("Skipping: binary not built");
+            return;
+        }
+
+        let reports = tempfile::tempdir().expect("Failed to create temp dir");
+
+        // The fixtures contain no bitwise operators
+        let output = Command::new(binary_path())
+            .arg("--output")
+            .arg(reports.path())
+            .args([
+                "--path",
+                fixtures_path().to_str().unwrap(),
+                "--dry-run",
+                "--operators",
+                "bitwise",
+            ])
+            .output()
+            .expect("Failed to execute command");
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+
+        assert!(
+            stdout.contains("bitwise: 0 mutants"),
+            "Should report that the bitwise category produced no mutants. Output: {}",
+            stdout
+        );
+    }
+
+    #[test]
+    fn quiet_prints_only_the_result() {
+        if !binary_exists() {
+            println!("Skipping: binary not built");
+            return;
+        }
+
+        let reports = tempfile::tempdir().expect("Failed to create temp dir");
+
+        let output = Command::new(binary_path())
+            .arg("--output")
+            .arg(reports.path())
+            .args([
+                "--path",
+                fixtures_path().to_str().unwrap(),
+                "--dry-run",
+                "--quiet",
+            ])
+            .output()
+            .expect("Failed to execute command");
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let lines: Vec<&str> = stdout.lines().collect();
+
+        assert_eq!(lines.len(), 1, "Output: {}", stdout);
+        assert!(
+            lines[0].ends_with("mutations would be tested"),
+            "Output: {}",
+            stdout
+        );
+    }
+
+    #[test]
+    fn list_operators_prints_categories_and_exits() {
+        if !binary_exists() {
+            println!("Skipping: binary not built");
+            return;
+        }
+
+        let output = Command::new(binary_path())
+            .args(["--list-operators", "--path", "/nonexistent"])
+            .output()
+            .expect("Failed to execute command");
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+
+        assert!(output.status.success(), "Should exit cleanly");
+        for expected in ["null_safety", "NullAssertionRemoval", "Null: x! → x"] {
+            assert!(
+                stdout.contains(expected),
+                "Should list {}. Output: {}",
+                expected,
+                stdout
+            );
+        }
+    }
+
+    #[test]
+    fn max_mutations_per_file_caps_large_files() {
+        if !binary_exists() {
+            println!("Skipping: binary not built");
+            return;
+        }
+
+        let reports = tempfile::tempdir().expect("Failed to create temp dir");
+
+        let output = Command::new(binary_path())
+            .arg("--output")
+            .arg(reports.path())
+            .args([
+                "--path",
+                fixtures_path().to_str().unwrap(),
+                "--dry-run",
+                "--max-mutations-per-file",
+                "3",
+            ])
+            .output()
+            .expect("Failed to execute command");
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+
+        assert!(
+            stdout.contains("Capped lib/calculator.dart at 3 of")
+                && stdout.contains("(--max-mutations-per-file)"),
+            "Should report the capped file. Output: {}",
+            stdout
+        );
+    }
+
+    #[test]
+    fn string_mutants_keep_each_quoting_style() {
+        if !binary_exists() {
+            println!("Skipping: binary not built");
+            return;
+        }
+
+        let reports = tempfile::tempdir().expect("Failed to create temp dir");
+
+        let output = Command::new(binary_path())
+            .arg("--output")
+            .arg(reports.path())
+            .args([
+                "--path",
+                fixtures_path().to_str().unwrap(),
+                "--dry-run",
+                "--operators",
+                "string",
+            ])
+            .output()
+            .expect("Failed to execute command");
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+
+        // Plain, raw and multi-line triple-quoted literals keep their quoting
+        for mutant in ["'' → 'mutated'", r"r'^\d+$' → r''", "''' → ''''''"] {
+            assert!(
+                stdout.contains(mutant),
+                "Should list the mutant {}. Output: {}",
+                mutant,
+                stdout
+            );
+        }
+    }
+
+    #[test]
+    fn only_survivors_retests_the_survivors_of_a_report() {
+        if !binary_exists() {
+            println!("Skipping: binary not built");
+            return;
+        }
+
+        let reports = tempfile::tempdir().expect("Failed to create temp dir");
+
+        // Stale ids, as after an edit, so survivors are matched by line
+        let mutant = |id: &str, line: usize, status: &str| {
+            serde_json::json!({
+                "id": id,
+                "mutatorName": "Arithmetic: + → -",
+                "replacement": "-",
+                "status": status,
+                "location": {
+                    "start": { "line": line, "column": 14 },
+                    "end": { "line": line, "column": 15 }
+                }
+            })
+        };
+        let report = serde_json::json!({
+            "schemaVersion": "1",
+            "thresholds": { "high": 80, "low": 60 },
+            "files": {
+                "lib/calculator.dart": {
+                    "language": "dart",
+                    "mutants": [
+                        mutant("survived", 5, "Survived"),
+                        mutant("removed", 900, "Survived"),
+                        mutant("killed", 10, "Killed"),
+                    ]
+                }
+            }
+        });
+        let dir = tempfile::tempdir().expect("Failed to create temp dir");
+        let report_path = dir.path().join("report.json");
+        std::fs::write(&report_path, report.to_string()).expect("Failed to write report");
+
+        let output = Command::new(binary_path())
+            .arg("--output")
+            .arg(reports.path())
+            .args([
+                "--path",
+                fixtures_path().to_str().unwrap(),
+                "--dry-run",
+                "--only-survivors",
+                report_path.to_str().unwrap(),
+            ])
+            .output()
+            .expect("Failed to execute command");
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(
+            stdout.contains("1 mutations would be tested")
+                && stdout.contains("[calculator.dart:5] + → -"),
+            "Should only list the surviving mutant. Output: {}",
+            stdout
+        );
+        assert!(
+            stdout.contains("1 surviving mutants no longer match a mutation"),
+            "Should warn about the survivor that is gone. Output: {}",
+            stdout
+        );
+    }
+
+    #[test]
+    fn verify_mutations_reports_mutants_that_dont_compile() {
+        if !binary_exists() {
+            println!("Skipping: binary not built");
+            return;
+        }
+
+        // No dart on PATH: the parser alone checks, and no file is written
+        let dir = tempfile::tempdir().expect("Failed to create temp dir");
+        let output = Command::new(binary_path())
+            .args([
+                "--path",
+                fixtures_path().to_str().unwrap(),
+                "--verify-mutations",
+                "--output",
+                dir.path().to_str().unwrap(),
+            ])
+            .env("PATH", "")
+            .output()
+            .expect("Failed to execute command");
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(output.status.success(), "Output: {stdout}");
+        assert!(
+            stdout.contains("checking mutants with the parser only")
+                && stdout.contains("mutants don't compile"),
+            "Should check the mutants without Dart. Output: {}",
+            stdout
+        );
+        let report = std::fs::read_to_string(dir.path().join("mutation-verification.md"))
+            .expect("Should write the verification report");
+        assert!(report.starts_with("# Mutation Verification Report"));
+    }
+
+    #[test]
+    fn cascade_sections_are_removed_one_at_a_time() {
+        if !binary_exists() {
+            println!("Skipping: binary not built");
+            return;
+        }
+
+        let reports = tempfile::tempdir().expect("Failed to create temp dir");
+
+        let output = Command::new(binary_path())
+            .arg("--output")
+            .arg(reports.path())
+            .args([
+                "--path",
+                fixtures_path().to_str().unwrap(),
+                "--glob",
+                "lib/receipt.dart",
+                "--operators",
+                "control_flow",
+                "--dry-run",
+            ])
+            .output()
+            .expect("Failed to execute command");
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        for removed in [
+            "[receipt.dart:8] ..writeln('RECEIPT') → ",
+            "[receipt.dart:19] ..lines.sort() → ",
+            "[receipt.dart:24] addAll(lines).. → ",
+            "[receipt.dart:24] ..sort() → ",
+        ] {
+            assert!(
+                stdout.contains(removed),
+                "Should drop `{}`. Output: {}",
+                removed,
+                stdout
+            );
+        }
+    }
+
+    #[test]
+    fn operators_filter_limits_mutants_and_warns_on_unknown_names() {
+        if !binary_exists() {
+            println!("Skipping: binary not built");
+            return;
+        }
+
+        let reports = tempfile::tempdir().expect("Failed to create temp dir");
+
+        let run = |operators: &str| {
+            let output = Command::new(binary_path())
+                .arg("--output")
+                .arg(reports.path())
+                .args([
+                    "--path",
+                    fixtures_path().to_str().unwrap(),
+                    "--dry-run",
+                    "--operators",
+                    operators,
+                ])
+                .output()
+                .expect("Failed to execute command");
+            String::from_utf8_lossy(&output.stdout).to_string()
+        };
+        let count = |stdout: &str| -> usize {
+            stdout
+                .lines()
+                .find_map(|line| line.trim().strip_suffix(" mutations would be tested"))
+                .and_then(|n| n.trim().parse().ok())
+                .unwrap_or_default()
+        };
+
+        let all = run("arithmetic,comparison,logical,boolean,unary,string,control_flow");
+        let arithmetic = run("arithmetic,bogus");
+
+        assert!(
+            arithmetic.contains("Unknown operator 'bogus'"),
+            "Should warn about the unknown operator. Output: {}",
+            arithmetic
+        );
+        assert!(
+            count(&arithmetic) > 0 && count(&arithmetic) < count(&all),
+            "Arithmetic alone should produce fewer mutants. Output: {}",
+            arithmetic
+        );
+    }
+
+    #[test]
+    fn smoke_preset_samples_mutations() {
+        if !binary_exists() {
+            println!("Skipping: binary not built");
+            return;
+        }
+
+        let reports = tempfile::tempdir().expect("Failed to create temp dir");
+
+        let output = Command::new(binary_path())
+            .arg("--output")
+            .arg(reports.path())
+            .args([
+                "--path",
+                fixtures_path().to_str().unwrap(),
+                "--dry-run",
+                "--preset",
+                "smoke",
+                // Keep mutants in files no test imports in the count
+                "--no-import-check",
+            ])
+            .output()
+            .expect("Failed to execute command");
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+
+        // The fixtures generate ~100 mutations, so smoke samples its minimum of 25
+        assert!(
+            stdout.contains("25 mutations would be tested"),
+            "Smoke preset should sample a subset of mutations. Output: {}",
+            stdout
+        );
+    }
+
+    #[test]
+    fn explain_prints_mutation_context_and_hint() {
+        if !binary_exists() {
+            println!("Skipping: binary not built");
+            return;
+        }
+
+        // Mutation ids hash file:line:column:original:replacement
+        let calculator = fixtures_path().join("lib").join("calculator.dart");
+        let id = format!(
+            "{:x}",
+            md5::compute(format!("{}:5:14:+:-", calculator.display()))
+        );
+
+        let output = Command::new(binary_path())
+            .args([
+                "--path",
+                fixtures_path().to_str().unwrap(),
+                "--explain",
+                &id,
+            ])
+            .output()
+            .expect("Failed to execute command");
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(
+            output.status.success(),
+            "Explain should succeed. Output: {}",
+            stdout
+        );
+        assert!(
+            stdout.contains("calculator.dart:5"),
+            "Should show location. Output: {}",
+            stdout
+        );
+        assert!(stdout.contains("- ") && stdout.contains("return a + b;"));
+        assert!(stdout.contains("+ ") && stdout.contains("return a - b;"));
+        assert!(
+            stdout.contains("Test hint:"),
+            "Should include a test hint. Output: {}",
+            stdout
+        );
+    }
+
+    #[test]
+    fn missing_file_thresholds_config_fails_fast() {
+        if !binary_exists() {
+            println!("Skipping: binary not built");
+            return;
+        }
+
+        let reports = tempfile::tempdir().expect("Failed to create temp dir");
+
+        let output = Command::new(binary_path())
+            .arg("--output")
+            .arg(reports.path())
+            .args([
+                "--path",
+                fixtures_path().to_str().unwrap(),
+                "--dry-run",
+                "--file-thresholds",
+                "/nonexistent/thresholds.json",
+            ])
+            .output()
+            .expect("Failed to execute command");
+
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(!output.status.success());
+        assert!(
+            stderr.contains("Failed to read file thresholds"),
+            "Should report the unreadable config. Stderr: {}",
+            stderr
+        );
+    }
+
+    #[test]
+    fn explain_fails_for_unknown_id() {
+        if !binary_exists() {
+            println!("Skipping: binary not built");
+            return;
+        }
+
+        let output = Command::new(binary_path())
+            .args([
+                "--path",
+                fixtures_path().to_str().unwrap(),
+                "--explain",
+                "not-a-mutation-id",
+            ])
+            .output()
+            .expect("Failed to execute command");
+
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(!output.status.success());
+        assert!(
+            stderr.contains("No mutation found"),
+            "Should report the missing id. Stderr: {}",
+            stderr
+        );
+    }
+
+    #[test]
+    fn max_mutations_caps_tested_mutations() {
+        if !binary_exists() {
+            println!("Skipping: binary not built");
+            return;
+        }
+
+        let reports = tempfile::tempdir().expect("Failed to create temp dir");
+
+        let output = Command::new(binary_path())
+            .arg("--output")
+            .arg(reports.path())
+            .args([
+                "--path",
+                fixtures_path().to_str().unwrap(),
+                "--dry-run",
+                "--max-mutations",
+                "7",
+                // Keep mutants in files no test imports in the count
+                "--no-import-check",
+            ])
+            .output()
+            .expect("Failed to execute command");
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(
+            stdout.contains("Capped at 7 of"),
+            "Should note that the cap was applied. Output: {}",
+            stdout
+        );
+        assert!(
+            stdout.contains("7 mutations would be tested"),
+            "Should test exactly the capped number of mutations. Output: {}",
+            stdout
+        );
+    }
+
+    #[test]
+    fn full_run_on_fixtures_produces_report() {
+        if !binary_exists() || !dart_available() {
+            println!("Skipping: binary not built or Dart not available");
+            return;
+        }
+
+        // IMPORTANT: Copy fixtures to temp dir to prevent corruption during mutation testing
+        let Some(temp_fixtures) = copy_fixtures_to_temp() else {
+            println!("Skipping: failed to copy fixtures");
+            return;
+        };
+        let project_path = temp_fixtures.path().join("simple_dart_project");
+
+        // Run dart pub get on the COPY
+        let pub_get = Command::new("dart")
+            .args(["pub", "get"])
+            .current_dir(&project_path)
+            .output();
+
+        if pub_get.is_err() || !pub_get.unwrap().status.success() {
+            println!("Skipping: dart pub get failed");
+            return;
+        }
+
+        let temp_output = std::env::temp_dir().join("dart_mutant_e2e_test");
+        std::fs::create_dir_all(&temp_output).ok();
+
+        let output = Command::new(binary_path())
+            .args([
+                "--path",
+                project_path.to_str().unwrap(),
+                "--output",
+                temp_output.to_str().unwrap(),
+                "--html",
+                "--json",
+                "--sample",
+                "5", // Only test 5 mutations for speed
+                "--timeout",
+                "10",
+            ])
+            .output()
+            .expect("Failed to execute command");
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let stderr = String::from_utf8_lossy(&output.stderr);
+
+        println!("stdout: {}", stdout);
+        println!("stderr: {}", stderr);
+
+        // Check for report files
+        let html_report = temp_output.join("mutation-report.html");
+        let json_report = temp_output.join("mutation-report.json");
+
+        if html_report.exists() {
+            let html = std::fs::read_to_string(&html_report).unwrap();
+            assert!(
+                html.contains("<!DOCTYPE html>"),
+                "HTML report should be valid HTML"
+            );
+        }
+
+        if json_report.exists() {
+            let json = std::fs::read_to_string(&json_report).unwrap();
+            assert!(
+                json.contains("mutationScore") || json.contains("mutation_score"),
                 "JSON report should contain mutation score"
             );
         }
@@ -598,6 +1595,150 @@ mod full_pipeline_e2e {
     }
 }
 
+mod library_api {
+    use super::*;
+
+    #[tokio::test]
+    async fn run_returns_summary_without_the_binary() {
+        // Dry run: no Dart needed, but the rest of the pipeline is real
+        let output = tempfile::tempdir().expect("Failed to create temp dir");
+        let path = fixtures_path();
+        let args = dart_mutant::Args::try_parse_resolved_from([
+            "dart_mutant",
+            "--path",
+            path.to_str().unwrap(),
+            "--output",
+            output.path().to_str().unwrap(),
+            "--dry-run",
+            "--sample",
+            "5",
+            "--seed",
+            "7",
+        ])
+        .expect("Arguments should parse");
+
+        let result = dart_mutant::run(&args).await.expect("Run should succeed");
+
+        assert_eq!(result.sample_seed, Some(7));
+        assert!(output.path().join("mutation-report.html").exists());
+    }
+
+    #[derive(Default)]
+    struct Phases(std::sync::Mutex<Vec<dart_mutant::Phase>>);
+
+    impl dart_mutant::ProgressSink for Phases {
+        fn on_phase_change(&self, phase: dart_mutant::Phase) {
+            self.0.lock().unwrap().push(phase);
+        }
+    }
+
+    #[tokio::test]
+    async fn run_with_progress_reports_phases() {
+        let output = tempfile::tempdir().expect("Failed to create temp dir");
+        let path = fixtures_path();
+        let args = dart_mutant::Args::try_parse_resolved_from([
+            "dart_mutant",
+            "--path",
+            path.to_str().unwrap(),
+            "--output",
+            output.path().to_str().unwrap(),
+            "--dry-run",
+        ])
+        .expect("Arguments should parse");
+        let phases = std::sync::Arc::new(Phases::default());
+
+        dart_mutant::run_with_progress(&args, phases.clone())
+            .await
+            .expect("Run should succeed");
+
+        let phases = phases.0.lock().unwrap();
+        assert!(matches!(
+            phases.as_slice(),
+            [
+                dart_mutant::Phase::Discovering,
+                dart_mutant::Phase::Parsing { files },
+                dart_mutant::Phase::Reporting,
+                dart_mutant::Phase::Done,
+            ] if *files > 0
+        ));
+    }
+}
+
+mod reuse_vm_e2e {
+    use super::*;
+    use std::time::Instant;
+
+    /// Run a fixed sample of mutants and time the run
+    async fn timed_run(
+        project: &std::path::Path,
+        extra: &[&str],
+    ) -> (dart_mutant::MutationResult, std::time::Duration) {
+        let output = tempfile::tempdir().expect("Failed to create temp dir");
+        let mut argv = vec![
+            "dart_mutant",
+            "--path",
+            project.to_str().unwrap(),
+            "--output",
+            output.path().to_str().unwrap(),
+            "--sample",
+            "10",
+            "--seed",
+            "42",
+            "--parallel",
+            "2",
+            "--timeout",
+            "60",
+        ];
+        argv.extend_from_slice(extra);
+        let args =
+            dart_mutant::Args::try_parse_resolved_from(argv).expect("Arguments should parse");
+
+        let start = Instant::now();
+        let result = dart_mutant::run(&args).await.expect("Run should succeed");
+        (result, start.elapsed())
+    }
+
+    /// Benchmark against the cold-start path: same mutants, same outcome,
+    /// and the time of each run printed for comparison
+    #[tokio::test]
+    async fn reuse_vm_matches_cold_start_results() {
+        if !dart_available() {
+            println!("Skipping: Dart not available");
+            return;
+        }
+
+        // IMPORTANT: Copy fixtures to temp dir to prevent corruption during mutation testing
+        let Some(temp_fixtures) = copy_fixtures_to_temp() else {
+            println!("Skipping: failed to copy fixtures");
+            return;
+        };
+        let project_path = temp_fixtures.path().join("simple_dart_project");
+        let pub_get = Command::new("dart")
+            .args(["pub", "get"])
+            .current_dir(&project_path)
+            .output();
+        if !pub_get.is_ok_and(|out| out.status.success()) {
+            println!("Skipping: dart pub get failed");
+            return;
+        }
+
+        let (cold, cold_time) = timed_run(&project_path, &[]).await;
+        let (warm, warm_time) = timed_run(&project_path, &["--reuse-vm"]).await;
+
+        println!(
+            "cold start: {:.1}s, --reuse-vm: {:.1}s ({:.1}x)",
+            cold_time.as_secs_f64(),
+            warm_time.as_secs_f64(),
+            cold_time.as_secs_f64() / warm_time.as_secs_f64()
+        );
+        assert_eq!(
+            (cold.killed + cold.timeout, cold.survived),
+            (warm.killed + warm.timeout, warm.survived),
+            "--reuse-vm should kill the same mutants"
+        );
+    }
+}
+
 mod threshold_behavior {
     #[test]
     fn threshold_zero_always_passes() {
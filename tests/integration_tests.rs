@@ -302,12 +302,15 @@ mod e2e_mutation_testing {
     #[serial]
     fn test_dry_run_mode() {
         let project_dir = sample_project_dir();
+        let reports = tempfile::tempdir().expect("Failed to create temp dir");
 
         let output = Command::new("cargo")
             .arg("run")
             .arg("--")
             .arg("--path")
             .arg(&project_dir)
+            .arg("--output")
+            .arg(reports.path())
             .arg("--dry-run")
             .current_dir(env!("CARGO_MANIFEST_DIR"))
             .output()
@@ -729,6 +732,61 @@ mod compile_error_tests {
         );
     }
 
+    /// Mutants that fail to compile are reported as errors, not kills
+    #[test]
+    fn test_compile_errors_are_not_counted_as_killed() {
+        if !dart_available() {
+            eprintln!("Skipping test: Dart not available");
+            return;
+        }
+
+        let project_dir = compile_error_project_dir();
+        let temp_dir = copy_to_temp(&project_dir, "compile_status");
+        ensure_dart_deps(&temp_dir);
+        let output_dir = temp_dir.join("mutation-reports");
+
+        let output = Command::new("cargo")
+            .arg("run")
+            .arg("--")
+            .arg("--path")
+            .arg(&temp_dir)
+            .arg("--output")
+            .arg(&output_dir)
+            .arg("--json")
+            .arg("--timeout")
+            .arg("30")
+            .current_dir(env!("CARGO_MANIFEST_DIR"))
+            .output()
+            .expect("Failed to run dart_mutant");
+
+        let report = fs::read_to_string(output_dir.join("mutation-report.json"));
+        drop(fs::remove_dir_all(&temp_dir));
+        let report: serde_json::Value = serde_json::from_str(&report.unwrap_or_else(|e| {
+            panic!(
+                "JSON report should be written ({e}).\nStdout: {}\nStderr: {}",
+                String::from_utf8_lossy(&output.stdout),
+                String::from_utf8_lossy(&output.stderr)
+            )
+        }))
+        .expect("Report should be valid JSON");
+
+        let mutants = report["files"]["lib/type_sensitive.dart"]["mutants"]
+            .as_array()
+            .expect("type_sensitive.dart should have mutants");
+        let status_at = |line: u64, replacement: &str| {
+            mutants
+                .iter()
+                .find(|m| m["location"]["start"]["line"] == line && m["replacement"] == replacement)
+                .map(|m| m["status"].as_str().unwrap_or_default().to_string())
+        };
+
+        // String - String and List - List don't compile
+        assert_eq!(status_at(10, "-").as_deref(), Some("CompileError"));
+        assert_eq!(status_at(15, "-").as_deref(), Some("CompileError"));
+        // int - int compiles, so the tests decide
+        assert_ne!(status_at(20, "-").as_deref(), Some("CompileError"));
+    }
+
     /// Verify that the tests for the compile_error_project pass with original code
     #[test]
     fn test_compile_error_project_tests_pass() {